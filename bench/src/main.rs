@@ -23,6 +23,15 @@ fn do_self(n: usize) {
     })
 }
 
+fn do_self_affine(n: usize) {
+    let interner = symbol_table::SymbolTable::new().with_thread_affinity();
+    (0..n).into_par_iter().for_each(|_| {
+        for word in TEXT.split_whitespace() {
+            interner.intern(word);
+        }
+    })
+}
+
 #[cfg(feature = "global")]
 fn do_self_global(n: usize) {
     (0..n).into_par_iter().for_each(|_| {
@@ -64,6 +73,7 @@ fn do_lasso(n: usize) {
 #[allow(clippy::type_complexity)]
 static BENCHES: &[(fn(usize), &str)] = &[
     (do_self, "self"),
+    (do_self_affine, "self-affine"),
     #[cfg(feature = "global")]
     (do_self_global, "self-global"),
     (do_string_interner, "string-interner"),
@@ -0,0 +1,9 @@
+/*!
+The C API of the [`symbol_table`] crate, built as a `staticlib` and a
+`cdylib` for linking from C.
+
+The functions are in [`symbol_table::ffi`], and declared for C in
+`include/symbol_table.h` at the root of that crate.
+*/
+
+pub use symbol_table::ffi::*;
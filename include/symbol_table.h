@@ -0,0 +1,43 @@
+/*
+ * C API for the global symbol table of the `symbol_table` crate.
+ *
+ * Build the `symbol_table_ffi` crate in `ffi/`, and link against its
+ * `staticlib` or `cdylib`. Symbols are shared with `GlobalSymbol` on the Rust side;
+ * `0` is never a valid symbol. Interned strings are never freed. None of
+ * these functions panic: they return 0 or NULL instead.
+ */
+
+#ifndef SYMBOL_TABLE_H
+#define SYMBOL_TABLE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Intern a NUL-terminated string. Returns 0 if it is NULL or not UTF-8. */
+uint32_t symbol_intern(const char *string);
+
+/* Intern `len` bytes, which may contain NULs. Returns 0 if not UTF-8. */
+uint32_t symbol_intern_len(const char *string, size_t len);
+
+/*
+ * Resolve a symbol to its NUL-terminated string. Returns NULL for 0 and
+ * other unknown symbols, or if the string contains a NUL.
+ */
+const char *symbol_resolve(uint32_t sym);
+
+/*
+ * Resolve a symbol to its string, which is not NUL-terminated, and store its
+ * length in `len` (if not NULL). Returns NULL for 0 and other unknown
+ * symbols.
+ */
+const char *symbol_resolve_len(uint32_t sym, size_t *len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SYMBOL_TABLE_H */
@@ -0,0 +1,171 @@
+/*!
+Macros for the [`symbol_table`](https://docs.rs/symbol_table) crate.
+
+The symbols these macros create are `GlobalSymbol`s, so the `global` feature
+of `symbol_table` has to be enabled.
+*/
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, Lit, LitStr, Meta};
+
+/// Create a `GlobalSymbol` from a string literal, hashing it at compile time.
+///
+/// Like `symbol_table::static_symbol!`, this only interns the string once
+/// per call site. The string's hash is computed while compiling, so even
+/// that first use skips the hashing, with
+/// `GlobalSymbol::new_prehashed`.
+///
+/// The hash depends on the pointer width and endianness, so when
+/// cross-compiling for a target that differs from the compiler's host in
+/// those, the string is hashed at runtime instead. The same goes for builds
+/// whose hasher doesn't match the compiler's anyway, which is checked once
+/// per program by hashing a probe string.
+///
+/// ```
+/// use symbol_table::GlobalSymbol;
+/// use symbol_table_macros::symbol;
+///
+/// let hello = symbol!("hello");
+/// assert_eq!(hello, GlobalSymbol::from("hello"));
+/// ```
+#[proc_macro]
+pub fn symbol(input: TokenStream) -> TokenStream {
+    let lit = parse_macro_input!(input as LitStr);
+    let table = symbol_table::SymbolTable::new();
+    let hash = table.hash_str(&lit.value());
+    let probe_hash = table.hash_str(symbol_table::__HASH_PROBE);
+    let width = if cfg!(target_pointer_width = "64") {
+        "64"
+    } else {
+        "32"
+    };
+    let endian = if cfg!(target_endian = "little") {
+        "little"
+    } else {
+        "big"
+    };
+
+    quote! {{
+        static SYMBOL: ::std::sync::OnceLock<::symbol_table::GlobalSymbol> =
+            ::std::sync::OnceLock::new();
+        *SYMBOL.get_or_init(|| {
+            // the hash was computed on the host, so it's only valid for
+            // targets that hash the same way
+            if cfg!(all(target_pointer_width = #width, target_endian = #endian))
+                && ::symbol_table::GlobalSymbol::__hashes_match(#probe_hash)
+            {
+                ::symbol_table::GlobalSymbol::new_prehashed(#hash, #lit)
+            } else {
+                ::symbol_table::GlobalSymbol::new(#lit)
+            }
+        })
+    }}
+    .into()
+}
+
+/// Derive conversions between the variants of a fieldless enum and
+/// `GlobalSymbol`s.
+///
+/// This generates `to_symbol(&self) -> GlobalSymbol` and
+/// `from_symbol(GlobalSymbol) -> Option<Self>`. The symbols of all variants
+/// are interned together on first use, into a `symbol_table::KeywordTable`.
+///
+/// A variant's string is its name in snake case, like
+/// `GlobalSymbol::to_snake_case` gives, so `HTTPServer` is `http_server`,
+/// unless it's given with `#[symbol = "..."]`.
+///
+/// ```
+/// use symbol_table::GlobalSymbol;
+/// use symbol_table_macros::Symbols;
+///
+/// #[derive(Debug, PartialEq, Symbols)]
+/// enum Keyword {
+///     Let,
+///     If,
+///     #[symbol = "fn"]
+///     Function,
+///     SelfType,
+/// }
+///
+/// assert_eq!(Keyword::Let.to_symbol(), GlobalSymbol::from("let"));
+/// assert_eq!(Keyword::from_symbol("fn".into()), Some(Keyword::Function));
+/// assert_eq!(Keyword::from_symbol("self_type".into()), Some(Keyword::SelfType));
+/// assert_eq!(Keyword::from_symbol("loop".into()), None);
+/// ```
+#[proc_macro_derive(Symbols, attributes(symbol))]
+pub fn derive_symbols(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match symbols(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn symbols(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "Symbols can only be derived for enums",
+        ));
+    };
+
+    let mut names = Vec::new();
+    let mut strings = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            let msg = "Symbols can only be derived for variants without fields";
+            return Err(syn::Error::new_spanned(variant, msg));
+        }
+        let mut string = symbol_table::__snake_case(&variant.ident.to_string());
+        for attr in variant.attrs.iter().filter(|a| a.path().is_ident("symbol")) {
+            string = symbol_attr(&attr.meta)?;
+        }
+        names.push(&variant.ident);
+        strings.push(string);
+    }
+
+    let ident = &input.ident;
+    let indices: Vec<usize> = (0..names.len()).collect();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            fn __symbol_table_keywords() -> &'static ::symbol_table::KeywordTable {
+                static TABLE: ::std::sync::OnceLock<::symbol_table::KeywordTable> =
+                    ::std::sync::OnceLock::new();
+                TABLE.get_or_init(|| ::symbol_table::KeywordTable::new(&[#(#strings),*]))
+            }
+
+            /// The symbol of this variant.
+            pub fn to_symbol(&self) -> ::symbol_table::GlobalSymbol {
+                let index = match self {
+                    #(Self::#names => #indices,)*
+                };
+                Self::__symbol_table_keywords().symbol(index)
+            }
+
+            /// The variant of a symbol, if it's one of them.
+            pub fn from_symbol(sym: ::symbol_table::GlobalSymbol) -> ::std::option::Option<Self> {
+                match Self::__symbol_table_keywords().index_of(sym)? {
+                    #(#indices => ::std::option::Option::Some(Self::#names),)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+        }
+    })
+}
+
+/// The string of a `#[symbol = "..."]` attribute.
+fn symbol_attr(meta: &Meta) -> syn::Result<String> {
+    if let Meta::NameValue(nv) = meta {
+        if let Expr::Lit(lit) = &nv.value {
+            if let Lit::Str(s) = &lit.lit {
+                return Ok(s.value());
+            }
+        }
+    }
+    Err(syn::Error::new_spanned(
+        meta,
+        r#"expected `#[symbol = "..."]`"#,
+    ))
+}
@@ -0,0 +1,176 @@
+use crate::*;
+
+use hashbrown::HashTable;
+
+/// Another spelling of a string in the table, stored in the shard that the
+/// spelling hashes to.
+#[derive(Clone)]
+pub(crate) struct Alias {
+    hash: u64,
+    string: Box<[u8]>,
+    // the raw canonical symbol, which can be in any shard
+    sym: usize,
+    // whether this is the string itself, interned into another shard
+    pin: bool,
+}
+
+/// The aliases of a shard, by hash.
+pub(crate) type Aliases = HashTable<Alias>;
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Make interning `alias` give the `canonical` symbol from now on.
+    ///
+    /// This maps other spellings, like deprecated keywords, to one symbol,
+    /// without a separate map that every call site has to consult.
+    /// Interning and [looking up](Self::lookup) `alias`, after the
+    /// [transform](Self::with_transform), give `canonical`, and the alias
+    /// is never stored as a string of its own, so resolving always gives the
+    /// canonical string. Aliasing a spelling again replaces its canonical
+    /// symbol.
+    ///
+    /// Aliases are part of the table, so they're cloned with it, and kept by
+    /// [`compact`](Self::compact) and [`rollback_to`](Self::rollback_to) as
+    /// long as their canonical string is. They aren't saved, like the
+    /// transform.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let color = table.intern("color");
+    /// table.alias("colour", color);
+    ///
+    /// assert_eq!(table.intern("colour"), color);
+    /// assert_eq!(table.lookup("colour"), Some(color));
+    /// assert_eq!(table.resolve(table.intern("colour")), "color");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `canonical` isn't a [symbol of this
+    /// table](Self::contains_symbol), if `alias` is already interned as a
+    /// string of its own, or if it's an [inline](Self::with_inline_strings)
+    /// string, which has its own symbol without looking it up.
+    pub fn alias(&self, alias: &str, canonical: K) {
+        assert!(
+            self.contains_symbol(canonical),
+            "Can't alias a symbol that isn't in the table"
+        );
+        let alias = match &self.transform {
+            Some(f) => f(alias),
+            None => Cow::Borrowed(alias),
+        };
+        let bytes = alias.as_bytes();
+        assert!(
+            self.inline_symbol(bytes).is_none(),
+            "Can't alias the inline string {alias:?}"
+        );
+
+        let hash = hash_one(&self.build_hasher, bytes);
+        let shard_i = shard_of_hash(hash, self.n_shards);
+        let mut shard = self.lock_shard(shard_i);
+        if let Some(idx) = shard.find(hash, bytes) {
+            // don't poison the lock by panicking while holding it
+            drop(shard);
+            assert!(
+                Self::make_symbol(shard_i, idx) == canonical,
+                "Can't alias {alias:?}, which is already interned"
+            );
+            return;
+        }
+        let sym = canonical.into_usize();
+        let found = shard.aliases.find_mut(hash, |a| *a.string == *bytes);
+        match found {
+            Some(found) if found.pin => {
+                let pinned = found.sym;
+                drop(shard);
+                assert!(
+                    pinned == sym,
+                    "Can't alias {alias:?}, which is already interned"
+                );
+            }
+            Some(found) => found.sym = sym,
+            None => {
+                let string = bytes.into();
+                let alias = Alias {
+                    hash,
+                    string,
+                    sym,
+                    pin: false,
+                };
+                shard.aliases.insert_unique(hash, alias, |a| a.hash);
+            }
+        }
+    }
+
+    /// Make a string that was [interned into another
+    /// shard](Self::intern_in_shard) than its locked one give its symbol,
+    /// so it's still only interned once.
+    pub(crate) fn pin(shard: &mut Shard<B>, hash: u64, string: &[u8], sym: K) {
+        let alias = Alias {
+            hash,
+            string: string.into(),
+            sym: sym.into_usize(),
+            pin: true,
+        };
+        shard.aliases.insert_unique(hash, alias, |a| a.hash);
+    }
+
+    /// The canonical symbol of a string that is an alias in its locked
+    /// shard.
+    #[inline]
+    pub(crate) fn aliased(shard: &Shard<B>, hash: u64, string: &[u8]) -> Option<K> {
+        if shard.aliases.is_empty() {
+            return None;
+        }
+        let alias = shard.aliases.find(hash, |a| *a.string == *string)?;
+        Some(K::try_from_usize(alias.sym).unwrap())
+    }
+
+    /// Check that the aliases of a locked shard hash to it, and aren't
+    /// strings of their own, giving their canonical symbols, and the strings
+    /// those have to resolve to, if they're pinned.
+    #[cfg(feature = "check")]
+    pub(crate) fn check_aliases(
+        &self,
+        shard_i: usize,
+        shard: &Shard<B>,
+    ) -> Result<Vec<(K, Option<String>)>, String> {
+        shard
+            .aliases
+            .iter()
+            .map(|alias| {
+                let string = String::from_utf8_lossy(&alias.string);
+                let hash = hash_one(&self.build_hasher, &alias.string);
+                if hash != alias.hash || shard_of_hash(hash, self.n_shards) != shard_i {
+                    return Err(format!(
+                        "alias {string:?} of shard {shard_i} has the wrong hash"
+                    ));
+                }
+                if shard.find(hash, &alias.string).is_some() {
+                    return Err(format!(
+                        "alias {string:?} of shard {shard_i} is also interned"
+                    ));
+                }
+                let sym = K::try_from_usize(alias.sym).ok_or_else(|| {
+                    format!("alias {string:?} of shard {shard_i} has a bad symbol")
+                })?;
+                Ok((sym, alias.pin.then(|| string.into_owned())))
+            })
+            .collect()
+    }
+
+    /// Translate the canonical symbols of all aliases, dropping the ones
+    /// that `f` gives `None` for.
+    pub(crate) fn remap_aliases(&mut self, mut f: impl FnMut(K) -> Option<K>) {
+        for shard in &mut self.shards {
+            shard.get_mut().unwrap().aliases.retain(|alias| {
+                match f(K::try_from_usize(alias.sym).unwrap()) {
+                    Some(sym) => {
+                        alias.sym = sym.into_usize();
+                        true
+                    }
+                    None => false,
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::*;
+
+use backend::ArcBackend;
+
+impl<const N: usize, S: BuildHasher, K: SymbolKey> SymbolTable<N, S, ArcBackend, K> {
+    /// Intern a shared string into the [`SymbolTable`].
+    ///
+    /// This is like [`intern`](Self::intern), but if the string isn't
+    /// already in the table, the table keeps `string` itself instead of
+    /// copying it, whether or not it's shared with others.
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use symbol_table::{backend::ArcBackend, DeterministicHashBuilder, SymbolTable};
+    ///
+    /// let table: SymbolTable<16, DeterministicHashBuilder, ArcBackend> = SymbolTable::default();
+    /// let name: Arc<str> = Arc::from("plugin");
+    /// let sym = table.intern_arc(name.clone());
+    /// assert!(Arc::ptr_eq(&table.resolve_arc(sym), &name));
+    /// ```
+    pub fn intern_arc(&self, string: Arc<str>) -> K {
+        let transformed = match &self.transform {
+            Some(f) => f(&string),
+            None => return self.intern_internable(Arc::<[u8]>::from(string)),
+        };
+        unwrap_interned(match transformed {
+            // unchanged, so it can still be kept by the table
+            Cow::Borrowed(s) if *s == *string => {
+                self.try_intern_internable(Arc::<[u8]>::from(string))
+            }
+            Cow::Borrowed(s) => self.try_intern_internable(s),
+            Cow::Owned(s) => self.try_intern_internable(s),
+        })
+    }
+
+    /// Resolve a symbol to the interned string, shared.
+    ///
+    /// Unlike [`resolve`](Self::resolve), the string isn't borrowed from the
+    /// table, so it can outlive it. The string of an
+    /// [inline](Self::with_inline_strings) symbol isn't stored, so that's
+    /// copied into a new [`Arc`].
+    ///
+    /// ```
+    /// use symbol_table::{backend::ArcBackend, DeterministicHashBuilder, SymbolTable};
+    ///
+    /// let table: SymbolTable<16, DeterministicHashBuilder, ArcBackend> = SymbolTable::default();
+    /// let foo = table.intern("foo");
+    /// let resolved = table.resolve_arc(foo);
+    /// drop(table);
+    /// assert_eq!(&*resolved, "foo");
+    /// ```
+    pub fn resolve_arc(&self, sym: K) -> Arc<str> {
+        let Some((shard_i, idx)) = Self::split_symbol(sym) else {
+            return self.resolve(sym).into();
+        };
+        let bytes = self.lock_shard(shard_i).strs.get_arc(idx);
+        // SAFETY: only `str`s are interned through the public API of a
+        // `SymbolTable`, see `resolve`, and a `str` is laid out like its bytes
+        unsafe { Arc::from_raw(Arc::into_raw(bytes) as *const str) }
+    }
+}
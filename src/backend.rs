@@ -0,0 +1,509 @@
+/*!
+Storage backends for the strings interned into a [`SymbolTable`](crate::SymbolTable).
+
+Each shard of a table owns one [`Backend`], which is picked with the table's
+`B` type parameter. The backends in this module make different memory/speed
+trade-offs:
+
+- [`StringBackend`] (the default) keeps every string in its own `Box<str>`.
+- [`ArcBackend`] keeps every string in its own `Arc<str>`, so the table can
+  hand out strings that outlive it, see [`SymbolTable::resolve_arc`](crate::SymbolTable::resolve_arc).
+- [`BufferBackend`] appends string bytes into large contiguous chunks,
+  which saves an allocation per string and improves cache locality.
+- [`AllocBackend`] works like [`BufferBackend`], but allocates its chunks
+  from an `allocator_api2` allocator, e.g. an arena.
+  This requires the `allocator-api2` feature on the crate.
+
+```
+use symbol_table::{backend::BufferBackend, DeterministicHashBuilder, SymbolTable};
+
+let table: SymbolTable<16, DeterministicHashBuilder, BufferBackend> = SymbolTable::default();
+let foo = table.intern("foo");
+assert_eq!(table.resolve(foo), "foo");
+```
+*/
+
+use std::{ptr::NonNull, sync::Arc};
+
+// the index of the next string pushed onto a backend holding `len` of them
+pub(crate) fn next_idx(len: usize) -> u32 {
+    u32::try_from(len).expect("Can't store more than 2^32 strings in a shard")
+}
+
+/// Detach a string from the borrow of the backend it came from, e.g. to
+/// return it after unlocking the shard.
+///
+/// # Safety
+///
+/// `bytes` must come from [`Backend::get`], and the backend must outlive
+/// `'a` without being truncated below the string. The backend guarantees
+/// the rest: the bytes never move, they're never written to again, and
+/// they're only ever borrowed shared, so the pointer keeps its provenance.
+pub(crate) unsafe fn detach<'a>(bytes: &[u8]) -> &'a [u8] {
+    // SAFETY: see above
+    unsafe { &*(bytes as *const [u8]) }
+}
+
+/// Storage for the strings interned into one shard of a symbol table.
+///
+/// A backend is an append-only list of strings; the index returned by
+/// [`push`](Backend::push) is what gets encoded into a [`Symbol`](crate::Symbol).
+/// Backends store raw bytes, so the same backends work for
+/// [`ByteSymbolTable`](crate::ByteSymbolTable)s, which intern bytes that may
+/// not be UTF-8.
+///
+/// # Safety
+///
+/// The symbol table hands out `&str`s that outlive the lock guarding the
+/// backend, so implementors must guarantee that a string returned by
+/// [`get`](Backend::get) keeps its address and contents until the backend is
+/// dropped, no matter how many strings are pushed afterwards.
+///
+/// [`get`](Backend::get) must also return exactly the bytes that were pushed
+/// at that index, since a [`SymbolTable`](crate::SymbolTable) relies on them
+/// still being valid UTF-8.
+pub unsafe trait Backend {
+    /// An empty backend.
+    ///
+    /// This is a constant so that tables can still be created in `const`
+    /// contexts, e.g. in a `static`.
+    const EMPTY: Self;
+
+    /// The number of strings in this backend.
+    fn len(&self) -> usize;
+
+    /// Whether this backend holds no strings.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append a string, returning its index.
+    ///
+    /// Indices must be handed out consecutively, starting from 0, so a
+    /// backend can hold at most 2^32 strings; pushing more must panic rather
+    /// than wrap around.
+    fn push(&mut self, string: &[u8]) -> u32;
+
+    /// Append an owned string, returning its index.
+    ///
+    /// Backends that store strings in their own allocations can override
+    /// this to reuse the allocation of `string`. By default, this just calls
+    /// [`push`](Backend::push).
+    fn push_owned(&mut self, string: Vec<u8>) -> u32 {
+        self.push(&string)
+    }
+
+    /// Append a shared string, returning its index.
+    ///
+    /// Like [`push_owned`](Backend::push_owned), backends can override this
+    /// to keep `string` instead of copying it. By default, this just calls
+    /// [`push`](Backend::push).
+    fn push_arc(&mut self, string: Arc<[u8]>) -> u32 {
+        self.push(&string)
+    }
+
+    /// Append a string that lives for the rest of the program, returning
+    /// its index.
+    ///
+    /// Backends can override this to point to `string` instead of copying
+    /// it, like [`StringBackend`] does. By default, this just calls
+    /// [`push`](Backend::push).
+    fn push_static(&mut self, string: &'static [u8]) -> u32 {
+        self.push(string)
+    }
+
+    /// Get the string at the given index.
+    ///
+    /// The index is always one previously returned by [`push`](Backend::push).
+    fn get(&self, idx: u32) -> &[u8];
+
+    /// Get the string at the given index, without checking that the index
+    /// is in bounds.
+    ///
+    /// By default, this just calls [`get`](Backend::get).
+    ///
+    /// # Safety
+    ///
+    /// The index must be one previously returned by [`push`](Backend::push),
+    /// and not [truncated](Backend::truncate) away since.
+    unsafe fn get_unchecked(&self, idx: u32) -> &[u8] {
+        self.get(idx)
+    }
+
+    /// Drop every string from index `len` on.
+    ///
+    /// This is only called with exclusive access to the table, so the
+    /// dropped strings can't be borrowed anymore. The strings before `len`
+    /// must stay where they are.
+    fn truncate(&mut self, len: usize);
+}
+
+/// The default [`Backend`], which stores each string in its own allocation.
+///
+/// The strings are allocated as `Box<[u8]>`s, but only kept as raw
+/// pointers: a `Box` asserts that it's the only way to reach its contents,
+/// which the strings handed out by the table would contradict whenever the
+/// `Box` is moved, e.g. when the list of strings grows.
+///
+/// `'static` strings aren't copied at all, see
+/// [`SymbolTable::intern_static`](crate::SymbolTable::intern_static).
+#[derive(Default)]
+pub struct StringBackend {
+    // every pointer is from `Box::leak`, and freed on drop, except for the
+    // `'static` strings
+    strs: Vec<NonNull<[u8]>>,
+    // the indices of the `'static` strings, in order
+    statics: Vec<u32>,
+}
+
+// SAFETY: the backend owns its strings, like a `Vec<Box<[u8]>>` would
+unsafe impl Send for StringBackend {}
+unsafe impl Sync for StringBackend {}
+
+impl StringBackend {
+    fn push_box(&mut self, string: Box<[u8]>) -> u32 {
+        let idx = next_idx(self.strs.len());
+        self.strs.push(NonNull::from(Box::leak(string)));
+        idx
+    }
+}
+
+impl Drop for StringBackend {
+    fn drop(&mut self) {
+        self.truncate(0);
+    }
+}
+
+unsafe impl Backend for StringBackend {
+    const EMPTY: Self = Self {
+        strs: Vec::new(),
+        statics: Vec::new(),
+    };
+
+    fn len(&self) -> usize {
+        self.strs.len()
+    }
+
+    fn push(&mut self, string: &[u8]) -> u32 {
+        self.push_box(string.into())
+    }
+
+    fn push_owned(&mut self, string: Vec<u8>) -> u32 {
+        self.push_box(string.into_boxed_slice())
+    }
+
+    fn push_static(&mut self, string: &'static [u8]) -> u32 {
+        let idx = next_idx(self.strs.len());
+        self.strs.push(NonNull::from(string));
+        self.statics.push(idx);
+        idx
+    }
+
+    fn get(&self, idx: u32) -> &[u8] {
+        // SAFETY: the string is alive until it's truncated away, which needs
+        // `&mut self`, and it's never written to
+        unsafe { self.strs[idx as usize].as_ref() }
+    }
+
+    unsafe fn get_unchecked(&self, idx: u32) -> &[u8] {
+        // SAFETY: the caller checked the index, and the string is alive
+        // like in `get`
+        unsafe { self.strs.get_unchecked(idx as usize).as_ref() }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        let len = len.min(self.strs.len());
+        let first_static = self.statics.partition_point(|&idx| (idx as usize) < len);
+        let mut statics = self.statics.drain(first_static..).peekable();
+        for (idx, string) in (len..).zip(self.strs.drain(len..)) {
+            if statics.next_if_eq(&(idx as u32)).is_some() {
+                continue;
+            }
+            // SAFETY: the pointer is from `Box::leak`, and it's gone from
+            // `strs` now, so nothing can borrow the string anymore
+            drop(unsafe { Box::from_raw(string.as_ptr()) });
+        }
+    }
+}
+
+/// A [`Backend`] that stores each string in its own `Arc<[u8]>`.
+///
+/// This costs a reference count per string over a [`StringBackend`], but a
+/// string can then be shared beyond the table: see
+/// [`SymbolTable::resolve_arc`](crate::SymbolTable::resolve_arc) and
+/// [`SymbolTable::intern_arc`](crate::SymbolTable::intern_arc).
+#[derive(Default)]
+pub struct ArcBackend {
+    strs: Vec<Arc<[u8]>>,
+}
+
+impl ArcBackend {
+    /// The string at the given index, shared.
+    pub(crate) fn get_arc(&self, idx: u32) -> Arc<[u8]> {
+        self.strs[idx as usize].clone()
+    }
+}
+
+unsafe impl Backend for ArcBackend {
+    const EMPTY: Self = Self { strs: Vec::new() };
+
+    fn len(&self) -> usize {
+        self.strs.len()
+    }
+
+    fn push(&mut self, string: &[u8]) -> u32 {
+        self.push_arc(string.into())
+    }
+
+    fn push_owned(&mut self, string: Vec<u8>) -> u32 {
+        self.push_arc(string.into())
+    }
+
+    fn push_arc(&mut self, string: Arc<[u8]>) -> u32 {
+        let idx = next_idx(self.strs.len());
+        self.strs.push(string);
+        idx
+    }
+
+    fn get(&self, idx: u32) -> &[u8] {
+        &self.strs[idx as usize]
+    }
+
+    unsafe fn get_unchecked(&self, idx: u32) -> &[u8] {
+        // SAFETY: the caller checked the index
+        unsafe { self.strs.get_unchecked(idx as usize) }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.strs.truncate(len);
+    }
+}
+
+/// The capacity of the first chunk allocated by a [`BufferBackend`].
+const MIN_CHUNK_CAPACITY: usize = 4 * 1024;
+
+/// Chunks stop doubling in size once they reach this capacity.
+const MAX_CHUNK_CAPACITY: usize = 1024 * 1024;
+
+/// A [`Backend`] that appends string bytes into large contiguous chunks.
+///
+/// Each interned string is stored as a `(chunk, offset, len)` span into one
+/// of the chunks, instead of as its own heap allocation. Chunks are never
+/// grown past their initial capacity, so the bytes of a string never move
+/// once they have been written.
+#[derive(Default)]
+pub struct BufferBackend(Chunks<Vec<u8>>);
+
+unsafe impl Backend for BufferBackend {
+    const EMPTY: Self = Self(Chunks::EMPTY);
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn push(&mut self, string: &[u8]) -> u32 {
+        self.0.push(string)
+    }
+
+    fn get(&self, idx: u32) -> &[u8] {
+        self.0.get(idx)
+    }
+
+    unsafe fn get_unchecked(&self, idx: u32) -> &[u8] {
+        // SAFETY: the caller checked the index
+        unsafe { self.0.get_unchecked(idx) }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.0.truncate(len)
+    }
+}
+
+/// A [`Backend`] like [`BufferBackend`], that allocates its chunks from `A`.
+///
+/// This requires the `allocator-api2` feature on the crate.
+///
+/// A backend has to be constant to create (see [`Backend::EMPTY`]), so the
+/// allocator is made with [`Default`] whenever a chunk is needed. To intern
+/// into an arena, `A` can be a zero-sized handle to it; if deallocating is a
+/// no-op for the arena, all the strings are released along with it.
+///
+/// ```
+/// use allocator_api2::alloc::Global;
+/// use symbol_table::{backend::AllocBackend, DeterministicHashBuilder, SymbolTable};
+///
+/// let table: SymbolTable<16, DeterministicHashBuilder, AllocBackend<Global>> =
+///     SymbolTable::default();
+/// let foo = table.intern("foo");
+/// assert_eq!(table.resolve(foo), "foo");
+/// ```
+#[cfg(feature = "allocator-api2")]
+#[derive(Default)]
+pub struct AllocBackend<A: allocator_api2::alloc::Allocator>(
+    Chunks<allocator_api2::vec::Vec<u8, A>>,
+);
+
+#[cfg(feature = "allocator-api2")]
+unsafe impl<A: allocator_api2::alloc::Allocator + Default> Backend for AllocBackend<A> {
+    const EMPTY: Self = Self(Chunks::EMPTY);
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn push(&mut self, string: &[u8]) -> u32 {
+        self.0.push(string)
+    }
+
+    fn get(&self, idx: u32) -> &[u8] {
+        self.0.get(idx)
+    }
+
+    unsafe fn get_unchecked(&self, idx: u32) -> &[u8] {
+        // SAFETY: the caller checked the index
+        unsafe { self.0.get_unchecked(idx) }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.0.truncate(len)
+    }
+}
+
+/// A buffer of string bytes, for [`Chunks`].
+///
+/// Extending a chunk within its capacity must not move its bytes, like with
+/// a `Vec`: that's what keeps the strings of the backends in place. It must
+/// also only write past the end, through the chunk's own pointer, rather
+/// than through a `&mut` to all of the chunk's bytes, which would invalidate
+/// the strings borrowed from it. `Vec::extend_from_slice` does that.
+trait Chunk: std::ops::Deref<Target = [u8]> {
+    fn with_capacity(capacity: usize) -> Self;
+    fn capacity(&self) -> usize;
+    fn extend_from_slice(&mut self, bytes: &[u8]);
+    fn truncate(&mut self, len: usize);
+}
+
+impl Chunk for Vec<u8> {
+    fn with_capacity(capacity: usize) -> Self {
+        Vec::with_capacity(capacity)
+    }
+
+    fn capacity(&self) -> usize {
+        Vec::capacity(self)
+    }
+
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        Vec::extend_from_slice(self, bytes)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        Vec::truncate(self, len)
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+impl<A: allocator_api2::alloc::Allocator + Default> Chunk for allocator_api2::vec::Vec<u8, A> {
+    fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, A::default())
+    }
+
+    fn capacity(&self) -> usize {
+        Self::capacity(self)
+    }
+
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        Self::extend_from_slice(self, bytes)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        Self::truncate(self, len)
+    }
+}
+
+/// The strings of a [`BufferBackend`] or an [`AllocBackend`], as spans into
+/// chunks that are never grown.
+struct Chunks<C> {
+    chunks: Vec<C>,
+    spans: Vec<Span>,
+}
+
+#[derive(Clone, Copy)]
+struct Span {
+    chunk: u32,
+    offset: u32,
+    len: u32,
+}
+
+impl<C: Chunk> Default for Chunks<C> {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl<C: Chunk> Chunks<C> {
+    const EMPTY: Self = Self {
+        chunks: Vec::new(),
+        spans: Vec::new(),
+    };
+
+    fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    fn push(&mut self, string: &[u8]) -> u32 {
+        let len: u32 = string.len().try_into().expect("string too long to intern");
+        let idx = next_idx(self.spans.len());
+
+        let fits = self.chunks.last().is_some_and(|chunk| {
+            // never grow a chunk, that would move the strings already in it
+            chunk.capacity() - chunk.len() >= string.len()
+        });
+        if !fits {
+            let prev = self.chunks.last().map_or(0, C::capacity);
+            let capacity = (prev * 2)
+                .clamp(MIN_CHUNK_CAPACITY, MAX_CHUNK_CAPACITY)
+                .max(string.len());
+            self.chunks.push(C::with_capacity(capacity));
+        }
+
+        let chunk_i = self.chunks.len() - 1;
+        let chunk = &mut self.chunks[chunk_i];
+        let offset = chunk.len() as u32;
+        chunk.extend_from_slice(string);
+        self.spans.push(Span {
+            chunk: chunk_i as u32,
+            offset,
+            len,
+        });
+        idx
+    }
+
+    fn get(&self, idx: u32) -> &[u8] {
+        let Span { chunk, offset, len } = self.spans[idx as usize];
+        let (start, end) = (offset as usize, (offset + len) as usize);
+        &self.chunks[chunk as usize][start..end]
+    }
+
+    // SAFETY: the index must be in bounds, and then so is its span
+    unsafe fn get_unchecked(&self, idx: u32) -> &[u8] {
+        unsafe {
+            let Span { chunk, offset, len } = *self.spans.get_unchecked(idx as usize);
+            let (start, end) = (offset as usize, (offset + len) as usize);
+            self.chunks
+                .get_unchecked(chunk as usize)
+                .get_unchecked(start..end)
+        }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.spans.truncate(len);
+        match self.spans.last() {
+            Some(&Span { chunk, offset, len }) => {
+                self.chunks.truncate(chunk as usize + 1);
+                // this doesn't shrink the chunk, so it still never moves
+                self.chunks[chunk as usize].truncate((offset + len) as usize);
+            }
+            None => self.chunks.clear(),
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::*;
+
+/// A symbol table that interns borrowed `&'a str`s without copying them.
+///
+/// This is useful when all the strings already live in one big buffer, e.g.
+/// a corpus loaded from a file: instead of copying every token into the
+/// table like a [`SymbolTable`] does, this table only remembers where each
+/// string is. Resolving then gives back the original `&'a str`.
+///
+/// ```
+/// use symbol_table::BorrowedSymbolTable;
+///
+/// let text = String::from("the quick brown fox jumps over the lazy dog");
+/// let table = BorrowedSymbolTable::new();
+/// let syms: Vec<_> = text.split(' ').map(|word| table.intern(word)).collect();
+/// assert_eq!(syms[0], syms[6]);
+///
+/// // the resolved string points into `text`
+/// let the: &str = table.resolve(syms[6]);
+/// assert_eq!(the.as_ptr(), text.as_ptr());
+/// ```
+///
+/// The table is invariant in `'a`, so it can't be used as a table of
+/// shorter-lived strings, which would then be resolved as `&'a str`s:
+///
+/// ```compile_fail
+/// use symbol_table::BorrowedSymbolTable;
+///
+/// fn shrink<'a>(table: &'a BorrowedSymbolTable<'static>) -> &'a BorrowedSymbolTable<'a> {
+///     table
+/// }
+///
+/// let table: BorrowedSymbolTable<'static> = BorrowedSymbolTable::new();
+/// let sym = {
+///     let temp = String::from("temporary");
+///     shrink(&table).intern(&temp)
+/// };
+/// let dangling: &'static str = table.resolve(sym);
+/// ```
+pub struct BorrowedSymbolTable<
+    'a,
+    const N: usize = DEFAULT_N_SHARDS,
+    S = DeterministicHashBuilder,
+    K = Symbol,
+> {
+    table: SymbolTable<N, S, BorrowedBackend, K>,
+    // invariant: interning takes `&'a str`s, and resolving gives them back
+    _strs: PhantomData<fn(&'a str) -> &'a str>,
+}
+
+/// A backend that only stores pointers to the interned strings.
+///
+/// This is only sound because this type is private: the only table that uses
+/// it is the one inside a [`BorrowedSymbolTable`], which only ever interns
+/// strings that outlive it.
+struct BorrowedBackend {
+    strs: Vec<*const [u8]>,
+}
+
+// SAFETY: the pointers are only used as `&[u8]`s, which are `Send` and `Sync`.
+unsafe impl Send for BorrowedBackend {}
+unsafe impl Sync for BorrowedBackend {}
+
+// SAFETY: the strings are owned outside the table, and outlive it.
+unsafe impl Backend for BorrowedBackend {
+    const EMPTY: Self = Self { strs: Vec::new() };
+
+    fn len(&self) -> usize {
+        self.strs.len()
+    }
+
+    fn push(&mut self, string: &[u8]) -> u32 {
+        let idx = crate::backend::next_idx(self.strs.len());
+        self.strs.push(string);
+        idx
+    }
+
+    fn get(&self, idx: u32) -> &[u8] {
+        // SAFETY: see the comment on the type
+        unsafe { &*self.strs[idx as usize] }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.strs.truncate(len);
+    }
+}
+
+impl BorrowedSymbolTable<'_, DEFAULT_N_SHARDS, DeterministicHashBuilder> {
+    /// Creates a new [`BorrowedSymbolTable`] with the default generic arguments.
+    pub const fn new() -> Self {
+        Self::with_hasher(DeterministicHashBuilder)
+    }
+}
+
+impl<'a, const N: usize, S: BuildHasher, K: SymbolKey> BorrowedSymbolTable<'a, N, S, K> {
+    /// Creates a new [`BorrowedSymbolTable`] with a custom hasher.
+    pub const fn with_hasher(build_hasher: S) -> Self {
+        Self {
+            table: SymbolTable::with_hasher(build_hasher),
+            _strs: PhantomData,
+        }
+    }
+
+    /// Encode very short strings directly in their symbols.
+    ///
+    /// See [`SymbolTable::with_inline_strings`].
+    pub fn with_inline_strings(mut self) -> Self {
+        self.table = self.table.with_inline_strings();
+        self
+    }
+
+    /// Intern a string into the [`BorrowedSymbolTable`], without copying it.
+    pub fn intern(&self, string: &'a str) -> K {
+        self.table.intern_internable(string)
+    }
+
+    /// Intern a part of the string of a symbol, without copying it.
+    ///
+    /// If the part is new, it points into the string of `parent`, so a path
+    /// and its components are only stored once:
+    ///
+    /// ```
+    /// use symbol_table::BorrowedSymbolTable;
+    ///
+    /// let paths = String::from("/usr/bin");
+    /// let table = BorrowedSymbolTable::new();
+    /// let path = table.intern(&paths);
+    /// let usr = table.intern_slice(path, 1..4);
+    /// assert_eq!(table.resolve(usr).as_ptr(), paths[1..].as_ptr());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds of the string, or doesn't start
+    /// and end on `char` boundaries, like indexing a `str`.
+    pub fn intern_slice(&self, parent: K, range: impl SliceIndex<str, Output = str>) -> K {
+        self.intern(&self.resolve(parent)[range])
+    }
+
+    /// Resolve a symbol to the interned string.
+    ///
+    /// Unlike [`SymbolTable::resolve`], the string isn't borrowed from the
+    /// table, so it can outlive it.
+    pub fn resolve(&self, sym: K) -> &'a str {
+        let str = self.table.resolve(sym);
+        // SAFETY: the strings in `self.table` were all interned from `&'a str`s
+        // (or are inline, and therefore `'static`).
+        unsafe { &*(str as *const str) }
+    }
+}
+
+impl<const N: usize, S: Default + BuildHasher, K: SymbolKey> Default
+    for BorrowedSymbolTable<'_, N, S, K>
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
@@ -0,0 +1,92 @@
+use crate::*;
+
+use ::borsh::{
+    io::{self, Read, Write},
+    BorshDeserialize, BorshSerialize,
+};
+
+/// Serializes whether strings are [inline](SymbolTable::with_inline_strings),
+/// how many shards are [in use](SymbolTable::with_shards), and the strings
+/// of every shard in order, like the `serde` impl, so the symbols can be
+/// restored exactly.
+///
+/// This requires the `borsh` feature on the crate. The table's transform and
+/// limits aren't serialized.
+///
+/// ```
+/// use symbol_table::{Symbol, SymbolTable};
+///
+/// let table = SymbolTable::new();
+/// let foo = table.intern("foo");
+/// let bytes = borsh::to_vec(&(&table, foo)).unwrap();
+///
+/// let (table, foo): (SymbolTable, Symbol) = borsh::from_slice(&bytes).unwrap();
+/// assert_eq!(table.resolve(foo), "foo");
+/// ```
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> BorshSerialize
+    for SymbolTable<N, S, B, K>
+{
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.inline.serialize(writer)?;
+        (self.n_shards as u32).serialize(writer)?;
+        (N as u32).serialize(writer)?;
+        for shard_i in 0..N {
+            let len = self.shards[shard_i].lock().unwrap().strs.len() as u32;
+            len.serialize(writer)?;
+            for idx in 0..len {
+                let sym = Self::make_symbol(shard_i, idx);
+                // SAFETY: only `str`s are interned through the public API of
+                // a `SymbolTable`, see `resolve`
+                let string = unsafe { std::str::from_utf8_unchecked(self.resolve_bytes(sym)) };
+                string.serialize(writer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Restores a table serialized by the [`BorshSerialize`] impl.
+///
+/// This fails if the table had a different number of shards. Strings that
+/// were [interned into another shard](SymbolTable::intern_in_shard) than
+/// they hash to stay there, as do all the strings of a table with a
+/// different hasher.
+impl<const N: usize, S, B, K> BorshDeserialize for SymbolTable<N, S, B, K>
+where
+    S: Default + BuildHasher,
+    B: Backend,
+    K: SymbolKey,
+{
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let inline = bool::deserialize_reader(reader)?;
+        if inline && K::BITS <= 16 {
+            return Err(invalid_data("inline symbols need more than 16 bits"));
+        }
+        let n_shards = u32::deserialize_reader(reader)? as usize;
+        if !(1..=N).contains(&n_shards) {
+            return Err(invalid_data(format!(
+                "serialized with {n_shards} of {N} shards in use"
+            )));
+        }
+        let len = u32::deserialize_reader(reader)?;
+        if len as usize != N {
+            return Err(invalid_data(format!(
+                "serialized with {len} shards, not {N}"
+            )));
+        }
+
+        let mut table = Self::with_hasher(S::default()).with_shards(n_shards);
+        table.inline = inline;
+        for shard_i in 0..N {
+            for _ in 0..u32::deserialize_reader(reader)? {
+                let string = String::deserialize_reader(reader)?;
+                table.restore(shard_i, &string).map_err(invalid_data)?;
+            }
+        }
+        Ok(table)
+    }
+}
+
+fn invalid_data(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
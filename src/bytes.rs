@@ -0,0 +1,81 @@
+use crate::*;
+
+/// A table in which you can intern byte strings and get back [`Symbol`]s.
+///
+/// This is just like a [`SymbolTable`], with the same sharded design and the
+/// same symbol encoding, except that the interned strings are `[u8]`s that
+/// don't have to be valid UTF-8.
+///
+/// ```
+/// use symbol_table::ByteSymbolTable;
+///
+/// let table = ByteSymbolTable::new();
+/// let sym = table.intern_bytes(b"\xff\xfe");
+/// assert_eq!(table.intern_bytes(b"\xff\xfe"), sym);
+/// assert_eq!(table.resolve_bytes(sym), b"\xff\xfe");
+/// ```
+pub struct ByteSymbolTable<
+    const N: usize = DEFAULT_N_SHARDS,
+    S = DeterministicHashBuilder,
+    B = StringBackend,
+    K = Symbol,
+> {
+    // Never exposed, since it may hold bytes that aren't UTF-8.
+    table: SymbolTable<N, S, B, K>,
+}
+
+impl ByteSymbolTable<DEFAULT_N_SHARDS, DeterministicHashBuilder> {
+    /// Creates a new [`ByteSymbolTable`] with the default generic arguments.
+    pub const fn new() -> Self {
+        Self::with_hasher(DeterministicHashBuilder)
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> ByteSymbolTable<N, S, B, K> {
+    /// Creates a new [`ByteSymbolTable`] with a custom hasher.
+    pub const fn with_hasher(build_hasher: S) -> Self {
+        Self {
+            table: SymbolTable::with_hasher(build_hasher),
+        }
+    }
+
+    /// Encode very short byte strings directly in their symbols.
+    ///
+    /// See [`SymbolTable::with_inline_strings`]; this applies to byte strings
+    /// of up to two ASCII bytes.
+    pub fn with_inline_strings(mut self) -> Self {
+        self.table = self.table.with_inline_strings();
+        self
+    }
+
+    /// Intern a byte string into the [`ByteSymbolTable`].
+    ///
+    /// Interning the same bytes will give the same symbol.
+    pub fn intern_bytes(&self, bytes: &[u8]) -> K {
+        self.table.intern_internable(bytes)
+    }
+
+    /// Intern an owned byte string, reusing its allocation if the backend
+    /// supports it.
+    ///
+    /// See [`SymbolTable::intern_owned`].
+    pub fn intern_bytes_owned(&self, bytes: Vec<u8>) -> K {
+        self.table.intern_internable(bytes)
+    }
+
+    /// Resolve a symbol to the interned bytes.
+    ///
+    /// The resolved bytes are immutable and will live as long as the
+    /// [`ByteSymbolTable`].
+    pub fn resolve_bytes(&self, sym: K) -> &[u8] {
+        self.table.resolve_bytes(sym)
+    }
+}
+
+impl<const N: usize, S: Default + BuildHasher, B: Backend, K: SymbolKey> Default
+    for ByteSymbolTable<N, S, B, K>
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
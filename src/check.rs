@@ -0,0 +1,137 @@
+use crate::*;
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Audit the table, describing the first broken invariant it finds.
+    ///
+    /// This requires the `check` feature on the crate. It's meant for
+    /// fuzzers and soak tests, to assert that the table wasn't corrupted,
+    /// e.g. through unsafe code around it. It checks that every shard's map
+    /// indexes each of its strings exactly once, that every string rehashes
+    /// to its stored hash and to its shard, or is found there if it was
+    /// [interned into another shard](Self::intern_in_shard), so no string is
+    /// stored twice in the table, and that the symbol of every string
+    /// encodes its shard and index. It also checks the
+    /// [aliases](Self::alias), and that nothing is stored in shards beyond
+    /// the ones [in use](Self::with_shards).
+    ///
+    /// It locks one shard at a time, and takes time linear in the number
+    /// and length of the strings, so it can run concurrently with interning.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// table.intern_many(["foo", "bar"]);
+    /// assert_eq!(table.check_invariants(), Ok(()));
+    /// ```
+    pub fn check_invariants(&self) -> Result<(), String> {
+        for shard_i in 0..N {
+            let shard = self.lock_shard(shard_i);
+            let canonical = self.check_shard(shard_i, &shard)?;
+            // the canonical symbols may be in other shards
+            drop(shard);
+            for (sym, pinned) in canonical {
+                if !self.contains_symbol(sym) {
+                    return Err(format!(
+                        "alias in shard {shard_i} is of {:#x}, which isn't in the table",
+                        sym.into_usize()
+                    ));
+                }
+                let Some(string) = pinned else { continue };
+                if self.resolve_bytes(sym) != string.as_bytes()
+                    || self.lookup_bytes(string.as_bytes()) != Some(sym)
+                {
+                    return Err(format!(
+                        "{string:?} is pinned to {:#x}, but isn't found there",
+                        sym.into_usize()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the invariants of a locked shard, giving the canonical symbols
+    /// of its aliases, and its strings that hash to other shards, which are
+    /// checked without the lock.
+    fn check_shard(
+        &self,
+        shard_i: usize,
+        shard: &Shard<B>,
+    ) -> Result<Vec<(K, Option<String>)>, String> {
+        let len = shard.strs.len();
+        if shard_i >= self.n_shards && len > 0 {
+            return Err(format!(
+                "shard {shard_i} isn't in use, but has {len} strings"
+            ));
+        }
+        if shard.hashes.len() != len {
+            return Err(format!(
+                "shard {shard_i} has {len} strings, but {} hashes",
+                shard.hashes.len()
+            ));
+        }
+        #[cfg(feature = "counts")]
+        if shard.counts.len() != len {
+            return Err(format!(
+                "shard {shard_i} has {len} strings, but {} counts",
+                shard.counts.len()
+            ));
+        }
+        if shard.map.len() != len {
+            return Err(format!(
+                "shard {shard_i} has {len} strings, but {} map entries",
+                shard.map.len()
+            ));
+        }
+
+        let mut indexed = vec![false; len];
+        for &idx in shard.map.keys() {
+            match indexed.get_mut(idx as usize) {
+                Some(seen @ false) => *seen = true,
+                Some(true) => return Err(format!("shard {shard_i} maps index {idx} twice")),
+                None => return Err(format!("shard {shard_i} maps index {idx} out of bounds")),
+            }
+        }
+
+        let mut misplaced = Vec::new();
+        for (idx, &hash) in shard.hashes.iter().enumerate() {
+            let idx = idx as u32;
+            let bytes = shard.strs.get(idx);
+            if std::str::from_utf8(bytes).is_err() {
+                return Err(format!("string {idx} of shard {shard_i} isn't UTF-8"));
+            }
+            if self.inline_symbol(bytes).is_some() {
+                return Err(format!("string {idx} of shard {shard_i} should be inline"));
+            }
+            if hash_one(&self.build_hasher, bytes) != hash {
+                return Err(format!(
+                    "string {idx} of shard {shard_i} has the wrong hash"
+                ));
+            }
+            if shard.find(hash, bytes) != Some(idx) {
+                return Err(format!(
+                    "string {idx} of shard {shard_i} isn't found by its hash"
+                ));
+            }
+            // `make_symbol` panics on indices that don't fit
+            if idx as usize + 1 >= Self::MAX_IDX {
+                return Err(format!("shard {shard_i} has too many strings"));
+            }
+            let sym = Self::make_symbol(shard_i, idx);
+            if Self::split_symbol(sym) != Some((shard_i, idx)) {
+                return Err(format!(
+                    "string {idx} of shard {shard_i} has the symbol {:#x}, which doesn't round-trip",
+                    sym.into_usize()
+                ));
+            }
+            // strings interned with `intern_in_shard` have to be found in
+            // the shard they hash to
+            if shard_of_hash(hash, self.n_shards) != shard_i {
+                misplaced.push((sym, String::from_utf8_lossy(bytes).into_owned()));
+            }
+        }
+
+        let mut canonical = self.check_aliases(shard_i, shard)?;
+        canonical.extend(misplaced.into_iter().map(|(sym, s)| (sym, Some(s))));
+        Ok(canonical)
+    }
+}
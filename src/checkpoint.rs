@@ -0,0 +1,77 @@
+use crate::*;
+
+/// A point in the history of a [`SymbolTable`] to roll back to.
+///
+/// Created by [`SymbolTable::checkpoint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    // the number of strings in every shard
+    pub(crate) lens: Vec<usize>,
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Remember which strings are in the table right now.
+    ///
+    /// Pass the [`Checkpoint`] to [`rollback_to`](Self::rollback_to) to
+    /// discard everything interned after this.
+    ///
+    /// The shards are checkpointed one after the other, so strings interned
+    /// concurrently may or may not be included.
+    pub fn checkpoint(&self) -> Checkpoint {
+        let lens = self.shards.iter().map(|s| s.lock().unwrap().strs.len());
+        Checkpoint {
+            lens: lens.collect(),
+        }
+    }
+
+    /// Discard every string interned since the [`Checkpoint`] was taken.
+    ///
+    /// Since strings are only ever appended to the shards, this just
+    /// truncates them. Symbols interned before the checkpoint stay valid, but
+    /// symbols interned after it must not be used anymore: resolving them
+    /// panics, or gives a string interned after the rollback.
+    ///
+    /// The checkpoint must come from this table. Rolling back to a checkpoint
+    /// taken before an earlier rollback or [`compact`](Self::compact) doesn't
+    /// restore anything, and only discards strings as if the checkpoint was
+    /// taken later.
+    ///
+    /// ```
+    /// let mut table = symbol_table::SymbolTable::new();
+    /// let foo = table.intern("foo");
+    /// let cp = table.checkpoint();
+    /// table.intern("bar");
+    ///
+    /// table.rollback_to(&cp);
+    /// assert_eq!(table.resolve(foo), "foo");
+    /// assert_eq!(table.checkpoint(), cp);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// A [log](Self::with_log) can't be rolled back, and replaying it would
+    /// then give different symbols, so this panics if the table has one.
+    pub fn rollback_to(&mut self, checkpoint: &Checkpoint) {
+        assert!(self.log.is_none(), "Can't roll back a table with a log");
+        let mut discarded = 0;
+        for (shard, &len) in self.shards.iter_mut().zip(&checkpoint.lens) {
+            let shard = shard.get_mut().unwrap();
+            if len < shard.strs.len() {
+                discarded += shard.strs.len() - len;
+                shard.strs.truncate(len);
+                shard.hashes.truncate(len);
+                #[cfg(feature = "counts")]
+                shard.counts.truncate(len);
+                shard.map.retain(|&idx, _| (idx as usize) < len);
+            }
+        }
+
+        if self.max_entries != usize::MAX {
+            self.entries.fetch_sub(discarded, Ordering::Relaxed);
+        }
+        self.remap_aliases(|sym| match Self::split_symbol(sym) {
+            Some((shard_i, idx)) => ((idx as usize) < checkpoint.lens[shard_i]).then_some(sym),
+            None => Some(sym),
+        });
+    }
+}
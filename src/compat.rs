@@ -0,0 +1,195 @@
+/*!
+Interop with the `lasso` and `string_interner` crates, to migrate from them
+incrementally.
+
+With the `lasso` feature on the crate, [`SymbolTable`] implements lasso's
+[`Interner`](lasso::Interner), [`Reader`](lasso::Reader), and
+[`Resolver`](lasso::Resolver) traits, so code that is generic over those
+can use a symbol table as it is. Its keys are the table's symbols, and
+`&SymbolTable` is an interner too, like `&ThreadedRodeo`.
+
+Keys that are already stored elsewhere can be converted to symbols one at a
+time with [`FromLassoKey`], or [`FromInternerSymbol`] for `string_interner`
+with the `string-interner` feature, by interning their strings. That
+crate's `StringInterner` is a concrete type without traits to implement, so
+its call sites have to switch to the table's own methods, which are named
+differently: `get_or_intern` is [`intern`](SymbolTable::intern), and `get`
+is [`lookup`](SymbolTable::lookup).
+
+```
+# #[cfg(feature = "lasso")] {
+use lasso::{Interner, Rodeo};
+use symbol_table::{compat::FromLassoKey, SymbolTable};
+
+// generic code keeps working with a symbol table
+fn intern_all<K>(interner: &mut impl Interner<K>, words: &str) -> Vec<K> {
+    words.split(' ').map(|w| interner.get_or_intern(w)).collect()
+}
+
+let mut rodeo = Rodeo::default();
+let keys = intern_all(&mut rodeo, "old keys");
+let mut table = SymbolTable::new();
+let syms = intern_all(&mut table, "new symbols");
+
+// and keys stored from before can be converted
+let old = table.intern_lasso_key(&rodeo, keys[0]).unwrap();
+assert_eq!(table.resolve(old), "old");
+# }
+```
+*/
+
+use crate::*;
+
+/// Tables that keys from a `lasso` interner can be converted into.
+///
+/// This requires the `lasso` feature on the crate.
+#[cfg(feature = "lasso")]
+pub trait FromLassoKey<LK> {
+    /// The symbols of this table.
+    type Symbol;
+
+    /// Intern the string of `key` into this table, or `None` if `key` isn't
+    /// from `resolver`.
+    fn intern_lasso_key(
+        &self,
+        resolver: &impl lasso::Resolver<LK>,
+        key: LK,
+    ) -> Option<Self::Symbol>;
+}
+
+/// Tables that symbols from a `string_interner` interner can be converted
+/// into.
+///
+/// This requires the `string-interner` feature on the crate.
+#[cfg(feature = "string-interner")]
+pub trait FromInternerSymbol<IB: string_interner::backend::Backend> {
+    /// The symbols of this table.
+    type Symbol;
+
+    /// Intern the string of `sym` into this table, or `None` if `sym` isn't
+    /// from `interner`.
+    fn intern_interner_symbol<H: BuildHasher>(
+        &self,
+        interner: &string_interner::StringInterner<IB, H>,
+        sym: IB::Symbol,
+    ) -> Option<Self::Symbol>;
+}
+
+#[cfg(feature = "lasso")]
+impl<LK, const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> FromLassoKey<LK>
+    for SymbolTable<N, S, B, K>
+{
+    type Symbol = K;
+
+    fn intern_lasso_key(&self, resolver: &impl lasso::Resolver<LK>, key: LK) -> Option<K> {
+        Some(self.intern(resolver.try_resolve(&key)?))
+    }
+}
+
+#[cfg(feature = "string-interner")]
+impl<IB, const N: usize, S, B, K> FromInternerSymbol<IB> for SymbolTable<N, S, B, K>
+where
+    IB: string_interner::backend::Backend,
+    S: BuildHasher,
+    B: Backend,
+    K: SymbolKey,
+{
+    type Symbol = K;
+
+    fn intern_interner_symbol<H: BuildHasher>(
+        &self,
+        interner: &string_interner::StringInterner<IB, H>,
+        sym: IB::Symbol,
+    ) -> Option<K> {
+        Some(self.intern(interner.resolve(sym)?))
+    }
+}
+
+#[cfg(feature = "lasso")]
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> lasso::Resolver<K>
+    for SymbolTable<N, S, B, K>
+{
+    fn resolve<'a>(&'a self, key: &K) -> &'a str {
+        SymbolTable::resolve(self, *key)
+    }
+
+    fn try_resolve<'a>(&'a self, key: &K) -> Option<&'a str> {
+        self.contains_symbol(*key)
+            .then(|| SymbolTable::resolve(self, *key))
+    }
+
+    unsafe fn resolve_unchecked<'a>(&'a self, key: &K) -> &'a str {
+        // SAFETY: a key of this table is a symbol that resolves
+        unsafe { SymbolTable::resolve_unchecked(self, *key) }
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.contains_symbol(*key)
+    }
+
+    fn len(&self) -> usize {
+        (0..N)
+            .map(|shard_i| self.lock_shard(shard_i).strs.len())
+            .sum()
+    }
+}
+
+#[cfg(feature = "lasso")]
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> lasso::Reader<K>
+    for SymbolTable<N, S, B, K>
+{
+    fn get(&self, val: &str) -> Option<K> {
+        self.lookup(val)
+    }
+
+    fn contains(&self, val: &str) -> bool {
+        self.lookup(val).is_some()
+    }
+}
+
+/// Interning through lasso's trait panics where
+/// [`try_intern`](SymbolTable::try_intern) fails, even in the `try_`
+/// methods, since lasso's errors can't be made outside of lasso. Tables
+/// only fail if they're set up to, e.g.
+/// [`with_max_entries`](SymbolTable::with_max_entries).
+#[cfg(feature = "lasso")]
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> lasso::Interner<K>
+    for SymbolTable<N, S, B, K>
+{
+    fn get_or_intern(&mut self, val: &str) -> K {
+        self.intern(val)
+    }
+
+    fn try_get_or_intern(&mut self, val: &str) -> lasso::LassoResult<K> {
+        Ok(self.intern(val))
+    }
+
+    fn get_or_intern_static(&mut self, val: &'static str) -> K {
+        self.intern_static(val)
+    }
+
+    fn try_get_or_intern_static(&mut self, val: &'static str) -> lasso::LassoResult<K> {
+        Ok(self.intern_static(val))
+    }
+}
+
+#[cfg(feature = "lasso")]
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> lasso::Interner<K>
+    for &SymbolTable<N, S, B, K>
+{
+    fn get_or_intern(&mut self, val: &str) -> K {
+        self.intern(val)
+    }
+
+    fn try_get_or_intern(&mut self, val: &str) -> lasso::LassoResult<K> {
+        Ok(self.intern(val))
+    }
+
+    fn get_or_intern_static(&mut self, val: &'static str) -> K {
+        self.intern_static(val)
+    }
+
+    fn try_get_or_intern_static(&mut self, val: &'static str) -> lasso::LassoResult<K> {
+        Ok(self.intern_static(val))
+    }
+}
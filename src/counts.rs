@@ -0,0 +1,53 @@
+use crate::*;
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// How many times the string of `sym` was interned, including the first
+    /// time.
+    ///
+    /// This requires the `counts` feature on the crate, which keeps a count
+    /// for every stored string, e.g. to find the hot strings that are worth
+    /// making static with `static_symbol!`, or the
+    /// frequencies of the words of a text. Every interning method counts,
+    /// but [`lookup`](Self::lookup) doesn't. [Inline](Self::with_inline_strings)
+    /// strings aren't stored, so they aren't counted, and this gives `0` for
+    /// them. Cloning a table clones the counts, and
+    /// [`compact`](Self::compact) keeps them.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let the = table.intern_words("the cat and the hat")[0];
+    /// assert_eq!(table.intern_count(the), 2);
+    /// ```
+    pub fn intern_count(&self, sym: K) -> u64 {
+        match Self::split_symbol(sym) {
+            Some((shard_i, idx)) => self.lock_shard(shard_i).counts[idx as usize],
+            None => 0,
+        }
+    }
+
+    /// The `n` most [interned](Self::intern_count) strings, most interned
+    /// first, with their counts.
+    ///
+    /// This requires the `counts` feature on the crate. Strings that were
+    /// interned as many times are ordered by symbol. The shards are read one
+    /// after the other, so strings interned concurrently may or may not be
+    /// included.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// table.intern_words("the cat and the hat and the bat");
+    /// let top: Vec<_> = table.top_n(2).into_iter().map(|(sym, n)| (table.resolve(sym), n)).collect();
+    /// assert_eq!(top, [("the", 3), ("and", 2)]);
+    /// ```
+    pub fn top_n(&self, n: usize) -> Vec<(K, u64)> {
+        let mut counts = Vec::new();
+        for shard_i in 0..N {
+            let shard = self.shards[shard_i].lock().unwrap();
+            let syms = (0..).map(|idx| Self::make_symbol(shard_i, idx));
+            counts.extend(syms.zip(shard.counts.iter().copied()));
+        }
+        counts.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        counts.truncate(n);
+        counts
+    }
+}
@@ -0,0 +1,94 @@
+use crate::*;
+
+use std::ffi::{CStr, CString, NulError};
+
+/// A table that stores its strings NUL-terminated, for passing them to C.
+///
+/// Every string is stored with a trailing NUL byte, so
+/// [`resolve_cstr`](Self::resolve_cstr) can hand out a [`CStr`] pointing
+/// straight into the table, without allocating a `CString` each time.
+///
+/// Strings interned with [`intern`](Self::intern) must not contain interior
+/// NUL bytes, since they couldn't be resolved as a [`CStr`];
+/// [`intern_cstr`](Self::intern_cstr) accepts any [`CStr`], even one that
+/// isn't UTF-8.
+///
+/// ```
+/// use symbol_table::CStrSymbolTable;
+///
+/// let table = CStrSymbolTable::new();
+/// let sym = table.intern("hello").unwrap();
+/// assert_eq!(table.intern_cstr(c"hello"), sym);
+/// assert_eq!(table.resolve_cstr(sym), c"hello");
+/// assert_eq!(table.resolve(sym), Some("hello"));
+///
+/// assert!(table.intern("nul\0inside").is_err());
+/// ```
+pub struct CStrSymbolTable<
+    const N: usize = DEFAULT_N_SHARDS,
+    S = DeterministicHashBuilder,
+    B = StringBackend,
+    K = Symbol,
+> {
+    // Only ever holds NUL-terminated bytes without any interior NULs.
+    table: SymbolTable<N, S, B, K>,
+}
+
+impl CStrSymbolTable<DEFAULT_N_SHARDS, DeterministicHashBuilder> {
+    /// Creates a new [`CStrSymbolTable`] with the default generic arguments.
+    pub const fn new() -> Self {
+        Self::with_hasher(DeterministicHashBuilder)
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> CStrSymbolTable<N, S, B, K> {
+    /// Creates a new [`CStrSymbolTable`] with a custom hasher.
+    pub const fn with_hasher(build_hasher: S) -> Self {
+        Self {
+            table: SymbolTable::with_hasher(build_hasher),
+        }
+    }
+
+    /// Intern a string into the [`CStrSymbolTable`].
+    ///
+    /// Returns an error if the string contains a NUL byte.
+    pub fn intern(&self, string: &str) -> Result<K, NulError> {
+        let bytes = CString::new(string)?.into_bytes_with_nul();
+        Ok(self.table.intern_internable(bytes))
+    }
+
+    /// Intern a C string into the [`CStrSymbolTable`].
+    pub fn intern_cstr(&self, string: &CStr) -> K {
+        self.table.intern_internable(string.to_bytes_with_nul())
+    }
+
+    /// Resolve a symbol to the interned C string.
+    ///
+    /// The pointer from [`CStr::as_ptr`] stays valid as long as the table.
+    /// Like in every table, [`Symbol::EMPTY`] resolves to the empty string.
+    pub fn resolve_cstr(&self, sym: K) -> &CStr {
+        let bytes = self.table.resolve_bytes(sym);
+        // every stored string has its NUL, so only the inline empty string,
+        // the one inline symbol `resolve_bytes` accepts here, has no bytes
+        if bytes.is_empty() {
+            return c"";
+        }
+        // SAFETY: every string is stored with exactly one, trailing, NUL
+        // byte, and the table has no other inline strings, which
+        // `resolve_bytes` checks
+        unsafe { CStr::from_bytes_with_nul_unchecked(bytes) }
+    }
+
+    /// Resolve a symbol to the interned string, if it's valid UTF-8.
+    pub fn resolve(&self, sym: K) -> Option<&str> {
+        self.resolve_cstr(sym).to_str().ok()
+    }
+}
+
+impl<const N: usize, S: Default + BuildHasher, B: Backend, K: SymbolKey> Default
+    for CStrSymbolTable<N, S, B, K>
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
@@ -0,0 +1,102 @@
+use crate::*;
+
+use std::sync::PoisonError;
+use sync::RwLock;
+
+// the table set with `Symbol::set_debug_table`
+static DEBUG_TABLE: RwLock<Option<&'static dyn DebugTable>> = RwLock::new(None);
+
+/// A table that can describe its symbols for [`Debug`](fmt::Debug).
+trait DebugTable: Sync {
+    /// The shard and index of a stored symbol, and its string, if it's in
+    /// the table and can be read without blocking.
+    fn describe(&self, sym: Symbol) -> (usize, u32, Option<&str>);
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend> DebugTable for SymbolTable<N, S, B>
+where
+    Self: Sync,
+{
+    fn describe(&self, sym: Symbol) -> (usize, u32, Option<&str>) {
+        let (shard_i, idx) = Self::split_symbol(sym).unwrap();
+        // never block, the shard may be locked by this very thread, e.g. in
+        // an `on_intern` callback
+        let string = self.shards.get(shard_i).and_then(|shard| {
+            let shard = shard.try_lock().ok()?;
+            let bytes = (idx < shard.strs.len() as u32).then(|| shard.strs.get(idx))?;
+            // SAFETY: the string outlives the lock like in `resolve_bytes`,
+            // and it's a `str` like in `resolve`
+            Some(unsafe { std::str::from_utf8_unchecked(backend::detach(bytes)) })
+        });
+        (shard_i, idx, string)
+    }
+}
+
+impl Symbol {
+    /// Show the strings of `table` in the [`Debug`](fmt::Debug) output of
+    /// every [`Symbol`], until [`clear_debug_table`](Self::clear_debug_table).
+    ///
+    /// A symbol's number only means something to its table, so by default,
+    /// a symbol debugs as the shard and index it has in a table with the
+    /// default number of shards, or as its string if it's
+    /// [inline](SymbolTable::with_inline_strings). This is meant for
+    /// debugging sessions, so that `dbg!(sym)` shows the string too. The
+    /// shard and index are then decoded for the table's number of shards,
+    /// and the string is only shown if it's in the table, and its shard
+    /// isn't locked at that moment.
+    ///
+    /// ```
+    /// use symbol_table::{Symbol, SymbolTable};
+    ///
+    /// let table = SymbolTable::new().leak();
+    /// let foo = table.intern("foo");
+    /// Symbol::set_debug_table(table);
+    /// assert_eq!(format!("{foo:?}"), r#"Symbol { shard: 0, index: 0, string: "foo" }"#);
+    ///
+    /// Symbol::clear_debug_table();
+    /// assert_eq!(format!("{foo:?}"), "Symbol { shard: 0, index: 0 }");
+    /// ```
+    pub fn set_debug_table<const N: usize, S: BuildHasher, B: Backend>(
+        table: &'static SymbolTable<N, S, B>,
+    ) where
+        SymbolTable<N, S, B>: Sync,
+    {
+        *DEBUG_TABLE.write().unwrap_or_else(PoisonError::into_inner) = Some(table);
+    }
+
+    /// Stop showing strings in the [`Debug`](fmt::Debug) output of symbols,
+    /// see [`set_debug_table`](Self::set_debug_table).
+    pub fn clear_debug_table() {
+        *DEBUG_TABLE.write().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        type Layout = SymbolTable;
+        let mut debug = f.debug_struct("Symbol");
+        let raw = self.0.get() as usize;
+        if raw & Layout::INLINE_TAG != 0 {
+            let payload = (raw & !Layout::INLINE_TAG) as u32;
+            match inline::try_decode(payload) {
+                Some(string) => debug.field("inline", &string),
+                None => debug.field("invalid", &raw),
+            };
+            return debug.finish();
+        }
+
+        let table = *DEBUG_TABLE.read().unwrap_or_else(PoisonError::into_inner);
+        let (shard_i, idx, string) = match table {
+            Some(table) => table.describe(*self),
+            None => {
+                let (shard_i, idx) = Layout::split_symbol(*self).unwrap();
+                (shard_i, idx, None)
+            }
+        };
+        debug.field("shard", &shard_i).field("index", &idx);
+        if let Some(string) = string {
+            debug.field("string", &string);
+        }
+        debug.finish()
+    }
+}
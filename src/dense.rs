@@ -0,0 +1,180 @@
+use crate::*;
+
+use hashbrown::HashTable;
+use sync::RwLock;
+
+/// A symbol table that hands out dense symbols in insertion order.
+///
+/// The symbols of a [`SymbolTable`] embed the shard they live in, so they
+/// are spread all over the `u32` range. The symbols of a
+/// [`DenseSymbolTable`] are instead numbered `0, 1, 2, ...` in the order the
+/// strings were first interned (see [`index_of`](Self::index_of)), which makes
+/// them suitable for indexing into a `Vec` side table.
+///
+/// The empty string is [`Symbol::EMPTY`], like in every table, so it isn't
+/// stored, and doesn't get an index.
+///
+/// Lookups are still sharded `N` times, so interning a string that's already
+/// present only locks one shard. Interning a *new* string additionally takes
+/// a write lock on the table-wide string storage to allocate the next ID.
+///
+/// ```
+/// use symbol_table::DenseSymbolTable;
+///
+/// let table = DenseSymbolTable::new();
+/// let foo = table.intern("foo");
+/// let bar = table.intern("bar");
+/// assert_eq!(table.index_of(foo), 0);
+/// assert_eq!(table.index_of(bar), 1);
+/// assert_eq!(table.intern("foo"), foo);
+/// assert_eq!(table.resolve(bar), "bar");
+/// ```
+pub struct DenseSymbolTable<
+    const N: usize = DEFAULT_N_SHARDS,
+    S = DeterministicHashBuilder,
+    B = StringBackend,
+    K = Symbol,
+> {
+    build_hasher: S,
+    shards: [CachePadded<Mutex<DenseShard>>; N],
+    strs: RwLock<B>,
+    _key: PhantomData<K>,
+}
+
+struct DenseShard {
+    // (hash, id) of every string that lives in this shard
+    map: HashTable<(u64, u32)>,
+}
+
+impl DenseSymbolTable<DEFAULT_N_SHARDS, DeterministicHashBuilder> {
+    /// Creates a new [`DenseSymbolTable`] with the default generic arguments.
+    pub const fn new() -> Self {
+        Self::with_hasher(DeterministicHashBuilder)
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> DenseSymbolTable<N, S, B, K> {
+    /// Creates a new [`DenseSymbolTable`] with a custom hasher.
+    #[allow(clippy::assertions_on_constants)]
+    pub const fn with_hasher(build_hasher: S) -> Self {
+        assert!(0 < N);
+        Self {
+            build_hasher,
+            shards: [const {
+                CachePadded::new(Mutex::new(DenseShard {
+                    map: HashTable::new(),
+                }))
+            }; N],
+            strs: RwLock::new(B::EMPTY),
+            _key: PhantomData,
+        }
+    }
+
+    /// Intern a string into the [`DenseSymbolTable`].
+    ///
+    /// If the string is new, it gets the next ID in insertion order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table is full: the top bit of a symbol is reserved for
+    /// [inline](SymbolTable::with_inline_strings) symbols like
+    /// [`Symbol::EMPTY`], so a table holds `2^(K::BITS - 1) - 1` strings.
+    pub fn intern(&self, string: &str) -> K {
+        if string.is_empty() {
+            return inline::empty_symbol();
+        }
+        let hash = hash_one(&self.build_hasher, string.as_bytes());
+        let mut shard = self.shards[hash as usize % N].lock().unwrap();
+
+        let strs = self.strs.read().unwrap();
+        if let Some(&(_, id)) = shard
+            .map
+            .find(hash, |&(_, id)| strs.get(id) == string.as_bytes())
+        {
+            return Self::symbol(id);
+        }
+        drop(strs);
+
+        // We still hold the shard lock, so nobody else can insert this string
+        // between dropping the read lock and taking the write lock.
+        let mut strs = self.strs.write().unwrap();
+        let id = strs.len();
+        let Some(sym) = Self::try_symbol(id) else {
+            // don't poison the locks by panicking while holding them
+            drop(strs);
+            drop(shard);
+            panic!("Can't represent index {id} in a Symbol");
+        };
+        let id = strs.push(string.as_bytes());
+        drop(strs);
+
+        shard.map.insert_unique(hash, (hash, id), |&(hash, _)| hash);
+        sym
+    }
+
+    /// Resolve a symbol to the interned string.
+    ///
+    /// The resolved string is immutable and will live as long as the
+    /// [`DenseSymbolTable`].
+    pub fn resolve(&self, sym: K) -> &str {
+        if sym == inline::empty_symbol() {
+            return "";
+        }
+        let strs = self.strs.read().unwrap();
+        let bytes: &[u8] = strs.get(self.index_of(sym) as u32);
+        // SAFETY: the `Backend` guarantees the string never moves, and it
+        // lives as long as `self`. See `SymbolTable::resolve`.
+        // Only `str`s are ever pushed, so it's still valid UTF-8.
+        unsafe { std::str::from_utf8_unchecked(backend::detach(bytes)) }
+    }
+
+    /// The number of strings interned in this table.
+    pub fn len(&self) -> usize {
+        self.strs.read().unwrap().len()
+    }
+
+    /// Whether no strings have been interned in this table.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The insertion-order index of a symbol from a [`DenseSymbolTable`].
+    ///
+    /// The first string interned has index 0, the next one 1, and so on.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sym` is [`Symbol::EMPTY`], which has no index.
+    pub fn index_of(&self, sym: K) -> usize {
+        assert!(
+            sym != inline::empty_symbol(),
+            "The empty string has no index"
+        );
+        sym.into_usize() - 1
+    }
+
+    /// Get the symbol with the given insertion-order index, if there is one.
+    pub fn symbol_at(&self, index: usize) -> Option<K> {
+        (index < self.len()).then(|| Self::symbol(index as u32))
+    }
+
+    fn symbol(id: u32) -> K {
+        Self::try_symbol(id as usize)
+            .unwrap_or_else(|| panic!("Can't represent index {id} in a Symbol"))
+    }
+
+    /// The symbol of the string with the given index, unless the index is
+    /// too big for `K`, and would run into the tag bit of inline symbols.
+    fn try_symbol(id: usize) -> Option<K> {
+        let max = (1 << (K::BITS - 1)) - 2;
+        (id <= max).then(|| K::try_from_usize(id + 1)).flatten()
+    }
+}
+
+impl<const N: usize, S: Default + BuildHasher, B: Backend, K: SymbolKey> Default
+    for DenseSymbolTable<N, S, B, K>
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
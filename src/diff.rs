@@ -0,0 +1,75 @@
+use crate::*;
+
+/// How the strings of two tables, and their symbols, differ.
+///
+/// Created by [`SymbolTable::diff`]. Every list is sorted by string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TableDiff<'a, K = Symbol> {
+    /// The strings that are only in the first table, with their symbols.
+    pub only_in_self: Vec<(&'a str, K)>,
+    /// The strings that are only in the other table, with their symbols.
+    pub only_in_other: Vec<(&'a str, K)>,
+    /// The strings that are in both tables, but under different symbols,
+    /// with their symbols in the first table and in the other.
+    pub changed: Vec<(&'a str, K, K)>,
+}
+
+impl<K> TableDiff<'_, K> {
+    /// Whether the tables have the same strings under the same symbols.
+    pub fn is_empty(&self) -> bool {
+        self.only_in_self.is_empty() && self.only_in_other.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Compare the strings stored in this table with the ones in `other`,
+    /// e.g. to explain why the symbols of two snapshots drifted apart.
+    ///
+    /// Only stored strings are compared, and they're looked up as they are,
+    /// without the [transform](Self::with_transform). A string that's
+    /// [inline](Self::with_inline_strings) in one table but stored in the
+    /// other has different symbols in each, so it's
+    /// [`changed`](TableDiff::changed). Like [`symbols`](Self::symbols),
+    /// strings interned concurrently may or may not be included.
+    ///
+    /// ```
+    /// let old = symbol_table::SymbolTable::new();
+    /// let new = old.clone();
+    /// old.intern("removed");
+    /// new.intern("added");
+    ///
+    /// let diff = old.diff(&new);
+    /// assert_eq!(diff.only_in_self, [("removed", old.intern("removed"))]);
+    /// assert_eq!(diff.only_in_other, [("added", new.intern("added"))]);
+    /// assert!(diff.changed.is_empty());
+    /// ```
+    pub fn diff<'a, const M: usize, T: BuildHasher, C: Backend>(
+        &'a self,
+        other: &'a SymbolTable<M, T, C, K>,
+    ) -> TableDiff<'a, K> {
+        let mut diff = TableDiff {
+            only_in_self: Vec::new(),
+            only_in_other: Vec::new(),
+            changed: Vec::new(),
+        };
+        for sym in self.stored_symbols() {
+            let string = self.resolve(sym);
+            match other.lookup_bytes(string.as_bytes()) {
+                None => diff.only_in_self.push((string, sym)),
+                Some(theirs) if theirs != sym => diff.changed.push((string, sym, theirs)),
+                Some(_) => {}
+            }
+        }
+        for sym in other.stored_symbols() {
+            let string = other.resolve(sym);
+            if self.lookup_bytes(string.as_bytes()).is_none() {
+                diff.only_in_other.push((string, sym));
+            }
+        }
+        diff.only_in_self.sort_unstable();
+        diff.only_in_other.sort_unstable();
+        diff.changed.sort_unstable();
+        diff
+    }
+}
@@ -0,0 +1,54 @@
+use crate::*;
+
+/// A [`Symbol`] together with its table, which formats as its string.
+///
+/// Created by [`SymbolTable::display`].
+pub struct SymbolDisplay<'a, const N: usize, S, B, K> {
+    table: &'a SymbolTable<N, S, B, K>,
+    sym: K,
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Format a symbol as its string.
+    ///
+    /// The string is only resolved when the returned value is formatted,
+    /// both with [`Display`](fmt::Display) and [`Debug`](fmt::Debug).
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let foo = table.intern("foo");
+    /// assert_eq!(format!("{}", table.display(foo)), "foo");
+    /// assert_eq!(format!("{:?}", table.display(foo)), "\"foo\"");
+    /// assert_eq!(format!("{:>5}", table.display(foo)), "  foo");
+    /// ```
+    pub fn display(&self, sym: K) -> SymbolDisplay<'_, N, S, B, K> {
+        SymbolDisplay { table: self, sym }
+    }
+}
+
+impl<const N: usize, S, B, K> Clone for SymbolDisplay<'_, N, S, B, K>
+where
+    K: Copy,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<const N: usize, S, B, K: Copy> Copy for SymbolDisplay<'_, N, S, B, K> {}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> fmt::Display
+    for SymbolDisplay<'_, N, S, B, K>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.table.resolve(self.sym), f)
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> fmt::Debug
+    for SymbolDisplay<'_, N, S, B, K>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.table.resolve(self.sym), f)
+    }
+}
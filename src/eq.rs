@@ -0,0 +1,98 @@
+use crate::*;
+
+/// Two tables are equal if they contain the same strings, no matter which
+/// symbols those strings got.
+///
+/// Strings that a table with [inline strings](SymbolTable::with_inline_strings)
+/// would encode in their symbols aren't stored in it, so those are ignored if
+/// either table uses inline strings. Use
+/// [`same_symbols`](SymbolTable::same_symbols) to also compare the symbols.
+///
+/// ```
+/// use symbol_table::SymbolTable;
+///
+/// let a = SymbolTable::new();
+/// let b = SymbolTable::new();
+/// for s in ["foo", "bar"] {
+///     a.intern(s);
+/// }
+/// for s in ["bar", "foo"] {
+///     b.intern(s);
+/// }
+/// assert!(a == b);
+/// b.intern("baz");
+/// assert!(a != b);
+/// ```
+impl<const N: usize, const M: usize, S, S2, B, B2, K, K2> PartialEq<SymbolTable<M, S2, B2, K2>>
+    for SymbolTable<N, S, B, K>
+where
+    S: BuildHasher,
+    S2: BuildHasher,
+    B: Backend,
+    B2: Backend,
+    K: SymbolKey,
+    K2: SymbolKey,
+{
+    fn eq(&self, other: &SymbolTable<M, S2, B2, K2>) -> bool {
+        if std::ptr::eq(
+            self as *const Self as *const (),
+            other as *const _ as *const (),
+        ) {
+            return true;
+        }
+
+        let skip_inline = self.inline || other.inline;
+        let counted = |bytes: &[u8]| !skip_inline || inline::encode(bytes).is_none();
+        let mut len = 0;
+        for sym in self.stored_symbols() {
+            let bytes = self.resolve_bytes(sym);
+            if counted(bytes) {
+                if other.lookup_bytes(bytes).is_none() {
+                    return false;
+                }
+                len += 1;
+            }
+        }
+
+        // every string is only stored once, so it's enough to count them
+        let other_len = other.stored_symbols().map(|sym| other.resolve_bytes(sym));
+        other_len.filter(|bytes| counted(bytes)).count() == len
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> Eq for SymbolTable<N, S, B, K> {}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Whether both tables contain the same strings, with the same symbols.
+    ///
+    /// Unlike `==`, which only compares the strings, this holds only if
+    /// every symbol resolves to the same string in both tables. That's the
+    /// case for a [clone](Clone), or for two tables that interned the same
+    /// strings in the same order.
+    ///
+    /// ```
+    /// use symbol_table::SymbolTable;
+    ///
+    /// let a = SymbolTable::new();
+    /// let b = SymbolTable::new();
+    /// a.intern("foo");
+    /// a.intern("bar");
+    /// b.intern("bar");
+    /// b.intern("foo");
+    /// assert!(a == b);
+    /// assert!(a.same_symbols(&a.clone()));
+    /// ```
+    pub fn same_symbols<S2: BuildHasher, B2: Backend>(
+        &self,
+        other: &SymbolTable<N, S2, B2, K>,
+    ) -> bool {
+        if self.inline != other.inline {
+            return false;
+        }
+        // a checkpoint is the length of every shard
+        self.checkpoint() == other.checkpoint()
+            && self
+                .stored_symbols()
+                .all(|sym| self.resolve_bytes(sym) == other.resolve_bytes(sym))
+    }
+}
@@ -0,0 +1,98 @@
+use std::{borrow::Cow, fmt, io};
+
+/// The error from [`SymbolTable::try_intern`](crate::SymbolTable::try_intern)
+/// when a string would exceed one of the table's limits, is invalid, or
+/// couldn't be logged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InternError {
+    /// The string is longer than the table's
+    /// [`max_str_len`](crate::SymbolTable::with_max_str_len).
+    TooLong {
+        /// The length of the string, in bytes.
+        len: usize,
+        /// The maximum length of a string in the table.
+        max: usize,
+    },
+    /// The string is new, but the table already has its
+    /// [`max_entries`](crate::SymbolTable::with_max_entries).
+    TooManyEntries {
+        /// The maximum number of strings in the table.
+        max: usize,
+    },
+    /// The string is new, but appending it to the table's
+    /// [log](crate::SymbolTable::with_log) failed.
+    Log {
+        /// The kind of the I/O error.
+        kind: io::ErrorKind,
+    },
+    /// The string is new, but doesn't fit in the memory of a
+    /// [`SharedSymbolTable`](crate::SharedSymbolTable) anymore.
+    OutOfSpace {
+        /// The number of bytes available for strings in the table.
+        capacity: usize,
+    },
+    /// The string was rejected by the table's
+    /// [validator](crate::SymbolTable::with_validator).
+    Invalid {
+        /// Why the string isn't valid.
+        reason: InvalidStr,
+    },
+}
+
+impl fmt::Display for InternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLong { len, max } => {
+                write!(f, "string of {len} bytes is longer than the limit of {max}")
+            }
+            Self::TooManyEntries { max } => {
+                write!(f, "symbol table is full with {max} strings")
+            }
+            Self::Log { kind } => write!(f, "failed to append to the log: {kind}"),
+            Self::OutOfSpace { capacity } => {
+                write!(f, "symbol table is out of its {capacity} bytes of memory")
+            }
+            Self::Invalid { reason } => write!(f, "invalid string: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for InternError {}
+
+/// Why a [validator](crate::SymbolTable::with_validator) rejected a string.
+///
+/// The validators in the [`validate`](crate::validate) module give the first
+/// two kinds; others can give any reason with [`Other`](InvalidStr::Other).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvalidStr {
+    /// The string has a character that isn't ASCII.
+    NotAscii {
+        /// The byte offset of the first such character.
+        at: usize,
+    },
+    /// The string isn't an identifier.
+    NotIdentifier {
+        /// The byte offset of the first character that can't be in an
+        /// identifier there, or `0` for the empty string.
+        at: usize,
+    },
+    /// The string is invalid for another reason.
+    Other(Cow<'static, str>),
+}
+
+impl fmt::Display for InvalidStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAscii { at } => write!(f, "non-ASCII character at byte {at}"),
+            Self::NotIdentifier { at: 0 } => write!(f, "not an identifier"),
+            Self::NotIdentifier { at } => {
+                write!(f, "character at byte {at} can't be in an identifier")
+            }
+            Self::Other(reason) => f.write_str(reason),
+        }
+    }
+}
+
+impl std::error::Error for InvalidStr {}
@@ -0,0 +1,124 @@
+/*!
+A C API for the global symbol table.
+
+This requires the `ffi` feature on the crate, which implies `global`.
+
+These functions intern into the same table as [`GlobalSymbol`], so C and
+Rust code in one program share their symbols: a `u32` from
+[`symbol_intern`] is the raw value of the [`GlobalSymbol`] of that string,
+and `0` is never a valid symbol. The declarations for C are in
+`include/symbol_table.h` at the root of the crate. To link them, build the
+`symbol_table_ffi` crate in `ffi/`, which is just these functions as a
+`staticlib` and a `cdylib`, e.g. `libsymbol_table_ffi.a` and
+`libsymbol_table_ffi.so` on Linux.
+
+A panic can't unwind into C, so none of these functions panic: resolving
+a symbol that didn't come from the global table gives null, and if the
+table itself panics, e.g. because its lock was poisoned, they give `0` or
+null.
+*/
+
+use crate::*;
+
+use std::ffi::{c_char, CStr};
+use std::panic::{catch_unwind, UnwindSafe};
+
+// A NUL-terminated copy of every string that was resolved from C, since the
+// global table doesn't store the NULs.
+static C_STRS: CStrSymbolTable = CStrSymbolTable::new();
+
+fn intern_bytes(bytes: &[u8]) -> u32 {
+    match std::str::from_utf8(bytes) {
+        Ok(string) => NonZeroU32::from(GlobalSymbol::from(string)).get(),
+        Err(_) => 0,
+    }
+}
+
+fn resolve(sym: u32) -> Option<&'static str> {
+    let raw = Symbol::try_from_u32(sym)?;
+    if !DefaultNamespace::table().contains_symbol(raw) {
+        return None;
+    }
+    let sym: GlobalSymbol = NonZeroU32::from(raw).into();
+    Some(sym.as_str())
+}
+
+/// Run `f`, giving `failed` instead if it panics, since a panic can't
+/// unwind out of an `extern "C"` function.
+fn no_unwind<T>(failed: T, f: impl FnOnce() -> T + UnwindSafe) -> T {
+    catch_unwind(f).unwrap_or(failed)
+}
+
+/// Intern a NUL-terminated string, returning its symbol.
+///
+/// Returns `0` if `string` is null or not valid UTF-8.
+///
+/// # Safety
+///
+/// `string` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn symbol_intern(string: *const c_char) -> u32 {
+    if string.is_null() {
+        return 0;
+    }
+    // SAFETY: the caller passes a NUL-terminated string
+    let bytes = unsafe { CStr::from_ptr(string) }.to_bytes();
+    no_unwind(0, || intern_bytes(bytes))
+}
+
+/// Intern the `len` bytes at `string`, returning their symbol.
+///
+/// The bytes don't have to be NUL-terminated, and may contain NULs. Returns
+/// `0` if they're not valid UTF-8.
+///
+/// # Safety
+///
+/// `string` must point to `len` readable bytes, or be null if `len` is `0`.
+#[no_mangle]
+pub unsafe extern "C" fn symbol_intern_len(string: *const c_char, len: usize) -> u32 {
+    let bytes = match len {
+        0 => &[],
+        // SAFETY: the caller passes `len` readable bytes
+        _ => unsafe { std::slice::from_raw_parts(string.cast::<u8>(), len) },
+    };
+    no_unwind(0, || intern_bytes(bytes))
+}
+
+/// Resolve a symbol to its NUL-terminated string.
+///
+/// The string lives as long as the program, so the pointer may be kept.
+/// Returns null for `0` and other symbols that aren't in the table, or if
+/// the string contains a NUL, since it couldn't be NUL-terminated; use
+/// [`symbol_resolve_len`] for those.
+#[no_mangle]
+pub extern "C" fn symbol_resolve(sym: u32) -> *const c_char {
+    no_unwind(std::ptr::null(), || {
+        match resolve(sym).map(|string| C_STRS.intern(string)) {
+            Some(Ok(c_sym)) => C_STRS.resolve_cstr(c_sym).as_ptr(),
+            _ => std::ptr::null(),
+        }
+    })
+}
+
+/// Resolve a symbol to its string, storing its length in bytes in `len`.
+///
+/// The string is *not* NUL-terminated, but this is faster than
+/// [`symbol_resolve`], and works for strings that contain NULs. It lives as
+/// long as the program. Returns null for `0` and other symbols that aren't
+/// in the table, and then sets `len` to `0`.
+///
+/// # Safety
+///
+/// `len` must be null or point to a writable `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn symbol_resolve_len(sym: u32, len: *mut usize) -> *const c_char {
+    let string = no_unwind(None, || resolve(sym));
+    if !len.is_null() {
+        // SAFETY: the caller passes a writable `size_t`
+        unsafe { len.write(string.map_or(0, str::len)) };
+    }
+    match string {
+        Some(string) => string.as_ptr().cast(),
+        None => std::ptr::null(),
+    }
+}
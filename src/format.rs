@@ -0,0 +1,215 @@
+use crate::*;
+
+/// Intern a formatted string into a [`SymbolTable`], without allocating a
+/// [`String`] for it.
+///
+/// `intern_fmt!(table, ...)` takes the same arguments as [`format!`] after
+/// the table, and is short for `table.intern_fmt(format_args!(...))`, see
+/// [`SymbolTable::intern_fmt`].
+///
+/// ```
+/// use symbol_table::{intern_fmt, SymbolTable};
+///
+/// let table = SymbolTable::new();
+/// let tmp = intern_fmt!(table, "tmp_{}", 42);
+/// assert_eq!(tmp, table.intern("tmp_42"));
+/// ```
+#[macro_export]
+macro_rules! intern_fmt {
+    ($table:expr, $($arg:tt)*) => {
+        $table.intern_fmt(::std::format_args!($($arg)*))
+    };
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Intern a formatted string, see [`intern_fmt!`](crate::intern_fmt).
+    ///
+    /// Short strings are formatted into a buffer on the stack, so the only
+    /// allocation is the backend's copy of a new string. Longer ones spill
+    /// over to the heap.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let names: Vec<_> = (0..3).map(|i| table.intern_fmt(format_args!("tmp_{i}"))).collect();
+    /// assert_eq!(table.resolve(names[2]), "tmp_2");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if a formatting trait implementation returns an error, like
+    /// [`format!`] does, or if the string exceeds the table's limits, like
+    /// [`intern`](Self::intern).
+    pub fn intern_fmt(&self, args: fmt::Arguments<'_>) -> K {
+        with_formatted(args, |string| self.intern(string))
+    }
+
+    /// Create a [`SymbolBuilder`] that interns into this table.
+    pub fn builder(&self) -> SymbolBuilder<'_, N, S, B, K> {
+        SymbolBuilder {
+            table: self,
+            buffer: String::new(),
+        }
+    }
+}
+
+/// A buffer that a string can be written into, piece by piece, and then
+/// interned.
+///
+/// Created by [`SymbolTable::builder`]. It implements [`fmt::Write`], so it
+/// can be written to with [`write!`]. [`finish`](Self::finish) interns the
+/// string and clears the buffer, keeping its memory, so a builder that's
+/// used for many strings only allocates while its buffer grows.
+///
+/// ```
+/// use std::fmt::Write;
+///
+/// let table = symbol_table::SymbolTable::new();
+/// let mut builder = table.builder();
+/// let syms: Vec<_> = (0..3)
+///     .map(|i| {
+///         write!(builder, "{}_{}", "tmp", i).unwrap();
+///         builder.finish()
+///     })
+///     .collect();
+/// assert_eq!(table.resolve(syms[1]), "tmp_1");
+/// ```
+pub struct SymbolBuilder<'a, const N: usize, S, B, K> {
+    table: &'a SymbolTable<N, S, B, K>,
+    buffer: String,
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolBuilder<'_, N, S, B, K> {
+    /// Append a string to the buffer.
+    pub fn push_str(&mut self, s: &str) {
+        self.buffer.push_str(s);
+    }
+
+    /// The string written so far.
+    pub fn as_str(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Throw away the string written so far.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// Intern the string written so far, and clear the buffer for the next.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string exceeds the table's limits, like
+    /// [`intern`](SymbolTable::intern).
+    pub fn finish(&mut self) -> K {
+        let sym = self.table.intern(&self.buffer);
+        self.buffer.clear();
+        sym
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> fmt::Write
+    for SymbolBuilder<'_, N, S, B, K>
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+/// Format `args`, and pass the string to `f`.
+///
+/// The string is only formatted if it isn't just a literal, and then into a
+/// [`FmtBuffer`].
+pub(crate) fn with_formatted<R>(args: fmt::Arguments<'_>, f: impl FnOnce(&str) -> R) -> R {
+    if let Some(string) = args.as_str() {
+        return f(string);
+    }
+    let mut buffer = FmtBuffer::new();
+    fmt::write(&mut buffer, args)
+        .expect("a formatting trait implementation returned an error unexpectedly");
+    f(buffer.as_str())
+}
+
+/// Write the `snake_case` version of a string, see
+/// [`GlobalSymbol::to_snake_case`](crate::GlobalSymbol::to_snake_case).
+pub(crate) fn write_snake_case(s: &str, out: &mut impl fmt::Write) -> fmt::Result {
+    let mut chars = s.chars().peekable();
+    // whether the last char is part of a word, and was lowercase or a digit,
+    // or uppercase, and whether any word was written yet
+    let (mut in_word, mut was_lower, mut was_upper) = (false, false, false);
+    let mut wrote = false;
+    while let Some(c) = chars.next() {
+        if !c.is_alphanumeric() {
+            (in_word, was_lower, was_upper) = (false, false, false);
+            continue;
+        }
+        let next_lower = chars.peek().is_some_and(|c| c.is_lowercase());
+        let new_word = !in_word || (c.is_uppercase() && (was_lower || (was_upper && next_lower)));
+        if new_word && wrote {
+            out.write_char('_')?;
+        }
+        for c in c.to_lowercase() {
+            out.write_char(c)?;
+        }
+        (in_word, wrote) = (true, true);
+        was_upper = c.is_uppercase();
+        was_lower = !was_upper;
+    }
+    Ok(())
+}
+
+/// Whether [`write_snake_case`] would write `s` as it is: lowercase words
+/// joined by single `'_'`s.
+#[cfg(feature = "global")]
+pub(crate) fn is_snake_case(s: &str) -> bool {
+    !s.starts_with('_')
+        && !s.ends_with('_')
+        && !s.contains("__")
+        && s.chars().all(|c| {
+            c == '_' || (c.is_alphanumeric() && !c.is_uppercase() && c.to_lowercase().eq([c]))
+        })
+}
+
+/// A string that is formatted on the stack, until it's too long for that.
+pub(crate) struct FmtBuffer {
+    stack: [u8; Self::STACK_LEN],
+    len: usize,
+    heap: Option<String>,
+}
+
+impl FmtBuffer {
+    const STACK_LEN: usize = 64;
+
+    pub(crate) fn new() -> Self {
+        Self {
+            stack: [0; Self::STACK_LEN],
+            len: 0,
+            heap: None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        match &self.heap {
+            Some(string) => string,
+            // SAFETY: only whole `str`s are written to the stack
+            None => unsafe { std::str::from_utf8_unchecked(&self.stack[..self.len]) },
+        }
+    }
+}
+
+impl fmt::Write for FmtBuffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.heap.is_none() {
+            if let Some(rest) = self.stack.get_mut(self.len..self.len + s.len()) {
+                rest.copy_from_slice(s.as_bytes());
+                self.len += s.len();
+                return Ok(());
+            }
+            let mut string = String::with_capacity(2 * (self.len + s.len()));
+            string.push_str(self.as_str());
+            self.heap = Some(string);
+        }
+        self.heap.as_mut().unwrap().push_str(s);
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use crate::*;
+
+use std::io::{self, Write};
+
+/// The first bytes of a table saved by [`SymbolTable::save_frozen`].
+const MAGIC: &[u8; 8] = b"symtabfz";
+
+/// The version of the format written by [`SymbolTable::save_frozen`].
+const VERSION: u32 = 1;
+
+/// The size of the header: the magic, version, shard count, symbol size,
+/// and inline flag.
+const HEADER_LEN: usize = 24;
+
+/// A read-only resolver that works directly on the bytes of a saved table.
+///
+/// [`SymbolTable::save_frozen`] writes a table in a layout that can be used
+/// as is, without parsing or copying it: so you can `mmap` the file (e.g.
+/// with the `memmap2` crate) and resolve symbols straight out of the mapped
+/// memory, which is instant no matter how big the table is.
+///
+/// Every symbol of the saved table resolves to the same string here.
+/// Nothing is validated up front, beyond the header, so
+/// [`resolve`](Self::resolve) checks the bounds and UTF-8 of each string it
+/// returns.
+///
+/// ```
+/// use symbol_table::{FrozenSymbolTable, SymbolTable};
+///
+/// let table = SymbolTable::new();
+/// let foo = table.intern("foo");
+/// let mut bytes = Vec::new();
+/// table.save_frozen(&mut bytes).unwrap();
+///
+/// let frozen = FrozenSymbolTable::from_bytes(&bytes).unwrap();
+/// assert_eq!(frozen.resolve(foo), Some("foo"));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FrozenSymbolTable<'a, K = Symbol> {
+    // whether the saved table had inline strings, besides the empty one
+    inline: bool,
+    shard_shift: u32,
+    n_shards: usize,
+    // `n_shards + 1` little-endian `u32`s, the index of the first string of
+    // every shard, and then the total number of strings
+    shard_starts: &'a [u8],
+    // a little-endian `u64` for every string, where it ends in `data`
+    ends: &'a [u8],
+    data: &'a [u8],
+    _key: PhantomData<K>,
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Save the table in the layout of a [`FrozenSymbolTable`].
+    ///
+    /// Unlike [`save_to`](Self::save_to), the saved table can't be loaded
+    /// back into a [`SymbolTable`]; it's only meant for resolving.
+    pub fn save_frozen(&self, mut writer: impl Write) -> io::Result<()> {
+        let lens: Vec<u32> = (0..N)
+            .map(|i| self.shards[i].lock().unwrap().strs.len() as u32)
+            .collect();
+        // the strings are read again below, and more may have been interned
+        // since, so stick to the counted ones
+        let syms = || {
+            let lens = &lens;
+            (0..N).flat_map(move |i| (0..lens[i]).map(move |idx| Self::make_symbol(i, idx)))
+        };
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(N as u32).to_le_bytes())?;
+        writer.write_all(&K::BITS.to_le_bytes())?;
+        writer.write_all(&(self.inline as u32).to_le_bytes())?;
+
+        let mut start = 0u32;
+        for len in lens.iter().chain([&0]) {
+            writer.write_all(&start.to_le_bytes())?;
+            start += len;
+        }
+        let mut end = 0u64;
+        for sym in syms() {
+            end += self.resolve_bytes(sym).len() as u64;
+            writer.write_all(&end.to_le_bytes())?;
+        }
+        for sym in syms() {
+            writer.write_all(self.resolve_bytes(sym))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, K: SymbolKey> FrozenSymbolTable<'a, K> {
+    /// Use the bytes written by [`SymbolTable::save_frozen`] as a resolver.
+    ///
+    /// This only reads the header, and fails with
+    /// [`io::ErrorKind::InvalidData`] if it wasn't saved by a table with the
+    /// same symbol type, or if `bytes` is too short.
+    pub fn from_bytes(bytes: &'a [u8]) -> io::Result<Self> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg);
+        if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+            return Err(invalid("not a frozen symbol table"));
+        }
+        if read_u32(bytes, 8) != Some(VERSION) {
+            return Err(invalid("unknown format version"));
+        }
+        if read_u32(bytes, 16) != Some(K::BITS) {
+            return Err(invalid("saved with a different symbol type"));
+        }
+
+        let n_shards = read_u32(bytes, 12).unwrap() as usize;
+        if !(1..=1024).contains(&n_shards) {
+            return Err(invalid("invalid shard count"));
+        }
+        let shard_bits = usize::BITS - (n_shards - 1).leading_zeros();
+        let starts_len = (n_shards + 1) * 4;
+        let shard_starts = bytes
+            .get(HEADER_LEN..HEADER_LEN + starts_len)
+            .ok_or_else(|| invalid("truncated shard table"))?;
+        let n_strs = read_u32(shard_starts, n_shards * 4).unwrap() as usize;
+        let rest = &bytes[HEADER_LEN + starts_len..];
+        if rest.len() / 8 < n_strs {
+            return Err(invalid("truncated string table"));
+        }
+        let (ends, data) = rest.split_at(n_strs * 8);
+
+        Ok(Self {
+            inline: read_u32(bytes, 20).unwrap() != 0,
+            shard_shift: K::BITS - 1 - shard_bits,
+            n_shards,
+            shard_starts,
+            ends,
+            data,
+            _key: PhantomData,
+        })
+    }
+
+    /// Resolve a symbol of the saved table to its string.
+    ///
+    /// Returns `None` if the symbol isn't in the table, or if the bytes are
+    /// corrupted. Inline symbols other than
+    /// [`EMPTY`](Symbol::EMPTY) are only in tables saved
+    /// [with inline strings](SymbolTable::with_inline_strings).
+    pub fn resolve(&self, sym: K) -> Option<&'a str> {
+        let raw = sym.into_usize();
+        let inline_tag = 1 << (K::BITS - 1);
+        if raw & inline_tag != 0 {
+            let payload = u32::try_from(raw & !inline_tag).ok()?;
+            if !self.inline && payload != inline::EMPTY {
+                return None;
+            }
+            return inline::try_decode(payload);
+        }
+
+        let shard_i = raw >> self.shard_shift;
+        let idx = (raw & ((1 << self.shard_shift) - 1)).checked_sub(1)?;
+        if shard_i >= self.n_shards {
+            return None;
+        }
+        let shard_start = read_u32(self.shard_starts, shard_i * 4)? as usize;
+        let shard_end = read_u32(self.shard_starts, shard_i * 4 + 4)? as usize;
+        let i = shard_start.checked_add(idx).filter(|&i| i < shard_end)?;
+
+        let start = match i {
+            0 => 0,
+            _ => read_u64(self.ends, (i - 1) * 8)?,
+        };
+        let end = read_u64(self.ends, i * 8)?;
+        let bytes = self.data.get(start as usize..end as usize)?;
+        std::str::from_utf8(bytes).ok()
+    }
+
+    /// The number of strings stored in the table.
+    pub fn len(&self) -> usize {
+        self.ends.len() / 8
+    }
+
+    /// Whether the table holds no strings.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    let bytes = bytes.get(at..at + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> Option<u64> {
+    let bytes = bytes.get(at..at + 8)?;
+    Some(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
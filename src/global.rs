@@ -1,6 +1,12 @@
 use crate::*;
 
-use std::str::FromStr;
+use std::{
+    ffi::OsStr,
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::OnceLock,
+};
 
 #[cfg(feature = "global")]
 /// Macro for creating symbols from &'static str. Useful for commonly used symbols known at compile time.
@@ -29,58 +35,556 @@ macro_rules! static_symbol {
     }};
 }
 
-/// A interned string in the global symbol table.
+/// Macro for declaring many symbols from &'static strs at once, e.g. the
+/// keywords of a language.
+///
+/// Each `NAME = "string";` declares a static [`LazyLock`](std::sync::LazyLock)
+/// that interns its string on first use, so it dereferences to the
+/// [`GlobalSymbol`]. Attributes and a visibility can be given before each
+/// name.
+///
+/// # Examples
+///
+/// ```
+/// use symbol_table::{static_symbols, GlobalSymbol};
+///
+/// static_symbols! {
+///     /// The `fn` keyword.
+///     pub FN = "fn";
+///     LET = "let";
+/// }
+///
+/// assert_eq!(*FN, GlobalSymbol::from("fn"));
+/// assert_eq!(LET.as_str(), "let");
+/// ```
+#[macro_export]
+macro_rules! static_symbols {
+    ($($(#[$attr:meta])* $vis:vis $name:ident = $s:literal;)*) => {
+        $(
+            $(#[$attr])*
+            $vis static $name: ::std::sync::LazyLock<$crate::GlobalSymbol> =
+                ::std::sync::LazyLock::new(|| $crate::GlobalSymbol::from($s));
+        )*
+    };
+}
+
+/// A interned string in the global symbol table of a [`Namespace`].
 ///
 /// This requires the `global` feature on the crate.
 ///
-/// [`GlobalSymbol`] is a wrapper around [`Symbol`] that knows to refer to a
+/// [`NsSymbol`] is a wrapper around [`Symbol`] that knows to refer to a
 /// built-in, global [`SymbolTable`]. Strings into the global table are never freed.
 ///
 /// This enables a lot of convenience methods and trait implementations over
-/// [`GlobalSymbol`] (see below). In particular,
+/// [`NsSymbol`] (see below). In particular,
 ///   you can convert it to `&'static str`,
 ///   convert [`From`] and [`Into`] a `&str`,
 ///   and de/serialize using [`serde`](https://serde.rs) if the `serde` feature is enabled.
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize))]
-#[cfg_attr(feature = "serde", serde(into = "&'static str"))]
-pub struct GlobalSymbol(Symbol);
+///
+/// Every [`Namespace`] has its own global table, so the symbols of different
+/// namespaces never collide. A [`GlobalSymbol`] is in the
+/// [`DefaultNamespace`]; other namespaces are declared with
+/// [`symbol_namespace!`](crate::symbol_namespace):
+///
+/// ```
+/// use symbol_table::{symbol_namespace, GlobalSymbol, NsSymbol};
+///
+/// symbol_namespace!(Fields);
+/// type Field = NsSymbol<Fields>;
+///
+/// let field = Field::intern("x");
+/// assert_eq!(field.as_str(), "x");
+/// assert_eq!(field, Field::intern("x"));
+/// assert_ne!(field.as_str(), GlobalSymbol::new("y").as_str());
+/// ```
+pub struct NsSymbol<Ns>(Symbol, PhantomData<fn() -> Ns>);
+
+/// A interned string in the global symbol table of the [`DefaultNamespace`].
+///
+/// This requires the `global` feature on the crate. This is the symbol most
+/// code wants; see [`NsSymbol`] for its methods, and for symbols with their
+/// own global table.
+pub type GlobalSymbol = NsSymbol<DefaultNamespace>;
+
+/// A set of [`GlobalSymbol`]s that has its own global table.
+///
+/// This requires the `global` feature on the crate. Declare a namespace
+/// with [`symbol_namespace!`](crate::symbol_namespace), which gives it a
+/// static table.
+pub trait Namespace: 'static {
+    /// The global table of this namespace.
+    fn table() -> &'static SymbolTable;
+}
+
+/// The [`Namespace`] of a plain [`GlobalSymbol`].
+///
+/// This requires the `global` feature on the crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DefaultNamespace {}
+
+// set by `GlobalSymbol::init_with`, or to a default table on first use
+static SINGLETON: OnceLock<SymbolTable> = OnceLock::new();
+
+impl Namespace for DefaultNamespace {
+    fn table() -> &'static SymbolTable {
+        SINGLETON.get_or_init(SymbolTable::new)
+    }
+}
+
+/// Declare a [`Namespace`] for [`GlobalSymbol`]s, with its own static table.
+///
+/// This requires the `global` feature on the crate. The namespace is an
+/// uninhabited marker type; attributes and a visibility can be given before
+/// its name.
+///
+/// ```
+/// use symbol_table::{symbol_namespace, NsSymbol};
+///
+/// symbol_namespace!(
+///     /// The names of types.
+///     pub TypeNames
+/// );
+///
+/// let ty = NsSymbol::<TypeNames>::intern("u32");
+/// assert_eq!(ty.as_str(), "u32");
+/// ```
+#[macro_export]
+macro_rules! symbol_namespace {
+    ($(#[$attr:meta])* $vis:vis $name:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        $vis enum $name {}
+
+        impl $crate::Namespace for $name {
+            fn table() -> &'static $crate::SymbolTable {
+                static TABLE: $crate::SymbolTable = $crate::SymbolTable::new();
+                &TABLE
+            }
+        }
+    };
+}
+
+// Not derived, so they don't need the namespace to implement them.
+
+impl<Ns> Clone for NsSymbol<Ns> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Ns> Copy for NsSymbol<Ns> {}
+
+impl<Ns> PartialEq for NsSymbol<Ns> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<Ns> Eq for NsSymbol<Ns> {}
 
-impl From<NonZeroU32> for GlobalSymbol {
+impl<Ns> PartialOrd for NsSymbol<Ns> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<Ns> Ord for NsSymbol<Ns> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<Ns> std::hash::Hash for NsSymbol<Ns> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl<Ns> From<NonZeroU32> for NsSymbol<Ns> {
     fn from(n: NonZeroU32) -> Self {
-        Self(Symbol::from(n))
+        Self(Symbol::from(n), PhantomData)
     }
 }
 
-impl From<GlobalSymbol> for NonZeroU32 {
-    fn from(n: GlobalSymbol) -> Self {
+impl<Ns> From<NsSymbol<Ns>> for NonZeroU32 {
+    fn from(n: NsSymbol<Ns>) -> Self {
         n.0.into()
     }
 }
 
-static SINGLETON: SymbolTable = SymbolTable::new();
-
 impl GlobalSymbol {
     /// Intern a string into the global symbol table.
     pub fn new(s: impl AsRef<str>) -> Self {
         s.as_ref().into()
     }
 
+    /// Intern the [`Display`](std::fmt::Display)ed string of a value into
+    /// the global symbol table.
+    ///
+    /// Like [`SymbolTable::intern_fmt`], short strings are formatted on the
+    /// stack, so this doesn't allocate a [`String`] like
+    /// `GlobalSymbol::new(value.to_string())` would.
+    ///
+    /// ```
+    /// use symbol_table::GlobalSymbol;
+    ///
+    /// assert_eq!(GlobalSymbol::from_display(42), GlobalSymbol::new("42"));
+    /// assert_eq!(GlobalSymbol::from_display(-1.5).as_str(), "-1.5");
+    /// ```
+    pub fn from_display(value: impl std::fmt::Display) -> Self {
+        let table = DefaultNamespace::table();
+        NsSymbol(table.intern_fmt(format_args!("{value}")), PhantomData)
+    }
+
+    /// Intern the concatenation of `parts` into the global symbol table.
+    ///
+    /// Like [`from_display`](Self::from_display), short strings are built on
+    /// the stack.
+    ///
+    /// ```
+    /// use symbol_table::GlobalSymbol;
+    ///
+    /// let module = GlobalSymbol::new("std");
+    /// assert_eq!(GlobalSymbol::concat(&[&*module, "::", "vec"]), "std::vec");
+    /// ```
+    pub fn concat(parts: &[impl AsRef<str>]) -> Self {
+        Self::join("", parts)
+    }
+
+    /// Intern the strings of `parts`, separated by `sep`, into the global
+    /// symbol table.
+    ///
+    /// ```
+    /// use symbol_table::GlobalSymbol;
+    ///
+    /// let path = ["crate", "module", "func"].map(GlobalSymbol::new);
+    /// assert_eq!(GlobalSymbol::join("::", path), "crate::module::func");
+    /// ```
+    pub fn join<T: AsRef<str>>(sep: &str, parts: impl IntoIterator<Item = T>) -> Self {
+        use std::fmt::Write;
+        let mut buffer = format::FmtBuffer::new();
+        for (i, part) in parts.into_iter().enumerate() {
+            if i > 0 {
+                buffer.write_str(sep).unwrap();
+            }
+            buffer.write_str(part.as_ref()).unwrap();
+        }
+        GlobalSymbol::new(buffer.as_str())
+    }
+
+    /// Intern a new string that starts with `prefix` into the global symbol
+    /// table, see [`SymbolTable::fresh`].
+    ///
+    /// ```
+    /// use symbol_table::GlobalSymbol;
+    ///
+    /// let a = GlobalSymbol::fresh("gensym");
+    /// let b = GlobalSymbol::fresh("gensym");
+    /// assert_ne!(a, b);
+    /// assert!(a.starts_with("gensym"));
+    /// ```
+    pub fn fresh(prefix: &str) -> Self {
+        NsSymbol(DefaultNamespace::table().fresh(prefix), PhantomData)
+    }
+
+    /// The symbol of a string, if it's already in the global symbol table.
+    ///
+    /// A [`GlobalSymbol`] hashes as its number, not as its string, so it
+    /// can't implement `Borrow<str>`. To query a map keyed by symbols with a
+    /// string, look up its symbol with this instead, so strings that aren't
+    /// keys don't end up in the global table:
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use symbol_table::GlobalSymbol;
+    ///
+    /// let mut map = HashMap::new();
+    /// map.insert(GlobalSymbol::from("foo"), 1);
+    ///
+    /// let get = |s| GlobalSymbol::lookup(s).and_then(|sym| map.get(&sym));
+    /// assert_eq!(get("foo"), Some(&1));
+    /// assert_eq!(get("never interned"), None);
+    /// assert_eq!(GlobalSymbol::lookup("never interned"), None);
+    /// ```
+    pub fn lookup(s: &str) -> Option<Self> {
+        let sym = DefaultNamespace::table().lookup(s)?;
+        Some(NsSymbol(sym, PhantomData))
+    }
+
+    /// Intern a string whose hash is already known into the global symbol
+    /// table, see [`SymbolTable::intern_prehashed`].
+    ///
+    /// The hash is the [`SymbolTable::hash_str`] of a default table, e.g.
+    /// computed at compile time by the `symbol!` macro of the
+    /// `symbol_table_macros` crate.
+    pub fn new_prehashed(hash: u64, s: &str) -> Self {
+        NsSymbol(
+            DefaultNamespace::table().intern_prehashed(hash, s),
+            PhantomData,
+        )
+    }
+
+    /// Whether `probe_hash` is the hash of a probe string in this program, so
+    /// other hashes computed alongside it can be used.
+    ///
+    /// The `symbol!` macro checks this before using the hashes it computed
+    /// at compile time, in case the compiler hashes differently than the
+    /// program, e.g. with another version of `foldhash`.
+    #[doc(hidden)]
+    pub fn __hashes_match(probe_hash: u64) -> bool {
+        static PROBE: OnceLock<u64> = OnceLock::new();
+        *PROBE.get_or_init(|| DefaultNamespace::table().hash_str(__HASH_PROBE)) == probe_hash
+    }
+
+    /// Iterate over all the symbols in the global symbol table.
+    ///
+    /// This is a snapshot, see [`SymbolTable::symbols`]. For the table of
+    /// another [`Namespace`], use `Ns::table().symbols()`.
+    ///
+    /// ```
+    /// use symbol_table::GlobalSymbol;
+    ///
+    /// let foo = GlobalSymbol::new("foo");
+    /// assert!(GlobalSymbol::all().any(|sym| sym == foo));
+    /// for sym in GlobalSymbol::all() {
+    ///     println!("{sym}");
+    /// }
+    /// ```
+    pub fn all() -> impl Iterator<Item = GlobalSymbol> {
+        let table = DefaultNamespace::table();
+        table.symbols().map(|sym| NsSymbol(sym, PhantomData))
+    }
+
+    /// Count the strings in the global symbol table, see
+    /// [`SymbolTable::stats`].
+    ///
+    /// ```
+    /// use symbol_table::GlobalSymbol;
+    ///
+    /// GlobalSymbol::new("foo");
+    /// let stats = GlobalSymbol::table_stats();
+    /// assert!(stats.len >= 1 && stats.bytes >= 3);
+    /// ```
+    pub fn table_stats() -> TableStats {
+        DefaultNamespace::table().stats()
+    }
+
+    /// Use `table` as the global symbol table.
+    ///
+    /// This lets a program configure the global table once at startup, e.g.
+    /// with [`reserve`](SymbolTable::reserve)d room, limits, or a
+    /// [transform](SymbolTable::with_transform). Otherwise, a default
+    /// [`SymbolTable::new`] is used.
+    ///
+    /// The hasher and its seed can't be configured, and neither can the
+    /// number of shards `N`: they are part of the table's type, and the
+    /// `symbol!` macro of `symbol_table_macros` relies on the global table
+    /// hashing like a default one. How many of the 16 shards are used can
+    /// be, e.g. fewer on a small machine with
+    /// [`with_available_parallelism`](SymbolTable::with_available_parallelism).
+    /// A program that needs more shards, e.g. for many threads, can keep its
+    /// own table instead, like a `SymbolTable<128>` made `'static` with
+    /// [`leak`](SymbolTable::leak), which resolves symbols to
+    /// `&'static str`s as well.
+    ///
+    /// This has to be called before any [`GlobalSymbol`] is created; once the
+    /// global table is set, `table` is handed back as the error.
+    ///
+    /// ```
+    /// use symbol_table::{GlobalSymbol, SymbolTable};
+    ///
+    /// let table = SymbolTable::new()
+    ///     .with_max_str_len(1024)
+    ///     .with_available_parallelism();
+    /// table.reserve(100_000);
+    /// assert!(GlobalSymbol::init_with(table).is_ok());
+    ///
+    /// assert_eq!(GlobalSymbol::new("foo").as_str(), "foo");
+    /// assert!(GlobalSymbol::init_with(SymbolTable::new()).is_err());
+    /// ```
+    // hands the table back like `OnceLock::set`, since it was moved in
+    #[allow(clippy::result_large_err)]
+    pub fn init_with(table: SymbolTable) -> Result<(), SymbolTable> {
+        SINGLETON.set(table)
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl<Ns: Namespace> NsSymbol<Ns> {
+    /// Drop every string from the global table of this namespace, as if it
+    /// was new.
+    ///
+    /// This requires the `test-util` feature on the crate. It's meant for
+    /// test suites that assert on the contents of a global table, so each
+    /// test can start from a clean slate. Tests that run in parallel share
+    /// the global tables, so a test that resets one should have a
+    /// [namespace](crate::symbol_namespace) to itself.
+    ///
+    /// This resets the strings, the count of entries for
+    /// [`with_max_entries`](SymbolTable::with_max_entries), and the counter
+    /// of [`fresh`](GlobalSymbol::fresh) symbols. The table keeps its
+    /// configuration, e.g. from [`init_with`](GlobalSymbol::init_with). The
+    /// NUL-terminated copies that the C API makes of resolved strings aren't
+    /// dropped, but they are found by their contents, so they stay correct.
+    ///
+    /// ```
+    /// use symbol_table::GlobalSymbol;
+    ///
+    /// let foo = GlobalSymbol::new("foo");
+    /// unsafe { GlobalSymbol::reset_for_tests() };
+    ///
+    /// // `foo` must not be used anymore, but the string can be interned again
+    /// assert_eq!(GlobalSymbol::new("foo").as_str(), "foo");
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// This frees the strings of all the symbols of this namespace created
+    /// so far, so none of them, nor any `&'static str` resolved from them,
+    /// may be used afterwards. For the default namespace, that includes the
+    /// symbols cached in statics, which are never interned again:
+    ///
+    /// - the symbol of every [`static_symbol!`](crate::static_symbol) call,
+    ///   and of every `symbol!` call of `symbol_table_macros`,
+    /// - the symbols declared by [`static_symbols!`](crate::static_symbols),
+    /// - the [`KeywordTable`] of every [`keywords!`](crate::keywords)
+    ///   module, and of every enum with `#[derive(Symbols)]` of
+    ///   `symbol_table_macros`.
+    ///
+    /// Nothing may use the table while this runs.
+    pub unsafe fn reset_for_tests() {
+        Ns::table().reset();
+    }
+}
+
+impl<Ns> NsSymbol<Ns> {
+    /// The symbol of the empty string, see [`Symbol::EMPTY`].
+    ///
+    /// Every namespace has it, and interning the empty string gives it,
+    /// unless the table has a [transform](SymbolTable::with_transform) that
+    /// changes the empty string. Comparing with it doesn't touch the table.
+    ///
+    /// ```
+    /// use symbol_table::GlobalSymbol;
+    ///
+    /// assert_eq!(GlobalSymbol::from(""), GlobalSymbol::EMPTY);
+    /// assert_eq!(GlobalSymbol::EMPTY.as_str(), "");
+    /// ```
+    pub const EMPTY: Self = NsSymbol(Symbol::EMPTY, PhantomData);
+}
+
+impl<Ns: Namespace> NsSymbol<Ns> {
+    /// Intern a string into the global table of this symbol's namespace.
+    ///
+    /// For the [`DefaultNamespace`], this is the same as
+    /// [`GlobalSymbol::new`]. That and the conversions from strings are only
+    /// implemented for [`GlobalSymbol`], so `GlobalSymbol::from(s)` knows
+    /// which table to use.
+    pub fn intern(s: impl AsRef<str>) -> Self {
+        NsSymbol(Ns::table().intern(s.as_ref()), PhantomData)
+    }
+
     /// Convert this symbol into the string in the static, global symbol table.
     pub fn as_str(&self) -> &'static str {
         (*self).into()
     }
+
+    /// Copy the string of this symbol into a new [`String`].
+    pub fn to_owned_string(&self) -> String {
+        self.as_str().to_owned()
+    }
+
+    /// Intern the lowercase version of this symbol's string.
+    ///
+    /// If the string is already lowercase, this is just the symbol itself.
+    /// Otherwise, short strings are lowercased on the stack, like
+    /// [`GlobalSymbol::from_display`]. Every [`char`] is mapped on its own,
+    /// so unlike [`str::to_lowercase`], a final `'Σ'` becomes `'σ'`.
+    ///
+    /// ```
+    /// use symbol_table::GlobalSymbol;
+    ///
+    /// assert_eq!(GlobalSymbol::new("Hello World").to_lowercase(), "hello world");
+    /// ```
+    pub fn to_lowercase(self) -> Self {
+        let s = self.as_str();
+        if s.chars().flat_map(char::to_lowercase).eq(s.chars()) {
+            return self;
+        }
+        self.intern_mapped(|buffer| {
+            s.chars()
+                .flat_map(char::to_lowercase)
+                .for_each(|c| buffer.write_char(c).unwrap())
+        })
+    }
+
+    /// Intern the uppercase version of this symbol's string.
+    ///
+    /// Like [`to_lowercase`](Self::to_lowercase), this is the symbol itself
+    /// if the string is already uppercase.
+    ///
+    /// ```
+    /// use symbol_table::GlobalSymbol;
+    ///
+    /// assert_eq!(GlobalSymbol::new("Hello World").to_uppercase(), "HELLO WORLD");
+    /// ```
+    pub fn to_uppercase(self) -> Self {
+        let s = self.as_str();
+        if s.chars().flat_map(char::to_uppercase).eq(s.chars()) {
+            return self;
+        }
+        self.intern_mapped(|buffer| {
+            s.chars()
+                .flat_map(char::to_uppercase)
+                .for_each(|c| buffer.write_char(c).unwrap())
+        })
+    }
+
+    /// Intern the `snake_case` version of this symbol's string.
+    ///
+    /// The string is split into words at every character that isn't
+    /// alphanumeric, which is dropped, and before every uppercase character
+    /// that follows a lowercase one or a digit, or that starts a word after
+    /// an acronym. The words are lowercased and joined with `'_'`. If that
+    /// gives the same string, this is the symbol itself. `#[derive(Symbols)]`
+    /// of `symbol_table_macros` gives variants the same names.
+    ///
+    /// ```
+    /// use symbol_table::GlobalSymbol;
+    ///
+    /// assert_eq!(GlobalSymbol::new("parseHTTPResponse").to_snake_case(), "parse_http_response");
+    /// assert_eq!(GlobalSymbol::new("Vec2 - len").to_snake_case(), "vec2_len");
+    /// let snake = GlobalSymbol::new("already_snake");
+    /// assert_eq!(snake.to_snake_case(), snake);
+    /// ```
+    pub fn to_snake_case(self) -> Self {
+        if format::is_snake_case(self.as_str()) {
+            return self;
+        }
+        self.intern_mapped(|buffer| format::write_snake_case(self.as_str(), buffer).unwrap())
+    }
+
+    /// Intern the string written by `f`, unless it's the same as this
+    /// symbol's string.
+    fn intern_mapped(self, f: impl FnOnce(&mut format::FmtBuffer)) -> Self {
+        let mut buffer = format::FmtBuffer::new();
+        f(&mut buffer);
+        match buffer.as_str() {
+            s if s == self.as_str() => self,
+            s => Self::intern(s),
+        }
+    }
 }
 
 impl From<&str> for GlobalSymbol {
     fn from(s: &str) -> Self {
-        GlobalSymbol(SINGLETON.intern(s))
+        GlobalSymbol::intern(s)
     }
 }
 
 impl From<String> for GlobalSymbol {
     fn from(s: String) -> Self {
-        s.as_str().into()
+        NsSymbol(DefaultNamespace::table().intern_owned(s), PhantomData)
     }
 }
 
@@ -98,49 +602,531 @@ impl FromStr for GlobalSymbol {
     }
 }
 
-impl From<GlobalSymbol> for &'static str {
-    fn from(sym: GlobalSymbol) -> Self {
-        SINGLETON.resolve(sym.0)
+impl<Ns: Namespace> From<NsSymbol<Ns>> for &'static str {
+    fn from(sym: NsSymbol<Ns>) -> Self {
+        Ns::table().resolve(sym.0)
+    }
+}
+
+/// Points to the interned string, which is `'static`, instead of copying
+/// it. This requires the `smol_str` feature on the crate.
+///
+/// ```
+/// use smol_str::SmolStr;
+/// use symbol_table::GlobalSymbol;
+///
+/// let name = SmolStr::new("some_long_identifier_name");
+/// let sym = GlobalSymbol::from(&name);
+/// assert_eq!(SmolStr::from(sym), name);
+/// ```
+#[cfg(feature = "smol_str")]
+impl<Ns: Namespace> From<NsSymbol<Ns>> for smol_str::SmolStr {
+    fn from(sym: NsSymbol<Ns>) -> Self {
+        smol_str::SmolStr::new_static(sym.into())
+    }
+}
+
+/// This requires the `smol_str` feature on the crate.
+#[cfg(feature = "smol_str")]
+impl From<&smol_str::SmolStr> for GlobalSymbol {
+    fn from(s: &smol_str::SmolStr) -> Self {
+        s.as_str().into()
+    }
+}
+
+/// Points to the interned string, which is `'static`, instead of copying
+/// it, unless it's short enough to be inline. This requires the
+/// `compact_str` feature on the crate.
+///
+/// ```
+/// use compact_str::CompactString;
+/// use symbol_table::GlobalSymbol;
+///
+/// let name = CompactString::new("some_long_identifier_name");
+/// let sym = GlobalSymbol::from(&name);
+/// assert_eq!(CompactString::from(sym), name);
+/// ```
+#[cfg(feature = "compact_str")]
+impl<Ns: Namespace> From<NsSymbol<Ns>> for compact_str::CompactString {
+    fn from(sym: NsSymbol<Ns>) -> Self {
+        compact_str::CompactString::const_new(sym.into())
+    }
+}
+
+/// This requires the `compact_str` feature on the crate.
+#[cfg(feature = "compact_str")]
+impl From<&compact_str::CompactString> for GlobalSymbol {
+    fn from(s: &compact_str::CompactString) -> Self {
+        s.as_str().into()
+    }
+}
+
+/// A symbol is a [`valuable::Value::String`], so it can be inspected as a
+/// structured value, e.g. recorded by `tracing` as
+/// `field = sym.as_value()` with its unstable `valuable` support. This
+/// requires the `valuable` feature on the crate.
+///
+/// `tracing`'s own `Value` trait is sealed, so it can't be implemented here.
+/// Record `sym.as_str()` instead, a `&'static str` that's recorded as is,
+/// without going through `%sym` formatting.
+///
+/// ```
+/// use symbol_table::GlobalSymbol;
+/// use valuable::{Valuable, Value};
+///
+/// let sym = GlobalSymbol::from("request_id");
+/// assert!(matches!(sym.as_value(), Value::String("request_id")));
+/// ```
+#[cfg(feature = "valuable")]
+impl<Ns: Namespace> valuable::Valuable for NsSymbol<Ns> {
+    fn as_value(&self) -> valuable::Value<'_> {
+        valuable::Value::String(self.as_str())
+    }
+
+    fn visit(&self, visit: &mut dyn valuable::Visit) {
+        visit.visit_value(self.as_value());
+    }
+}
+
+/// Dereferences to the string, so `str` methods can be called directly.
+///
+/// ```
+/// use symbol_table::GlobalSymbol;
+///
+/// let sym = GlobalSymbol::from("_private");
+/// assert!(sym.starts_with('_'));
+/// assert_eq!(sym.len(), 8);
+/// ```
+impl<Ns: Namespace> std::ops::Deref for NsSymbol<Ns> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<Ns: Namespace> AsRef<str> for NsSymbol<Ns> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// Symbols can be used as paths directly, e.g. for interned path components.
+///
+/// ```
+/// use std::path::PathBuf;
+/// use symbol_table::GlobalSymbol;
+///
+/// let mut path = PathBuf::from("/usr");
+/// path.push(GlobalSymbol::from("lib"));
+/// assert_eq!(path, PathBuf::from("/usr/lib"));
+/// ```
+impl<Ns: Namespace> AsRef<Path> for NsSymbol<Ns> {
+    fn as_ref(&self) -> &Path {
+        Path::new(self.as_str())
+    }
+}
+
+impl<Ns: Namespace> AsRef<OsStr> for NsSymbol<Ns> {
+    fn as_ref(&self) -> &OsStr {
+        OsStr::new(self.as_str())
     }
 }
 
-impl std::fmt::Debug for GlobalSymbol {
+// Compare by string with the string types, in both directions.
+macro_rules! impl_partial_eq_str {
+    ($($ty:ty),*) => {$(
+        impl<Ns: Namespace> PartialEq<$ty> for NsSymbol<Ns> {
+            fn eq(&self, other: &$ty) -> bool {
+                self.as_str() == &other[..]
+            }
+        }
+
+        impl<Ns: Namespace> PartialEq<NsSymbol<Ns>> for $ty {
+            fn eq(&self, other: &NsSymbol<Ns>) -> bool {
+                &self[..] == other.as_str()
+            }
+        }
+    )*};
+}
+
+impl_partial_eq_str!(str, &str, String);
+
+impl<Ns: Namespace> std::fmt::Debug for NsSymbol<Ns> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Debug::fmt(self.as_str(), f)
     }
 }
 
-impl std::fmt::Display for GlobalSymbol {
+impl<Ns: Namespace> std::fmt::Display for NsSymbol<Ns> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Display::fmt(self.as_str(), f)
     }
 }
 
+/// A [`GlobalSymbol`] that is ordered by its string.
+///
+/// This requires the `global` feature on the crate.
+///
+/// A [`GlobalSymbol`] is ordered by its number, which depends on the order
+/// strings were interned in, and may differ between runs. Wrap it in this
+/// to order it like its string instead, e.g. for the keys of a
+/// [`BTreeMap`](std::collections::BTreeMap) or for sorted output. Comparing
+/// two of them resolves both strings.
+///
+/// ```
+/// use std::collections::BTreeSet;
+/// use symbol_table::{GlobalSymbol, OrderedGlobalSymbol};
+///
+/// let set: BTreeSet<_> = ["foo", "bar", "baz"]
+///     .into_iter()
+///     .map(|s| OrderedGlobalSymbol(GlobalSymbol::new(s)))
+///     .collect();
+/// let strs: Vec<&str> = set.iter().map(|sym| sym.as_str()).collect();
+/// assert_eq!(strs, ["bar", "baz", "foo"]);
+/// ```
+pub struct OrderedGlobalSymbol<Ns = DefaultNamespace>(pub NsSymbol<Ns>);
+
+impl<Ns> Clone for OrderedGlobalSymbol<Ns> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Ns> Copy for OrderedGlobalSymbol<Ns> {}
+
+// Every string is interned once, so the symbols are equal exactly when
+// their strings are, and `Eq` and `Hash` can still use the symbol.
+impl<Ns> PartialEq for OrderedGlobalSymbol<Ns> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<Ns> Eq for OrderedGlobalSymbol<Ns> {}
+
+impl<Ns: Namespace> PartialOrd for OrderedGlobalSymbol<Ns> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<Ns: Namespace> Ord for OrderedGlobalSymbol<Ns> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        Ns::table().cmp_str(self.0 .0, other.0 .0)
+    }
+}
+
+impl<Ns> std::hash::Hash for OrderedGlobalSymbol<Ns> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl<Ns> From<NsSymbol<Ns>> for OrderedGlobalSymbol<Ns> {
+    fn from(sym: NsSymbol<Ns>) -> Self {
+        OrderedGlobalSymbol(sym)
+    }
+}
+
+impl<Ns> From<OrderedGlobalSymbol<Ns>> for NsSymbol<Ns> {
+    fn from(sym: OrderedGlobalSymbol<Ns>) -> Self {
+        sym.0
+    }
+}
+
+impl<Ns> std::ops::Deref for OrderedGlobalSymbol<Ns> {
+    type Target = NsSymbol<Ns>;
+
+    fn deref(&self) -> &NsSymbol<Ns> {
+        &self.0
+    }
+}
+
+impl<Ns: Namespace> std::fmt::Debug for OrderedGlobalSymbol<Ns> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl<Ns: Namespace> std::fmt::Display for OrderedGlobalSymbol<Ns> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// A [`GlobalSymbol`] that is hashed by its string.
+///
+/// This requires the `global` feature on the crate.
+///
+/// A [`GlobalSymbol`] hashes its number, which depends on the order strings
+/// were interned in, so it may differ between processes. Wrap it in this to
+/// hash it exactly like its `str` instead, e.g. for hashes of structures
+/// that have to match across a distributed system. Hashing it resolves the
+/// string, and hashes all of it, so it's slower than hashing a symbol.
+///
+/// ```
+/// use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher};
+/// use symbol_table::{GlobalSymbol, StrHashGlobalSymbol};
+///
+/// let build_hasher = BuildHasherDefault::<DefaultHasher>::default();
+/// let sym = StrHashGlobalSymbol(GlobalSymbol::new("foo"));
+/// assert_eq!(build_hasher.hash_one(sym), build_hasher.hash_one("foo"));
+/// ```
+pub struct StrHashGlobalSymbol<Ns = DefaultNamespace>(pub NsSymbol<Ns>);
+
+impl<Ns> Clone for StrHashGlobalSymbol<Ns> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Ns> Copy for StrHashGlobalSymbol<Ns> {}
+
+// The symbols are equal exactly when their strings are, like for
+// `OrderedGlobalSymbol`, so only `Hash` needs the string.
+impl<Ns> PartialEq for StrHashGlobalSymbol<Ns> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<Ns> Eq for StrHashGlobalSymbol<Ns> {}
+
+impl<Ns: Namespace> std::hash::Hash for StrHashGlobalSymbol<Ns> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.as_str().hash(state)
+    }
+}
+
+impl<Ns> From<NsSymbol<Ns>> for StrHashGlobalSymbol<Ns> {
+    fn from(sym: NsSymbol<Ns>) -> Self {
+        StrHashGlobalSymbol(sym)
+    }
+}
+
+impl<Ns> From<StrHashGlobalSymbol<Ns>> for NsSymbol<Ns> {
+    fn from(sym: StrHashGlobalSymbol<Ns>) -> Self {
+        sym.0
+    }
+}
+
+impl<Ns> std::ops::Deref for StrHashGlobalSymbol<Ns> {
+    type Target = NsSymbol<Ns>;
+
+    fn deref(&self) -> &NsSymbol<Ns> {
+        &self.0
+    }
+}
+
+impl<Ns: Namespace> std::fmt::Debug for StrHashGlobalSymbol<Ns> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl<Ns: Namespace> std::fmt::Display for StrHashGlobalSymbol<Ns> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// An interned path in the global path table.
+///
+/// This requires the `global` feature on the crate.
+///
+/// This is like [`GlobalSymbol`], but for [`Path`]s, which aren't necessarily
+/// valid UTF-8. Paths are interned into a global [`OsSymbolTable`], separate
+/// from the one behind [`GlobalSymbol`], and are never freed.
+///
+/// ```
+/// use std::path::Path;
+/// use symbol_table::PathSymbol;
+///
+/// let sym = PathSymbol::from(Path::new("/etc/hosts"));
+/// assert_eq!(sym, PathSymbol::new("/etc/hosts"));
+/// assert_eq!(sym.as_path(), Path::new("/etc/hosts"));
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PathSymbol(Symbol);
+
+static PATH_SINGLETON: OsSymbolTable = OsSymbolTable::new();
+
+impl PathSymbol {
+    /// Intern a path into the global path table.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        path.as_ref().into()
+    }
+
+    /// Convert this symbol into the path in the static, global path table.
+    pub fn as_path(&self) -> &'static Path {
+        PATH_SINGLETON.resolve_path(self.0)
+    }
+
+    /// Convert this symbol into the [`OsStr`] in the static, global path table.
+    pub fn as_os_str(&self) -> &'static OsStr {
+        PATH_SINGLETON.resolve_os(self.0)
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl PathSymbol {
+    /// Drop every path from the global path table, as if it was new.
+    ///
+    /// This requires the `test-util` feature on the crate, and is like
+    /// [`GlobalSymbol::reset_for_tests`] for the path table, which is
+    /// separate.
+    ///
+    /// # Safety
+    ///
+    /// This frees the paths of all the [`PathSymbol`]s created so far, so
+    /// none of them, nor any `&'static Path` resolved from them, may be used
+    /// afterwards. Nothing may use the path table while this runs.
+    pub unsafe fn reset_for_tests() {
+        PATH_SINGLETON.reset();
+    }
+}
+
+impl From<&Path> for PathSymbol {
+    fn from(path: &Path) -> Self {
+        PathSymbol(PATH_SINGLETON.intern_path(path))
+    }
+}
+
+impl From<&OsStr> for PathSymbol {
+    fn from(s: &OsStr) -> Self {
+        PathSymbol(PATH_SINGLETON.intern_os(s))
+    }
+}
+
+impl From<PathBuf> for PathSymbol {
+    fn from(path: PathBuf) -> Self {
+        path.as_path().into()
+    }
+}
+
+impl From<PathSymbol> for &'static Path {
+    fn from(sym: PathSymbol) -> Self {
+        sym.as_path()
+    }
+}
+
+impl AsRef<Path> for PathSymbol {
+    fn as_ref(&self) -> &Path {
+        self.as_path()
+    }
+}
+
+impl AsRef<OsStr> for PathSymbol {
+    fn as_ref(&self) -> &OsStr {
+        self.as_os_str()
+    }
+}
+
+impl std::fmt::Debug for PathSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.as_path(), f)
+    }
+}
+
+/// Serializes the string, which is interned again when deserializing.
+#[cfg(feature = "borsh")]
+impl<Ns: Namespace> ::borsh::BorshSerialize for NsSymbol<Ns> {
+    fn serialize<W: ::borsh::io::Write>(&self, writer: &mut W) -> ::borsh::io::Result<()> {
+        ::borsh::BorshSerialize::serialize(self.as_str(), writer)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl<Ns: Namespace> ::borsh::BorshDeserialize for NsSymbol<Ns> {
+    fn deserialize_reader<R: ::borsh::io::Read>(reader: &mut R) -> ::borsh::io::Result<Self> {
+        let string = <String as ::borsh::BorshDeserialize>::deserialize_reader(reader)?;
+        Ok(NsSymbol(Ns::table().intern_owned(string), PhantomData))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<Ns: Namespace> ::serde::Serialize for NsSymbol<Ns> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        if !serializer.is_human_readable() {
+            if let Some(index) = crate::serde::registry::index_of(*self) {
+                return serializer.serialize_u32(index);
+            }
+        }
+        serializer.serialize_str(self.as_str())
+    }
+}
+
 #[cfg(feature = "serde")]
-struct StrVisitor;
+struct StrVisitor<Ns>(PhantomData<Ns>);
 
 #[cfg(feature = "serde")]
-impl serde::de::Visitor<'_> for StrVisitor {
-    type Value = GlobalSymbol;
+impl<Ns: Namespace> ::serde::de::Visitor<'_> for StrVisitor<Ns> {
+    type Value = NsSymbol<Ns>;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        formatter.write_str("a &str")
+        formatter.write_str("a string")
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
-        E: serde::de::Error,
+        E: ::serde::de::Error,
+    {
+        Ok(NsSymbol::intern(v))
+    }
+
+    fn visit_borrowed_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        Ok(NsSymbol::intern(v))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        Ok(NsSymbol(Ns::table().intern_owned(v), PhantomData))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        match std::str::from_utf8(v) {
+            Ok(s) => Ok(NsSymbol::intern(s)),
+            Err(_) => Err(E::invalid_value(::serde::de::Unexpected::Bytes(v), &self)),
+        }
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
     {
-        Ok(v.into())
+        match String::from_utf8(v) {
+            Ok(s) => self.visit_string(s),
+            Err(e) => Err(E::invalid_value(
+                ::serde::de::Unexpected::Bytes(e.as_bytes()),
+                &self,
+            )),
+        }
     }
 }
 
 #[cfg(feature = "serde")]
-impl<'de> serde::Deserialize<'de> for GlobalSymbol {
+impl<'de, Ns: Namespace> ::serde::Deserialize<'de> for NsSymbol<Ns> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
-        D: serde::Deserializer<'de>,
+        D: ::serde::Deserializer<'de>,
     {
-        deserializer.deserialize_str(StrVisitor)
+        use crate::serde::registry;
+        if !deserializer.is_human_readable() && registry::in_scope::<Ns>() {
+            let index = <u32 as ::serde::Deserialize>::deserialize(deserializer)?;
+            return registry::get(index);
+        }
+        deserializer.deserialize_str(StrVisitor(PhantomData))
     }
 }
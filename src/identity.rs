@@ -0,0 +1,68 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasherDefault, Hasher},
+};
+
+/// A [`Hasher`] for keys that are already unique numbers, like symbols.
+///
+/// A symbol hashes as its `u32`, and hashing that with a general-purpose
+/// hasher is wasted work. This hasher takes the number as it is, only
+/// multiplying it by a constant to spread its bits over the whole `u64`,
+/// since hash maps like [`HashMap`] use the top bits too.
+///
+/// It's meant for symbols, from any table, and for other integer keys;
+/// other keys are folded into a number a byte at a time, which is slow and
+/// hashes badly. Use it through [`SymbolHashMap`] and [`SymbolHashSet`]:
+///
+/// ```
+/// use symbol_table::{SymbolHashMap, SymbolTable};
+///
+/// let table = SymbolTable::new();
+/// let mut map = SymbolHashMap::default();
+/// map.insert(table.intern("foo"), 1);
+/// assert_eq!(map[&table.intern("foo")], 1);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IdentityHasher(u64);
+
+impl IdentityHasher {
+    // the golden ratio, as in Fibonacci hashing
+    const MULTIPLIER: u64 = 0x9e37_79b9_7f4a_7c15;
+}
+
+impl Hasher for IdentityHasher {
+    fn finish(&self) -> u64 {
+        self.0.wrapping_mul(Self::MULTIPLIER)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = self.0.rotate_left(8) ^ byte as u64;
+        }
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.write_u64(n as u64)
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = self.0.rotate_left(32) ^ n;
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.write_u64(n as u64)
+    }
+}
+
+/// A [`BuildHasher`](std::hash::BuildHasher) for [`IdentityHasher`]s.
+pub type BuildIdentityHasher = BuildHasherDefault<IdentityHasher>;
+
+/// A [`HashMap`] keyed by symbols, that doesn't hash them.
+///
+/// See [`IdentityHasher`]. Create one with `SymbolHashMap::default()`.
+pub type SymbolHashMap<K, V> = HashMap<K, V, BuildIdentityHasher>;
+
+/// A [`HashSet`] of symbols, that doesn't hash them.
+///
+/// See [`IdentityHasher`]. Create one with `SymbolHashSet::default()`.
+pub type SymbolHashSet<K> = HashSet<K, BuildIdentityHasher>;
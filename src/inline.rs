@@ -0,0 +1,73 @@
+//! Symbols for very short strings that are encoded directly in the symbol.
+//!
+//! An inline symbol has the top bit set to tag it, and a 16-bit payload
+//! holding the string's length in bits 14 and 15, and up to two 7-bit ASCII
+//! characters in the low 14 bits. Resolving an
+//! inline symbol never touches the table: the string is looked up in a static
+//! table of all one- and two-character ASCII strings.
+
+/// The longest string (in bytes) that can be inlined.
+pub(crate) const MAX_INLINE_LEN: usize = 2;
+
+const LEN_SHIFT: u32 = 14;
+
+static ONE_CHAR: [u8; 128] = {
+    let mut bytes = [0; 128];
+    let mut i = 0;
+    while i < 128 {
+        bytes[i] = i as u8;
+        i += 1;
+    }
+    bytes
+};
+
+static TWO_CHARS: [u8; 2 * 128 * 128] = {
+    let mut bytes = [0; 2 * 128 * 128];
+    let mut i = 0;
+    while i < 128 * 128 {
+        bytes[2 * i] = (i >> 7) as u8;
+        bytes[2 * i + 1] = (i & 0x7f) as u8;
+        i += 1;
+    }
+    bytes
+};
+
+/// The payload of the empty string, which is inline in every table.
+pub(crate) const EMPTY: u32 = 0;
+
+/// The symbol of the empty string, for tables that number their other
+/// symbols themselves.
+pub(crate) fn empty_symbol<K: crate::SymbolKey>() -> K {
+    K::try_from_usize(1 << (K::BITS - 1) | EMPTY as usize).unwrap()
+}
+
+/// Encode a string as an inline payload, if it's short enough and ASCII.
+#[inline]
+pub(crate) fn encode(bytes: &[u8]) -> Option<u32> {
+    if bytes.len() > MAX_INLINE_LEN || !bytes.is_ascii() {
+        return None;
+    }
+    let chars = bytes.iter().fold(0, |acc, &b| (acc << 7) | b as u32);
+    Some((bytes.len() as u32) << LEN_SHIFT | chars)
+}
+
+/// Decode an inline payload back into its string.
+#[inline]
+pub(crate) fn decode(payload: u32) -> &'static str {
+    let chars = (payload & ((1 << LEN_SHIFT) - 1)) as usize;
+    let bytes: &'static [u8] = match payload >> LEN_SHIFT {
+        0 => &[],
+        1 => &ONE_CHAR[chars..chars + 1],
+        2 => &TWO_CHARS[2 * chars..2 * chars + 2],
+        len => unreachable!("invalid inline symbol length {len}"),
+    };
+    // SAFETY: all the bytes in the static tables are ASCII
+    unsafe { std::str::from_utf8_unchecked(bytes) }
+}
+
+/// Decode an inline payload, or `None` if no string encodes to it.
+pub(crate) fn try_decode(payload: u32) -> Option<&'static str> {
+    let len = payload >> LEN_SHIFT;
+    let chars = payload & ((1 << LEN_SHIFT) - 1);
+    (len as usize <= MAX_INLINE_LEN && chars < 1 << (7 * len)).then(|| decode(payload))
+}
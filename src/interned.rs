@@ -0,0 +1,138 @@
+use crate::*;
+
+use std::ops::Deref;
+
+/// A symbol together with its string, from
+/// [`SymbolTable::intern_get`].
+///
+/// Code that needs both the symbol and the text of a string would otherwise
+/// resolve the symbol right after interning it, locking its shard again.
+/// This carries the string along instead, and dereferences to it, while it
+/// compares and hashes by the symbol, in constant time. So only compare
+/// strings interned into the same table.
+///
+/// ```
+/// let table = symbol_table::SymbolTable::new();
+/// let name = table.intern_get("foo");
+/// assert_eq!(name.symbol(), table.intern("foo"));
+/// assert_eq!(name.len(), 3);
+/// assert_eq!(name.to_string(), "foo");
+/// assert_eq!(name, table.intern_get("foo"));
+/// ```
+#[derive(Clone, Copy)]
+pub struct InternedStr<'a, K = Symbol> {
+    sym: K,
+    string: &'a str,
+}
+
+impl<'a, K: SymbolKey> InternedStr<'a, K> {
+    /// The symbol of the string.
+    pub fn symbol(&self) -> K {
+        self.sym
+    }
+
+    /// The string, which lives as long as its table.
+    pub fn as_str(&self) -> &'a str {
+        self.string
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Intern a string into the [`SymbolTable`], giving its symbol along
+    /// with the stored string.
+    ///
+    /// This is like [`intern`](Self::intern) followed by
+    /// [`resolve`](Self::resolve), but only locks the string's shard once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string exceeds the table's limits; use
+    /// [`try_intern_get`](Self::try_intern_get) to handle that instead.
+    pub fn intern_get(&self, string: &str) -> InternedStr<'_, K> {
+        unwrap_interned(self.try_intern_get(string))
+    }
+
+    /// Intern a string like [`intern_get`](Self::intern_get), unless that
+    /// would exceed the table's limits, or the string is invalid.
+    ///
+    /// This fails like [`try_intern`](Self::try_intern).
+    pub fn try_intern_get(&self, string: &str) -> Result<InternedStr<'_, K>, InternError> {
+        match self.transform.as_ref().map(|f| f(string)) {
+            None => self.try_intern_resolved(string),
+            Some(Cow::Borrowed(s)) => self.try_intern_resolved(s),
+            Some(Cow::Owned(s)) => self.try_intern_resolved(s),
+        }
+    }
+
+    fn try_intern_resolved(
+        &self,
+        string: impl Internable,
+    ) -> Result<InternedStr<'_, K>, InternError> {
+        self.check_str(string.as_ref())?;
+
+        if let Some(sym) = self.inline_symbol(string.as_ref()) {
+            let string = self.resolve(sym);
+            return Ok(InternedStr { sym, string });
+        }
+
+        let hash = hash_one(&self.build_hasher, string.as_ref());
+        let shard_i = shard_of_hash(hash, self.n_shards);
+        let mut locked = self.lock_shard(shard_i);
+        if let Some(sym) = Self::aliased(&locked, hash, string.as_ref()) {
+            // the canonical string may be in another shard
+            drop(locked);
+            let string = self.resolve(sym);
+            return Ok(InternedStr { sym, string });
+        }
+        let idx = self.intern_locked(&mut locked, shard_i, hash, string)?;
+        // SAFETY: the string outlives the lock like in `resolve_bytes`
+        let bytes = unsafe { backend::detach(locked.strs.get(idx)) };
+        drop(locked);
+
+        Ok(InternedStr {
+            sym: Self::make_symbol(shard_i, idx),
+            // SAFETY: only `str`s are interned, like in `resolve`
+            string: unsafe { std::str::from_utf8_unchecked(bytes) },
+        })
+    }
+}
+
+impl<K: SymbolKey> Deref for InternedStr<'_, K> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.string
+    }
+}
+
+impl<K: SymbolKey> AsRef<str> for InternedStr<'_, K> {
+    fn as_ref(&self) -> &str {
+        self.string
+    }
+}
+
+impl<K: SymbolKey> PartialEq for InternedStr<'_, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.sym == other.sym
+    }
+}
+
+impl<K: SymbolKey> Eq for InternedStr<'_, K> {}
+
+impl<K: SymbolKey> Hash for InternedStr<'_, K> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.sym.hash(state)
+    }
+}
+
+impl<K: SymbolKey> fmt::Debug for InternedStr<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.string, f)
+    }
+}
+
+impl<K: SymbolKey> fmt::Display for InternedStr<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.string, f)
+    }
+}
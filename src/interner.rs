@@ -0,0 +1,226 @@
+use crate::*;
+
+use hashbrown::{hash_table::Entry, HashTable};
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt::{self, Debug},
+};
+
+/// A sharded interner for any `Hash + Eq` values, not just strings.
+///
+/// This has the same design as a [`SymbolTable`]: it's sharded `N` times,
+/// interning only takes `&self`, and resolved values are stable references
+/// that live as long as the interner. [`SymbolTable`] is the specialization
+/// of this for `str`, which can store the unsized strings more compactly.
+///
+/// ```
+/// use symbol_table::Interner;
+///
+/// let interner = Interner::new();
+/// let pair = interner.intern(&(1, "one"));
+/// assert_eq!(interner.intern_owned((1, "one")), pair);
+/// assert_eq!(interner.resolve(pair), &(1, "one"));
+/// ```
+pub struct Interner<T, const N: usize = DEFAULT_N_SHARDS, S = DeterministicHashBuilder> {
+    build_hasher: S,
+    shards: [CachePadded<Mutex<InternerShard<T>>>; N],
+    // Resolved values are shared across threads outside of the locks.
+    _values: PhantomData<T>,
+}
+
+/// An interned value from an [`Interner<T>`].
+///
+/// Resolve it back to the value by using [`Interner::resolve`].
+pub struct Id<T> {
+    sym: Symbol,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T> Interner<T, DEFAULT_N_SHARDS, DeterministicHashBuilder> {
+    /// Creates a new [`Interner`] with the default generic arguments.
+    pub const fn new() -> Self {
+        Self::with_hasher(DeterministicHashBuilder)
+    }
+}
+
+impl<T, const N: usize, S> Interner<T, N, S> {
+    const SHARD_BITS: u32 = 32 - (N as u32 - 1).leading_zeros();
+    const SHARD_SHIFT: u32 = 31 - Self::SHARD_BITS;
+    const MAX_IDX: u32 = (1 << Self::SHARD_SHIFT) - 1;
+
+    /// Creates a new [`Interner`] with a custom hasher.
+    #[allow(clippy::assertions_on_constants)]
+    pub const fn with_hasher(build_hasher: S) -> Self {
+        assert!(0 < N);
+        assert!(N <= 1024);
+        Self {
+            build_hasher,
+            shards: [const { CachePadded::new(Mutex::new(InternerShard::new())) }; N],
+            _values: PhantomData,
+        }
+    }
+}
+
+impl<T: Hash + Eq, const N: usize, S: BuildHasher> Interner<T, N, S> {
+    /// Intern a value into the [`Interner`], cloning it if it's new.
+    pub fn intern(&self, value: &T) -> Id<T>
+    where
+        T: Clone,
+    {
+        self.intern_with(value, T::clone)
+    }
+
+    /// Intern an owned value into the [`Interner`].
+    pub fn intern_owned(&self, value: T) -> Id<T> {
+        self.intern_with(value, |value| value)
+    }
+
+    fn intern_with<V: Borrow<T>>(&self, value: V, into_owned: impl FnOnce(V) -> T) -> Id<T> {
+        let hash = self.build_hasher.hash_one(value.borrow());
+        let shard_i = hash as usize % N;
+        let mut shard = self.shards[shard_i].lock().unwrap();
+        let shard = &mut *shard;
+
+        let values = &shard.values;
+        let entry = shard.map.entry(
+            hash,
+            |&idx| values.get(idx) == value.borrow(),
+            |&idx| shard.hashes[idx as usize],
+        );
+        let idx = match entry {
+            Entry::Occupied(e) => *e.get(),
+            Entry::Vacant(e) => {
+                let idx = shard.values.push(into_owned(value));
+                shard.hashes.push(hash);
+                *e.insert(idx).get()
+            }
+        };
+
+        let i = idx + 1;
+        assert!(i < Self::MAX_IDX, "Can't represent index {} in an Id", i);
+        let raw = (shard_i as u32) << Self::SHARD_SHIFT | i;
+        Id {
+            sym: Symbol(NonZeroU32::new(raw).unwrap()),
+            _value: PhantomData,
+        }
+    }
+
+    /// Resolve an [`Id`] to the interned value.
+    ///
+    /// The resolved value is immutable and will live as long as the
+    /// [`Interner`].
+    pub fn resolve(&self, id: Id<T>) -> &T {
+        let raw = id.sym.0.get();
+        let shard_i = (raw >> Self::SHARD_SHIFT) as usize;
+        let i = (raw & Self::MAX_IDX) - 1;
+        let shard = self.shards[shard_i].lock().unwrap();
+        let value: &T = shard.values.get(i);
+        // SAFETY: values never move once they are pushed, see `Chunks`. They
+        // live as long as `self`.
+        unsafe { &*(value as *const T) }
+    }
+}
+
+impl<T, const N: usize, S: Default> Default for Interner<T, N, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+struct InternerShard<T> {
+    map: HashTable<u32>,
+    hashes: Vec<u64>,
+    values: Chunks<T>,
+}
+
+impl<T> InternerShard<T> {
+    const fn new() -> Self {
+        Self {
+            map: HashTable::new(),
+            hashes: Vec::new(),
+            values: Chunks {
+                chunks: Vec::new(),
+                len: 0,
+            },
+        }
+    }
+}
+
+/// The log2 of the capacity of the first chunk in [`Chunks`].
+const FIRST_CHUNK_BITS: u32 = 4;
+
+/// An append-only list whose elements never move.
+///
+/// Chunk `c` has a capacity of `2^(c + FIRST_CHUNK_BITS)`, and is never
+/// grown past that.
+struct Chunks<T> {
+    chunks: Vec<Vec<T>>,
+    len: u32,
+}
+
+impl<T> Chunks<T> {
+    fn locate(idx: u32) -> (usize, usize) {
+        let i = idx as u64 + (1 << FIRST_CHUNK_BITS);
+        let chunk = 63 - i.leading_zeros() - FIRST_CHUNK_BITS;
+        let offset = i - (1 << (chunk + FIRST_CHUNK_BITS));
+        (chunk as usize, offset as usize)
+    }
+
+    fn push(&mut self, value: T) -> u32 {
+        let idx = self.len;
+        let (chunk, _) = Self::locate(idx);
+        if chunk == self.chunks.len() {
+            self.chunks
+                .push(Vec::with_capacity(1 << (chunk as u32 + FIRST_CHUNK_BITS)));
+        }
+        self.chunks[chunk].push(value);
+        self.len += 1;
+        idx
+    }
+
+    fn get(&self, idx: u32) -> &T {
+        let (chunk, offset) = Self::locate(idx);
+        &self.chunks[chunk][offset]
+    }
+}
+
+impl<T> Clone for Id<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Id<T> {}
+
+impl<T> PartialEq for Id<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.sym == other.sym
+    }
+}
+
+impl<T> Eq for Id<T> {}
+
+impl<T> PartialOrd for Id<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Id<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sym.cmp(&other.sym)
+    }
+}
+
+impl<T> Hash for Id<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.sym.hash(state)
+    }
+}
+
+impl<T> Debug for Id<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Id").field(&self.sym.0).finish()
+    }
+}
@@ -0,0 +1,219 @@
+use crate::*;
+
+use std::{
+    fmt::Debug,
+    num::{NonZeroU16, NonZeroU64, NonZeroUsize},
+};
+
+/// The representation of the symbols handed out by a [`SymbolTable`].
+///
+/// This is picked by the table's `K` type parameter. The default is the
+/// 32-bit [`Symbol`]; small tables can use the 16-bit [`Symbol16`], and
+/// tables that need more room can use [`Symbol64`] (see [`SymbolTable64`])
+/// or [`SymbolUsize`].
+///
+/// A key is a nonzero integer of [`BITS`](SymbolKey::BITS) bits, so an
+/// `Option` of a key is niche-optimized.
+/// Fewer bits means fewer strings fit in each shard of the table: the top
+/// bit is reserved, and the shard number takes `log2(N)` more bits.
+///
+/// ```
+/// use symbol_table::{backend::StringBackend, DeterministicHashBuilder, Symbol16, SymbolTable};
+///
+/// let table = SymbolTable::<4, DeterministicHashBuilder, StringBackend, Symbol16>::default();
+/// let foo: Symbol16 = table.intern("foo");
+/// assert_eq!(table.resolve(foo), "foo");
+/// ```
+pub trait SymbolKey: Copy + Eq + Ord + Hash + Debug {
+    /// The number of bits in the symbol.
+    ///
+    /// This may not be more than `usize::BITS`.
+    const BITS: u32;
+
+    /// Create a symbol from its raw representation.
+    ///
+    /// Returns `None` if `raw` is zero, or doesn't fit in
+    /// [`BITS`](SymbolKey::BITS) bits.
+    fn try_from_usize(raw: usize) -> Option<Self>;
+
+    /// Get the raw representation of this symbol.
+    fn into_usize(self) -> usize;
+}
+
+macro_rules! impl_symbol_key {
+    ($sym:ident, $nonzero:ident, $int:ident, $bits:expr) => {
+        impl SymbolKey for $sym {
+            const BITS: u32 = $bits;
+
+            #[inline]
+            fn try_from_usize(raw: usize) -> Option<Self> {
+                if raw > usize::MAX >> (usize::BITS - Self::BITS) {
+                    return None;
+                }
+                $nonzero::new(raw as $int).map($sym)
+            }
+
+            #[inline]
+            fn into_usize(self) -> usize {
+                self.0.get() as usize
+            }
+        }
+
+        impl From<$nonzero> for $sym {
+            fn from(i: $nonzero) -> Self {
+                $sym(i)
+            }
+        }
+
+        impl From<$sym> for $nonzero {
+            fn from(sym: $sym) -> Self {
+                sym.0
+            }
+        }
+
+        /// Serializes the raw integer, see the [`serde`](crate::serde) module.
+        #[cfg(feature = "serde")]
+        impl ::serde::Serialize for $sym {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.0.serialize(serializer)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> ::serde::Deserialize<'de> for $sym {
+            fn deserialize<D: ::serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                $nonzero::deserialize(deserializer).map($sym)
+            }
+        }
+
+        /// Serializes the raw integer, like the `serde` impl.
+        #[cfg(feature = "borsh")]
+        impl ::borsh::BorshSerialize for $sym {
+            fn serialize<W: ::borsh::io::Write>(&self, writer: &mut W) -> ::borsh::io::Result<()> {
+                ::borsh::BorshSerialize::serialize(&self.0.get(), writer)
+            }
+        }
+
+        #[cfg(feature = "borsh")]
+        impl ::borsh::BorshDeserialize for $sym {
+            fn deserialize_reader<R: ::borsh::io::Read>(
+                reader: &mut R,
+            ) -> ::borsh::io::Result<Self> {
+                let raw = <$int as ::borsh::BorshDeserialize>::deserialize_reader(reader)?;
+                $nonzero::new(raw).map($sym).ok_or_else(|| {
+                    ::borsh::io::Error::new(::borsh::io::ErrorKind::InvalidData, "symbol is zero")
+                })
+            }
+        }
+
+        // SAFETY: the symbol is `repr(transparent)` over the nonzero
+        // integer, which has these impls too
+        #[cfg(feature = "bytemuck")]
+        unsafe impl ::bytemuck::NoUninit for $sym {}
+        #[cfg(feature = "bytemuck")]
+        unsafe impl ::bytemuck::ZeroableInOption for $sym {}
+        #[cfg(feature = "bytemuck")]
+        unsafe impl ::bytemuck::PodInOption for $sym {}
+        #[cfg(feature = "bytemuck")]
+        unsafe impl ::bytemuck::CheckedBitPattern for $sym {
+            type Bits = $int;
+
+            #[inline]
+            fn is_valid_bit_pattern(bits: &$int) -> bool {
+                *bits != 0
+            }
+        }
+    };
+}
+
+impl_symbol_key!(Symbol, NonZeroU32, u32, 32);
+impl_symbol_key!(Symbol16, NonZeroU16, u16, 16);
+impl_symbol_key!(
+    Symbol64,
+    NonZeroU64,
+    u64,
+    if usize::BITS < 64 { usize::BITS } else { 64 }
+);
+impl_symbol_key!(SymbolUsize, NonZeroUsize, usize, usize::BITS);
+
+/// An interned symbol that fits in 16 bits.
+///
+/// This is like [`Symbol`], but internally it's a [`NonZeroU16`]. It's meant
+/// for tiny tables: with the default 16 shards, each shard holds a bit under
+/// 2048 strings.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(
+        zerocopy::IntoBytes,
+        zerocopy::Immutable,
+        zerocopy::KnownLayout,
+        zerocopy::TryFromBytes
+    )
+)]
+#[repr(transparent)]
+pub struct Symbol16(NonZeroU16);
+
+/// An interned symbol from a [`SymbolTable64`].
+///
+/// This is like [`Symbol`], but internally it's a [`NonZeroU64`], leaving
+/// room for far more strings than fit in 32 bits.
+/// It's still niche-optimized.
+///
+/// On targets with 32-bit pointers, only the low 32 bits are used, since
+/// a table couldn't hold more strings than that anyway.
+///
+/// ```
+/// # use std::mem::size_of; use symbol_table::Symbol64;
+/// assert_eq!(size_of::<Option<Symbol64>>(), size_of::<u64>());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(
+        zerocopy::IntoBytes,
+        zerocopy::Immutable,
+        zerocopy::KnownLayout,
+        zerocopy::TryFromBytes
+    )
+)]
+#[repr(transparent)]
+pub struct Symbol64(NonZeroU64);
+
+/// An interned symbol that's as wide as a pointer.
+///
+/// This is like [`Symbol`], but internally it's a [`NonZeroUsize`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(
+        zerocopy::IntoBytes,
+        zerocopy::Immutable,
+        zerocopy::KnownLayout,
+        zerocopy::TryFromBytes
+    )
+)]
+#[repr(transparent)]
+pub struct SymbolUsize(NonZeroUsize);
+
+/// A [`SymbolTable`] that hands out 64-bit [`Symbol64`]s.
+///
+/// A 32-bit [`Symbol`] has to fit the shard number and the index within the
+/// shard, which caps each of the default 16 shards at 2^27 - 2 strings. This
+/// table is only limited by the [`Backend`](crate::backend::Backend), which
+/// indexes its strings with a `u32`, so each shard can hold 2^32 strings.
+///
+/// ```
+/// use symbol_table::SymbolTable64;
+///
+/// let table: SymbolTable64 = SymbolTable64::default();
+/// let foo = table.intern("foo");
+/// assert_eq!(table.resolve(foo), "foo");
+/// ```
+pub type SymbolTable64<
+    const N: usize = DEFAULT_N_SHARDS,
+    S = DeterministicHashBuilder,
+    B = StringBackend,
+> = SymbolTable<N, S, B, Symbol64>;
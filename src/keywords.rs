@@ -0,0 +1,140 @@
+use crate::*;
+
+/// Macro for declaring a fixed list of keywords, with consecutive index
+/// constants that can be matched on.
+///
+/// This requires the `global` feature on the crate.
+///
+/// The symbols of interned strings depend on their hashes, so they can't be
+/// constants. So the constants this declares aren't symbols, but the
+/// *indices* of the keywords: it declares a module with a `usize` constant
+/// for every keyword, numbered `0, 1, 2, ...` in order, and a `table()` that
+/// interns all of them into the global table on first use (see
+/// [`KeywordTable`]). A [`GlobalSymbol`] is matched against the constants
+/// through its index, from `index_of`, and `symbol` gives the symbol of an
+/// index.
+///
+/// ```
+/// use symbol_table::{keywords, GlobalSymbol};
+///
+/// keywords! {
+///     /// The keywords of our language.
+///     pub mod kw {
+///         FN = "fn",
+///         LET = "let",
+///     }
+/// }
+///
+/// let sym = GlobalSymbol::new("let");
+/// match kw::index_of(sym) {
+///     Some(kw::FN) => panic!("not fn"),
+///     Some(kw::LET) => {}
+///     _ => panic!("not a keyword"),
+/// }
+/// assert_eq!(kw::LET, 1);
+/// assert_eq!(kw::symbol(kw::FN), GlobalSymbol::new("fn"));
+/// assert_eq!(kw::STRINGS, ["fn", "let"]);
+/// ```
+#[macro_export]
+macro_rules! keywords {
+    (
+        $(#[$mod_attr:meta])*
+        $vis:vis mod $module:ident {
+            $($(#[$attr:meta])* $name:ident = $s:literal),* $(,)?
+        }
+    ) => {
+        $(#[$mod_attr])*
+        $vis mod $module {
+            #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+            enum Index {
+                $($name,)*
+            }
+
+            $(
+                #[doc = ::std::concat!("The index of the keyword `", $s, "`.")]
+                ///
+                $(#[$attr])*
+                pub const $name: usize = Index::$name as usize;
+            )*
+
+            /// The strings of the keywords, by index.
+            pub const STRINGS: &[&str] = &[$($s),*];
+
+            /// The keywords, interned into the global table on first use.
+            pub fn table() -> &'static $crate::KeywordTable {
+                static TABLE: ::std::sync::OnceLock<$crate::KeywordTable> =
+                    ::std::sync::OnceLock::new();
+                TABLE.get_or_init(|| $crate::KeywordTable::new(STRINGS))
+            }
+
+            /// The symbol of the keyword at `index`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is out of bounds.
+            pub fn symbol(index: usize) -> $crate::GlobalSymbol {
+                table().symbol(index)
+            }
+
+            /// The index of the keyword `sym`, or `None` if it isn't one.
+            pub fn index_of(sym: $crate::GlobalSymbol) -> ::std::option::Option<usize> {
+                table().index_of(sym)
+            }
+        }
+    };
+}
+
+/// A fixed list of keywords, interned into the global table.
+///
+/// This requires the `global` feature on the crate. It's usually created by
+/// the [`keywords!`](crate::keywords) macro.
+///
+/// Every keyword has an index, its position in the list, and a
+/// [`GlobalSymbol`]. Since keyword lists are short, the index of a symbol is
+/// found with a binary search.
+#[derive(Debug, Clone)]
+pub struct KeywordTable {
+    symbols: Vec<GlobalSymbol>,
+    // every (symbol, index), sorted by symbol
+    indices: Vec<(GlobalSymbol, usize)>,
+}
+
+impl KeywordTable {
+    /// Intern the keywords, in order, into the global table.
+    ///
+    /// A string that's in the list more than once gets the index of its
+    /// first occurrence.
+    pub fn new(keywords: &[&str]) -> Self {
+        let symbols: Vec<GlobalSymbol> = keywords.iter().map(GlobalSymbol::new).collect();
+        let mut indices: Vec<_> = symbols.iter().copied().zip(0..).collect();
+        // a stable sort, so duplicates stay in order for the dedup
+        indices.sort_by_key(|&(sym, _)| sym);
+        indices.dedup_by_key(|&mut (sym, _)| sym);
+        Self { symbols, indices }
+    }
+
+    /// The symbol of the keyword at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn symbol(&self, index: usize) -> GlobalSymbol {
+        self.symbols[index]
+    }
+
+    /// The index of the keyword `sym`, or `None` if it isn't one.
+    pub fn index_of(&self, sym: GlobalSymbol) -> Option<usize> {
+        let i = self.indices.binary_search_by_key(&sym, |&(s, _)| s).ok()?;
+        Some(self.indices[i].1)
+    }
+
+    /// The number of keywords.
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Whether there are no keywords.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+}
@@ -9,24 +9,138 @@ With the `global` feature enabled, the
  provided [`GlobalSymbol`] type
  provides a lot of convenience methods and trait implementations
  for converting to/from strings.
+
+With the `loom` feature enabled, and built with `--cfg loom`, the tables
+ lock and count with [`loom`](https://docs.rs/loom)'s primitives
+ instead of `std`'s, so code using them can be model checked.
+ The tables then have to be created and used inside `loom::model`.
 */
 
+mod alias;
+mod arc;
+pub mod backend;
+mod borrowed;
+#[cfg(feature = "borsh")]
+mod borsh;
+mod bytes;
+#[cfg(feature = "check")]
+mod check;
+mod checkpoint;
+#[cfg(any(feature = "lasso", feature = "string-interner"))]
+pub mod compat;
+#[cfg(feature = "counts")]
+mod counts;
+mod cstr;
+mod debug;
+mod dense;
+mod diff;
+mod display;
+mod eq;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod format;
+mod frozen;
 #[cfg(feature = "global")]
 mod global;
+mod identity;
+mod inline;
+mod interned;
+mod interner;
+mod key;
+#[cfg(feature = "global")]
+mod keywords;
+mod local;
+// also built for its tests, since CI doesn't run on such targets
+#[cfg(any(test, all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+mod lock;
+mod log;
+mod lru;
+mod map;
+mod os;
+mod persist;
+mod pin;
+mod portable;
+mod rc;
+mod remap;
+mod scoped;
+mod search;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod set;
+mod shared;
+mod snapshot;
+mod sorted;
+mod staging;
+mod stats;
+mod stream;
+mod sync;
+mod trace;
+mod traits;
+pub mod validate;
+pub use borrowed::BorrowedSymbolTable;
+pub use bytes::ByteSymbolTable;
+pub use checkpoint::Checkpoint;
+pub use cstr::CStrSymbolTable;
+pub use dense::DenseSymbolTable;
+pub use diff::TableDiff;
+pub use display::SymbolDisplay;
+pub use error::{InternError, InvalidStr};
+pub use format::SymbolBuilder;
+pub use frozen::FrozenSymbolTable;
+#[cfg(feature = "global")]
+pub use global::{
+    DefaultNamespace, GlobalSymbol, Namespace, NsSymbol, OrderedGlobalSymbol, PathSymbol,
+    StrHashGlobalSymbol,
+};
+pub use identity::{BuildIdentityHasher, IdentityHasher, SymbolHashMap, SymbolHashSet};
+pub use interned::InternedStr;
+pub use interner::{Id, Interner};
+pub use key::{Symbol16, Symbol64, SymbolKey, SymbolTable64, SymbolUsize};
 #[cfg(feature = "global")]
-pub use global::GlobalSymbol;
+pub use keywords::KeywordTable;
+pub use local::LocalSymbolTable;
+pub use lru::{LruSymbol, LruSymbolTable};
+pub use map::SymbolMap;
+pub use os::OsSymbolTable;
+pub use portable::{PortableHashBuilder, PortableHasher, PortableSymbolTable};
+pub use rc::{RcSymbol, RcSymbolTable};
+pub use remap::SymbolRemap;
+pub use scoped::ScopedSymbolTable;
+pub use set::SymbolSet;
+pub use shared::SharedSymbolTable;
+pub use snapshot::TableSnapshot;
+pub use sorted::SortedIndex;
+pub use staging::StagingTable;
+pub use stats::TableStats;
+pub use stream::{SymbolDecoder, SymbolEncoder};
+pub use traits::{Intern, Resolve};
 
 use std::{
+    borrow::Cow,
+    fmt,
     hash::{BuildHasher, Hash},
+    io::Write,
+    marker::PhantomData,
     num::NonZeroU32,
+    ops::DerefMut,
+    panic::{RefUnwindSafe, UnwindSafe},
+    slice::SliceIndex,
+    sync::{atomic::Ordering, Arc},
 };
 
-use crossbeam_utils::CachePadded;
+use backend::{Backend, StringBackend};
 use hashbrown::hash_map::{HashMap, RawEntryMut};
-use std::sync::Mutex;
+use sync::{AtomicUsize, CachePadded, Mutex};
 
 /// A `BuildHasher` that builds a determinstically seeded hasher.
-#[derive(Default)]
+///
+/// This is the default hasher of a [`SymbolTable`], a `foldhash` hasher
+/// with a fixed seed. It's also available as [`FoldHashBuilder`], next to
+/// the other presets like `FxHashBuilder`. Its hashes, and so the symbols,
+/// can differ between platforms and versions of `foldhash`; use the
+/// [`PortableHashBuilder`] for symbols that don't.
+#[derive(Default, Clone, Copy)]
 pub struct DeterministicHashBuilder;
 
 impl BuildHasher for DeterministicHashBuilder {
@@ -36,6 +150,55 @@ impl BuildHasher for DeterministicHashBuilder {
     }
 }
 
+/// The `foldhash` preset, the same as [`DeterministicHashBuilder`].
+pub type FoldHashBuilder = DeterministicHashBuilder;
+
+/// The `FxHash` preset, the hasher of `rustc`, from the `rustc-hash` crate.
+///
+/// It's deterministic too, and compiles to less code than `foldhash`, but
+/// it may collide more on some inputs. This requires the `fxhash` feature
+/// on the crate. Pick it for a table with [`FxSymbolTable`], or pass it as
+/// the hasher of any other table:
+///
+/// ```
+/// use symbol_table::{FxHashBuilder, LocalSymbolTable};
+///
+/// let table: LocalSymbolTable<4, _> = LocalSymbolTable::with_hasher(FxHashBuilder::default());
+/// let foo = table.intern("foo");
+/// assert_eq!(table.resolve(foo), "foo");
+/// ```
+#[cfg(feature = "fxhash")]
+pub type FxHashBuilder = rustc_hash::FxBuildHasher;
+
+/// A [`SymbolTable`] with the [`FxHashBuilder`] preset.
+///
+/// This requires the `fxhash` feature on the crate.
+///
+/// ```
+/// use symbol_table::FxSymbolTable;
+///
+/// let table: FxSymbolTable = FxSymbolTable::default();
+/// let foo = table.intern("foo");
+/// assert_eq!(table.resolve(foo), "foo");
+/// ```
+#[cfg(feature = "fxhash")]
+pub type FxSymbolTable<const N: usize = DEFAULT_N_SHARDS, B = StringBackend, K = Symbol> =
+    SymbolTable<N, FxHashBuilder, B, K>;
+
+// hashed by the `symbol!` macro of `symbol_table_macros`, to check that it
+// hashes like the program it's used in
+#[doc(hidden)]
+pub const __HASH_PROBE: &str = "symbol_table";
+
+// the names that `#[derive(Symbols)]` of `symbol_table_macros` gives enum
+// variants, the same as `GlobalSymbol::to_snake_case`
+#[doc(hidden)]
+pub fn __snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    format::write_snake_case(name, &mut snake).unwrap();
+    snake
+}
+
 /// The default number of sharded in the [`SymbolTable`].
 pub const DEFAULT_N_SHARDS: usize = 16;
 
@@ -43,14 +206,150 @@ pub const DEFAULT_N_SHARDS: usize = 16;
 ///
 /// The table is sharded `N` times (default [`DEFAULT_N_SHARDS`])
 /// for lower contention when accessing concurrently.
-pub struct SymbolTable<const N: usize = DEFAULT_N_SHARDS, S = DeterministicHashBuilder> {
+///
+/// Every string goes into the shard picked by its hash, whichever thread
+/// interns it, so interning checks for duplicates, and resolving finds the
+/// string, with a single lock. For insert-heavy workloads on many threads,
+/// use more shards, like `SymbolTable<128>`, intern in batches with
+/// [`intern_many`](SymbolTable::intern_many), which locks every shard once
+/// per batch, or give each thread its own shard for new strings with
+/// [`with_thread_affinity`](SymbolTable::with_thread_affinity).
+///
+/// Interning takes longest when it makes a shard grow. With the `tracing`
+/// feature, every time a shard's map or its vector of strings reallocates,
+/// the table emits a [`tracing`](https://docs.rs/tracing) event at the
+/// `DEBUG` level, with the `shard` index and the new `capacity`. To grow
+/// the shards up front instead, use [`reserve`](SymbolTable::reserve).
+///
+/// The strings themselves are kept in a [`Backend`] (default
+/// [`StringBackend`]); see the [`backend`] module for the alternatives.
+///
+/// The symbols are [`Symbol`]s by default; see [`SymbolKey`] for the
+/// alternatives.
+pub struct SymbolTable<
+    const N: usize = DEFAULT_N_SHARDS,
+    S = DeterministicHashBuilder,
+    B = StringBackend,
+    K = Symbol,
+> {
     build_hasher: S,
-    shards: [CachePadded<Mutex<Shard>>; N],
+    inline: bool,
+    // how many of the `N` shards strings are spread over
+    n_shards: usize,
+    // whether new strings go into the shard of the thread interning them
+    thread_affinity: bool,
+    transform: Option<Transform>,
+    validator: Option<Validator>,
+    max_str_len: usize,
+    max_entries: usize,
+    // only counted when `max_entries` is set
+    entries: AtomicUsize,
+    // the next number to try for a `fresh` symbol
+    fresh: AtomicUsize,
+    log: Option<Mutex<Box<dyn Write + Send>>>,
+    on_intern: Option<OnIntern<K>>,
+    shards: [CachePadded<Mutex<Shard<B>>>; N],
+    _key: PhantomData<K>,
 }
 
-impl<const N: usize, S> SymbolTable<N, S> {
+/// A canonicalization hook run on strings before they are interned.
+// unwind-safe, so that tables stay `UnwindSafe` and `RefUnwindSafe`
+type Transform =
+    Arc<dyn for<'a> Fn(&'a str) -> Cow<'a, str> + Send + Sync + RefUnwindSafe + UnwindSafe>;
+
+/// A check run on strings before they are interned, after the transform.
+type Validator =
+    Arc<dyn Fn(&str) -> Result<(), InvalidStr> + Send + Sync + RefUnwindSafe + UnwindSafe>;
+
+/// A callback run on every new string, with its symbol.
+type OnIntern<K> = Box<dyn Fn(K, &str) + Send + Sync + RefUnwindSafe + UnwindSafe>;
+
+impl<const N: usize, S, B, K: SymbolKey> SymbolTable<N, S, B, K> {
     const SHARD_BITS: u32 = 32 - (N as u32 - 1).leading_zeros();
-    const MAX_IDX: u32 = u32::MAX >> Self::SHARD_BITS;
+    // the top bit is reserved to tag inline symbols
+    const INLINE_TAG: usize = 1 << (K::BITS - 1);
+    const SHARD_SHIFT: u32 = K::BITS - 1 - Self::SHARD_BITS;
+    const MAX_IDX: usize = (1 << Self::SHARD_SHIFT) - 1;
+
+    /// The symbol for the string at `idx` in shard `shard_i`.
+    fn make_symbol(shard_i: usize, idx: u32) -> K {
+        let i = idx as usize + 1;
+        assert!(i < Self::MAX_IDX, "Can't represent index {} in a Symbol", i);
+        let shard_bits = shard_i << Self::SHARD_SHIFT;
+        // println!("shard_bits = {shard_bits:x}");
+        K::try_from_usize(shard_bits | i).unwrap()
+    }
+
+    /// The inline symbol of a string, if it has one: the empty string always
+    /// does, and other short strings do with `with_inline_strings`.
+    pub(crate) fn inline_symbol(&self, string: &[u8]) -> Option<K> {
+        let payload = inline::encode(string).filter(|&p| self.inline || p == inline::EMPTY)?;
+        Some(K::try_from_usize(Self::INLINE_TAG | payload as usize).unwrap())
+    }
+
+    /// The shard and index of a symbol, undoing
+    /// [`make_symbol`](Self::make_symbol), or `None` for an inline symbol.
+    pub(crate) fn split_symbol(sym: K) -> Option<(usize, u32)> {
+        let raw = sym.into_usize();
+        if raw & Self::INLINE_TAG != 0 {
+            return None;
+        }
+        let idx = (raw & Self::MAX_IDX) - 1;
+        Some((raw >> Self::SHARD_SHIFT, idx as u32))
+    }
+
+    /// The shard of the table that the string of `sym` is stored in, from
+    /// `0` to `N - 1`.
+    ///
+    /// A symbol is made of its shard and its [index in the
+    /// shard](Self::index_in_shard), and how they're packed depends on the
+    /// number of shards `N`, so this only makes sense for symbols of a table
+    /// with the same `N`. Work on symbols can be partitioned by shard the
+    /// same way the table partitions its strings, e.g. to give each thread
+    /// its own shards:
+    ///
+    /// ```
+    /// use symbol_table::SymbolTable;
+    ///
+    /// let table = SymbolTable::new();
+    /// let syms: Vec<_> = ["a", "b", "c", "d"].map(|s| table.intern(s)).into();
+    ///
+    /// let mut by_shard = vec![Vec::new(); 16];
+    /// for &sym in &syms {
+    ///     by_shard[table.shard_of(sym)].push(table.index_in_shard(sym));
+    /// }
+    /// assert_eq!(by_shard.iter().map(Vec::len).sum::<usize>(), 4);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sym` is an [inline](Self::with_inline_strings) symbol,
+    /// whose string isn't stored in a shard. That includes
+    /// [`Symbol::EMPTY`], the symbol of the empty string in every table, so
+    /// check for it first if `sym` may be of `""`.
+    pub fn shard_of(&self, sym: K) -> usize {
+        Self::split_symbol(sym)
+            .expect("Inline symbols aren't in a shard")
+            .0
+    }
+
+    /// The index of the string of `sym` in [its shard](Self::shard_of).
+    ///
+    /// The strings of a shard get consecutive indices from `0`, in the
+    /// order they were interned, so per-shard side tables can be `Vec`s
+    /// indexed by this, like in a [`SymbolMap`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sym` is an [inline](Self::with_inline_strings) symbol,
+    /// whose string isn't stored in a shard. That includes
+    /// [`Symbol::EMPTY`], the symbol of the empty string in every table, so
+    /// check for it first if `sym` may be of `""`.
+    pub fn index_in_shard(&self, sym: K) -> u32 {
+        Self::split_symbol(sym)
+            .expect("Inline symbols aren't in a shard")
+            .1
+    }
 }
 
 impl SymbolTable<DEFAULT_N_SHARDS, DeterministicHashBuilder> {
@@ -65,80 +364,647 @@ impl SymbolTable<DEFAULT_N_SHARDS, DeterministicHashBuilder> {
     }
 }
 
-impl<const N: usize, S: BuildHasher> SymbolTable<N, S> {
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
     /// Creates a new [`SymbolTable`] with a custom hasher.
     #[allow(clippy::assertions_on_constants)]
     pub const fn with_hasher(build_hasher: S) -> Self {
-        // Only used for array initialization.
-        // Replace with an inline-const block when it's been stable for long enough.
-        #[allow(clippy::declare_interior_mutable_const)]
-        const SHARD_INIT: CachePadded<Mutex<Shard>> = CachePadded::new(Mutex::new(Shard::new()));
-
         assert!(0 < N);
         assert!(N <= 1024);
+        assert!(K::BITS <= usize::BITS);
+        assert!(Self::SHARD_BITS < K::BITS - 1);
         // println!("N = {}", N);
         // println!("SHARD_BITS = {}", Self::SHARD_BITS);
         // println!("MAX_IDX = {}", Self::MAX_IDX);
         Self {
             build_hasher,
-            shards: [SHARD_INIT; N],
+            inline: false,
+            n_shards: N,
+            thread_affinity: false,
+            transform: None,
+            validator: None,
+            max_str_len: usize::MAX,
+            max_entries: usize::MAX,
+            entries: AtomicUsize::new(0),
+            fresh: AtomicUsize::new(0),
+            log: None,
+            on_intern: None,
+            shards: [const { CachePadded::new(Mutex::new(Shard::new())) }; N],
+            _key: PhantomData,
         }
     }
+
+    /// Encode very short strings directly in their [`Symbol`]s.
+    ///
+    /// With this enabled, strings of one or two ASCII characters (think
+    /// operator names like `+` or `<=`) are never stored in the table: both
+    /// [`intern`](Self::intern) and [`resolve`](Self::resolve) handle them
+    /// without taking any locks. The empty string is always inline, see
+    /// [`Symbol::EMPTY`].
+    ///
+    /// ```
+    /// use symbol_table::SymbolTable;
+    ///
+    /// let table = SymbolTable::new().with_inline_strings();
+    /// let le = table.intern("<=");
+    /// assert_eq!(table.resolve(le), "<=");
+    /// assert_eq!(le, SymbolTable::new().with_inline_strings().intern("<="));
+    /// ```
+    ///
+    /// The top bit of every symbol is reserved to tag inline symbols, whether
+    /// this is enabled or not. So since 0.5, a shard holds half as many
+    /// strings as before, and symbols from other shards than the first have
+    /// different numbers than they used to.
+    ///
+    /// # Panics
+    ///
+    /// Inline symbols need at least 17 bits, so this panics with a 16-bit
+    /// [`SymbolKey`].
+    pub const fn with_inline_strings(mut self) -> Self {
+        assert!(K::BITS > 16, "Inline symbols need more than 16 bits");
+        self.inline = true;
+        self
+    }
+
+    /// Spread the strings over only the first `n` of the `N` shards.
+    ///
+    /// `N` is fixed at compile time, since it decides how many bits of every
+    /// symbol go to the shard, but how many shards are worth their memory
+    /// depends on how many threads intern at once, which often isn't known
+    /// until runtime. So `N` is only the most shards the table can use, and
+    /// this picks how many it does. The symbols of a table using fewer
+    /// shards still have as many bits for the shard, so they're only the
+    /// same as in a table with all `N` shards if `n` is `N`.
+    ///
+    /// ```
+    /// let table: symbol_table::SymbolTable<1024> = symbol_table::SymbolTable::default().with_shards(4);
+    /// let foo = table.intern("foo");
+    /// assert!(table.shard_of(foo) < 4);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0` or more than `N`, or if the table already has
+    /// strings, since they'd be in the wrong shards.
+    pub fn with_shards(mut self, n: usize) -> Self {
+        assert!(0 < n && n <= N, "Can't use {n} of {N} shards");
+        assert!(
+            self.shards
+                .iter_mut()
+                .all(|s| s.get_mut().unwrap().strs.is_empty()),
+            "Can't change the number of shards of a table with strings"
+        );
+        self.n_shards = n;
+        self
+    }
+
+    /// Use as many shards as the machine can run threads in parallel,
+    /// rounded up to a power of two, and at most `N`.
+    ///
+    /// Few shards suit a small machine, and many suit a big one, so this
+    /// scales the table to where it runs, see
+    /// [`with_shards`](Self::with_shards). It can't use more than `N`
+    /// shards, so it only uses more than the default 16 on a big machine
+    /// if `N` is bigger, up to 1024. If the parallelism can't be found out,
+    /// this keeps all `N` shards.
+    ///
+    /// ```
+    /// use symbol_table::SymbolTable;
+    ///
+    /// let table: SymbolTable<1024> = SymbolTable::default().with_available_parallelism();
+    /// let parallelism = std::thread::available_parallelism().unwrap().get();
+    /// assert_eq!(table.n_shards(), parallelism.next_power_of_two());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Like [`with_shards`](Self::with_shards), this panics if the table
+    /// already has strings.
+    pub fn with_available_parallelism(self) -> Self {
+        let n = std::thread::available_parallelism().map_or(N, |n| n.get().next_power_of_two());
+        self.with_shards(n.min(N))
+    }
+
+    /// How many of the `N` shards the strings are spread over, see
+    /// [`with_shards`](Self::with_shards).
+    pub fn n_shards(&self) -> usize {
+        self.n_shards
+    }
+
+    /// Transform every string before it gets interned.
+    ///
+    /// The `transform` runs on every string passed to
+    /// [`intern`](Self::intern) or [`intern_owned`](Self::intern_owned),
+    /// before it's hashed or stored. This keeps canonicalization like
+    /// trimming or lowercasing in one place, instead of at every call site.
+    /// Returning [`Cow::Borrowed`] avoids allocating for strings that are
+    /// already canonical.
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use symbol_table::SymbolTable;
+    ///
+    /// let table = SymbolTable::new().with_transform(|s| match s.trim() {
+    ///     s if s.chars().any(char::is_uppercase) => Cow::Owned(s.to_lowercase()),
+    ///     s => Cow::Borrowed(s),
+    /// });
+    /// let foo = table.intern("  Foo ");
+    /// assert_eq!(foo, table.intern("foo"));
+    /// assert_eq!(table.resolve(foo), "foo");
+    /// ```
+    pub fn with_transform(
+        mut self,
+        transform: impl for<'a> Fn(&'a str) -> Cow<'a, str>
+            + Send
+            + Sync
+            + RefUnwindSafe
+            + UnwindSafe
+            + 'static,
+    ) -> Self {
+        self.transform = Some(Arc::new(transform));
+        self
+    }
+
+    /// Reject the strings that `validator` returns an error for.
+    ///
+    /// [`try_intern`](Self::try_intern) returns the error as an
+    /// [`InternError::Invalid`], and the other interning methods panic. Like
+    /// the [length limit](Self::with_max_str_len), this applies after the
+    /// [transform](Self::with_transform), and to every string, even ones
+    /// that are already in the table or [inline](Self::with_inline_strings).
+    /// So a parser of untrusted input can hand strings to the table as they
+    /// come, without polluting it. The [`validate`] module has validators for
+    /// common rules.
+    ///
+    /// ```
+    /// use symbol_table::{validate, InternError, InvalidStr, SymbolTable};
+    ///
+    /// let table = SymbolTable::new().with_validator(validate::ascii);
+    /// assert!(table.try_intern("plain").is_ok());
+    /// assert_eq!(
+    ///     table.try_intern("café"),
+    ///     Err(InternError::Invalid { reason: InvalidStr::NotAscii { at: 3 } }),
+    /// );
+    /// ```
+    pub fn with_validator(
+        mut self,
+        validator: impl Fn(&str) -> Result<(), InvalidStr>
+            + Send
+            + Sync
+            + RefUnwindSafe
+            + UnwindSafe
+            + 'static,
+    ) -> Self {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// Call `on_intern` with every new string that gets interned, and its
+    /// symbol.
+    ///
+    /// This runs once per string, when it's stored, so strings that were
+    /// already in the table and [inline](Self::with_inline_strings) strings
+    /// don't count. It's meant to mirror the table into something else, like
+    /// a search index, without wrapping every call to
+    /// [`intern`](Self::intern). For the global table, set it on the table
+    /// passed to [`GlobalSymbol::init_with`].
+    ///
+    /// `on_intern` is called while the string's shard is locked, so the
+    /// symbols of a shard are passed in order, but it must not use the
+    /// table. The clone of a table doesn't call it.
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use symbol_table::SymbolTable;
+    ///
+    /// let new = Arc::new(Mutex::new(Vec::new()));
+    /// let table = SymbolTable::new().with_on_intern({
+    ///     let new = new.clone();
+    ///     move |sym, s| new.lock().unwrap().push((sym, s.to_string()))
+    /// });
+    /// let foo = table.intern("foo");
+    /// table.intern("foo");
+    /// assert_eq!(*new.lock().unwrap(), [(foo, "foo".to_string())]);
+    /// ```
+    pub fn with_on_intern(
+        mut self,
+        on_intern: impl Fn(K, &str) + Send + Sync + RefUnwindSafe + UnwindSafe + 'static,
+    ) -> Self {
+        self.on_intern = Some(Box::new(on_intern));
+        self
+    }
+
+    /// Limit the length of the strings in the table, in bytes.
+    ///
+    /// [`try_intern`](Self::try_intern) returns an error for longer strings,
+    /// and the other interning methods panic. The limit applies after the
+    /// [transform](Self::with_transform), if there is one.
+    ///
+    /// ```
+    /// use symbol_table::{InternError, SymbolTable};
+    ///
+    /// let table = SymbolTable::new().with_max_str_len(8);
+    /// assert!(table.try_intern("short").is_ok());
+    /// assert_eq!(
+    ///     table.try_intern("much too long"),
+    ///     Err(InternError::TooLong { len: 13, max: 8 }),
+    /// );
+    /// ```
+    pub const fn with_max_str_len(mut self, max_str_len: usize) -> Self {
+        self.max_str_len = max_str_len;
+        self
+    }
+
+    /// Limit the number of strings in the table.
+    ///
+    /// Once the table is full, [`try_intern`](Self::try_intern) returns an
+    /// error for new strings, and the other interning methods panic. Strings
+    /// that are already in the table can still be interned, and
+    /// [inline](Self::with_inline_strings) strings don't count towards the
+    /// limit.
+    ///
+    /// ```
+    /// use symbol_table::{InternError, SymbolTable};
+    ///
+    /// let table = SymbolTable::new().with_max_entries(1);
+    /// let foo = table.try_intern("foo").unwrap();
+    /// assert_eq!(table.try_intern("foo"), Ok(foo));
+    /// assert_eq!(
+    ///     table.try_intern("bar"),
+    ///     Err(InternError::TooManyEntries { max: 1 }),
+    /// );
+    /// ```
+    pub const fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
 }
 
-#[derive(Default)]
-struct Shard {
+struct Shard<B> {
     map: HashMap<u32, (), ()>,
-    strs: Vec<Box<str>>,
+    // the hash of every string, so growing the map doesn't rehash them
+    hashes: Vec<u64>,
+    strs: B,
+    // other spellings of strings, hashing to this shard
+    aliases: alias::Aliases,
+    #[cfg(feature = "stats")]
+    counters: stats::ShardCounters,
+    // how many times every string was interned
+    #[cfg(feature = "counts")]
+    counts: Vec<u64>,
 }
 
-impl Shard {
+impl<B: Backend> Shard<B> {
     const fn new() -> Self {
         Self {
             map: HashMap::with_hasher(()),
-            strs: Vec::new(),
+            hashes: Vec::new(),
+            strs: B::EMPTY,
+            aliases: alias::Aliases::new(),
+            #[cfg(feature = "stats")]
+            counters: stats::ShardCounters::NEW,
+            #[cfg(feature = "counts")]
+            counts: Vec::new(),
         }
     }
 
-    fn intern(&mut self, hash: u64, string: &str, build_hasher: &impl BuildHasher) -> u32 {
+    fn intern<E>(
+        &mut self,
+        hash: u64,
+        string: impl Internable,
+        reserve: impl FnOnce(&[u8]) -> Result<(), E>,
+    ) -> Result<u32, E> {
         let entry = self
             .map
             .raw_entry_mut()
-            .from_hash(hash, |&idx| string == self.strs[idx as usize].as_ref());
+            .from_hash(hash, |&idx| string.as_ref() == self.strs.get(idx));
 
         let index = match entry {
-            RawEntryMut::Occupied(e) => *e.key(),
+            RawEntryMut::Occupied(e) => {
+                #[cfg(feature = "stats")]
+                {
+                    self.counters.hits += 1;
+                }
+                #[cfg(feature = "counts")]
+                {
+                    self.counts[*e.key() as usize] += 1;
+                }
+                *e.key()
+            }
             RawEntryMut::Vacant(e) => {
-                let idx = self.strs.len() as u32;
-                self.strs.push(string.into());
+                reserve(string.as_ref())?;
+                #[cfg(feature = "stats")]
+                {
+                    self.counters.inserts += 1;
+                }
+                let idx = string.push_into(&mut self.strs);
+                self.hashes.push(hash);
+                #[cfg(feature = "counts")]
+                self.counts.push(1);
 
-                *e.insert_with_hasher(hash, idx, (), |&idx| {
-                    hash_one(build_hasher, self.strs[idx as usize].as_ref())
-                })
-                .0
+                *e.insert_with_hasher(hash, idx, (), |&idx| self.hashes[idx as usize])
+                    .0
             }
         };
 
         debug_assert!(!self.strs.is_empty());
         debug_assert!(!self.map.is_empty());
-        index
+        debug_assert_eq!(self.strs.len(), self.hashes.len());
+        Ok(index)
+    }
+
+    /// The index of a string, if it's in this shard.
+    fn find(&self, hash: u64, string: &[u8]) -> Option<u32> {
+        let entry = self
+            .map
+            .raw_entry()
+            .from_hash(hash, |&idx| string == self.strs.get(idx));
+        entry.map(|(&idx, _)| idx)
+    }
+
+    /// Make room for `additional` more strings.
+    fn reserve(&mut self, additional: usize) {
+        self.hashes.reserve(additional);
+        #[cfg(feature = "counts")]
+        self.counts.reserve(additional);
+        let capacity = self.hashes.len() + additional;
+        if self.map.capacity() >= capacity {
+            return;
+        }
+        // the map has no hasher to grow with, so rebuild it
+        let hashes = &self.hashes;
+        let mut map = HashMap::with_capacity_and_hasher(capacity, ());
+        for (idx, &hash) in hashes.iter().enumerate() {
+            match map.raw_entry_mut().from_hash(hash, |_| false) {
+                RawEntryMut::Vacant(e) => {
+                    e.insert_with_hasher(hash, idx as u32, (), |&idx| hashes[idx as usize]);
+                }
+                RawEntryMut::Occupied(_) => unreachable!(),
+            }
+        }
+        self.map = map;
+    }
+
+    /// Add a string that isn't in this shard yet, e.g. when rebuilding it.
+    ///
+    /// With the `counts` feature, its count starts at zero.
+    fn insert_new(&mut self, hash: u64, string: &[u8]) -> u32 {
+        let idx = self.strs.push(string);
+        self.hashes.push(hash);
+        #[cfg(feature = "counts")]
+        self.counts.push(0);
+        let hashes = &self.hashes;
+        match self.map.raw_entry_mut().from_hash(hash, |_| false) {
+            RawEntryMut::Vacant(e) => {
+                e.insert_with_hasher(hash, idx, (), |&idx| hashes[idx as usize]);
+            }
+            RawEntryMut::Occupied(_) => unreachable!(),
+        }
+        idx
     }
 }
 
-impl<const N: usize, S: Default + BuildHasher> Default for SymbolTable<N, S> {
+impl<const N: usize, S: Default + BuildHasher, B: Backend, K: SymbolKey> Default
+    for SymbolTable<N, S, B, K>
+{
     fn default() -> Self {
         Self::with_hasher(S::default())
     }
 }
 
+/// Cloning a table copies all of its strings, and the symbols from the
+/// original resolve to the same strings in the clone.
+///
+/// The clone doesn't append to the original's [log](SymbolTable::with_log),
+/// or call its [`on_intern`](SymbolTable::with_on_intern) callback.
+///
+/// ```
+/// let table = symbol_table::SymbolTable::new();
+/// let foo = table.intern("foo");
+/// let clone = table.clone();
+/// assert_eq!(clone.resolve(foo), "foo");
+/// assert_eq!(clone.intern("foo"), foo);
+/// ```
+impl<const N: usize, S: Clone, B: Backend, K: SymbolKey> Clone for SymbolTable<N, S, B, K> {
+    fn clone(&self) -> Self {
+        Self {
+            build_hasher: self.build_hasher.clone(),
+            inline: self.inline,
+            n_shards: self.n_shards,
+            thread_affinity: self.thread_affinity,
+            transform: self.transform.clone(),
+            validator: self.validator.clone(),
+            max_str_len: self.max_str_len,
+            max_entries: self.max_entries,
+            entries: AtomicUsize::new(self.entries.load(Ordering::Relaxed)),
+            fresh: AtomicUsize::new(self.fresh.load(Ordering::Relaxed)),
+            log: None,
+            on_intern: None,
+            shards: std::array::from_fn(|i| {
+                CachePadded::new(Mutex::new(self.shards[i].lock().unwrap().clone()))
+            }),
+            _key: PhantomData,
+        }
+    }
+}
+
+/// Shows the number of strings in the table and in each shard. The alternate
+/// format, `{:#?}`, also lists every stored string with its symbol.
+///
+/// ```
+/// let table = symbol_table::SymbolTable::new();
+/// table.intern("foo");
+/// assert!(format!("{table:?}").starts_with("SymbolTable { len: 1, shards: ["));
+/// assert!(format!("{table:#?}").contains(": \"foo\""));
+/// ```
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> fmt::Debug
+    for SymbolTable<N, S, B, K>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lens = self.shards.iter().map(|s| s.lock().unwrap().strs.len());
+        let lens: Vec<usize> = lens.collect();
+        let alternate = f.alternate();
+        let mut s = f.debug_struct("SymbolTable");
+        s.field("len", &lens.iter().sum::<usize>());
+        s.field("shards", &lens);
+        if alternate {
+            s.field("strings", &DebugStrings(self));
+        }
+        s.finish()
+    }
+}
+
+/// Indexing with a symbol [resolves](SymbolTable::resolve) it.
+///
+/// ```
+/// let table = symbol_table::SymbolTable::new();
+/// let foo = table.intern("foo");
+/// assert_eq!(&table[foo], "foo");
+/// ```
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> std::ops::Index<K>
+    for SymbolTable<N, S, B, K>
+{
+    type Output = str;
+
+    fn index(&self, sym: K) -> &str {
+        self.resolve(sym)
+    }
+}
+
+/// Interns all the strings, like [`intern`](SymbolTable::intern) would.
+///
+/// The strings are grouped by shard first, so that every shard is only
+/// locked once.
+///
+/// ```
+/// let mut table = symbol_table::SymbolTable::new();
+/// table.extend("a b c".split(' '));
+/// assert_eq!(table.lookup("b"), Some(table.intern("b")));
+/// ```
+///
+/// # Panics
+///
+/// Panics if a string exceeds the table's limits, like
+/// [`intern`](SymbolTable::intern).
+impl<'a, const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> Extend<&'a str>
+    for SymbolTable<N, S, B, K>
+{
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, strings: I) {
+        self.intern_all(strings, |_, _| {})
+    }
+}
+
+/// Creates a default table with all the strings interned, like [`Extend`]
+/// does.
+///
+/// ```
+/// use symbol_table::SymbolTable;
+///
+/// let table: SymbolTable = "the cat and the hat".split(' ').collect();
+/// assert_eq!(table.symbols().count(), 4);
+/// ```
+impl<'a, const N: usize, S: Default + BuildHasher, B: Backend, K: SymbolKey> FromIterator<&'a str>
+    for SymbolTable<N, S, B, K>
+{
+    fn from_iter<I: IntoIterator<Item = &'a str>>(strings: I) -> Self {
+        let mut table = Self::default();
+        table.extend(strings);
+        table
+    }
+}
+
+/// The strings of a table, for its alternate [`Debug`](fmt::Debug) format.
+struct DebugStrings<'a, const N: usize, S, B, K>(&'a SymbolTable<N, S, B, K>);
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> fmt::Debug
+    for DebugStrings<'_, N, S, B, K>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let table = self.0;
+        let strings = table.stored_symbols().map(|sym| (sym, table.resolve(sym)));
+        f.debug_map().entries(strings).finish()
+    }
+}
+
+impl<B: Backend> Clone for Shard<B> {
+    fn clone(&self) -> Self {
+        // push the strings one by one, so they get their own, stable, storage
+        let mut strs = B::EMPTY;
+        for idx in 0..self.strs.len() as u32 {
+            strs.push(self.strs.get(idx));
+        }
+        Self {
+            map: self.map.clone(),
+            hashes: self.hashes.clone(),
+            strs,
+            aliases: self.aliases.clone(),
+            #[cfg(feature = "stats")]
+            counters: self.counters,
+            #[cfg(feature = "counts")]
+            counts: self.counts.clone(),
+        }
+    }
+}
+
+/// A string that can be pushed into a [`Backend`], borrowed or owned.
+pub(crate) trait Internable: AsRef<[u8]> {
+    fn push_into(self, strs: &mut impl Backend) -> u32;
+}
+
+impl Internable for &str {
+    fn push_into(self, strs: &mut impl Backend) -> u32 {
+        strs.push(self.as_bytes())
+    }
+}
+
+impl Internable for String {
+    fn push_into(self, strs: &mut impl Backend) -> u32 {
+        strs.push_owned(self.into_bytes())
+    }
+}
+
+impl Internable for &[u8] {
+    fn push_into(self, strs: &mut impl Backend) -> u32 {
+        strs.push(self)
+    }
+}
+
+impl Internable for Vec<u8> {
+    fn push_into(self, strs: &mut impl Backend) -> u32 {
+        strs.push_owned(self)
+    }
+}
+
+impl Internable for Arc<[u8]> {
+    fn push_into(self, strs: &mut impl Backend) -> u32 {
+        strs.push_arc(self)
+    }
+}
+
+/// A string that lives for the rest of the program, which a backend may
+/// point to instead of copying it.
+pub(crate) struct Static(&'static [u8]);
+
+impl AsRef<[u8]> for Static {
+    fn as_ref(&self) -> &[u8] {
+        self.0
+    }
+}
+
+impl Internable for Static {
+    fn push_into(self, strs: &mut impl Backend) -> u32 {
+        strs.push_static(self.0)
+    }
+}
+
+/// Panic with the error if interning failed, for the infallible methods.
+#[track_caller]
+fn unwrap_interned<K>(result: Result<K, InternError>) -> K {
+    result.unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Hash the bytes of a string.
+///
+/// This feeds the hasher exactly like `str`'s [`Hash`] impl does, so a
+/// string and its bytes have the same hash.
 #[inline(always)]
-fn hash_one(build_hasher: &impl BuildHasher, string: &str) -> u64 {
+fn hash_one(build_hasher: &impl BuildHasher, bytes: &[u8]) -> u64 {
+    use std::hash::Hasher;
     let mut hasher = build_hasher.build_hasher();
-    string.hash(&mut hasher);
-    std::hash::Hasher::finish(&hasher)
+    hasher.write(bytes);
+    hasher.write_u8(0xff);
+    hasher.finish()
 }
 
-impl<const N: usize, S: BuildHasher> SymbolTable<N, S> {
+/// The shard of a string's hash, out of `n_shards`.
+///
+/// This takes the remainder of the whole `u64`, so it's the same on 32- and
+/// 64-bit targets.
+#[inline(always)]
+fn shard_of_hash(hash: u64, n_shards: usize) -> usize {
+    (hash % n_shards as u64) as usize
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
     /// Intern a string into the [`SymbolTable`].
     ///
     /// Note how this method only takes `&self`, so it can be used concurrently.
@@ -149,19 +1015,643 @@ impl<const N: usize, S: BuildHasher> SymbolTable<N, S> {
     /// let mut table = symbol_table::SymbolTable::new();
     /// assert_eq!(table.intern("foo"), table.intern("foo"));
     /// ```
-    pub fn intern(&self, string: &str) -> Symbol {
-        let hash = hash_one(&self.build_hasher, string);
-        let shard_i = hash as usize % N;
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string exceeds the table's limits; use
+    /// [`try_intern`](Self::try_intern) to handle that instead.
+    pub fn intern(&self, string: &str) -> K {
+        unwrap_interned(self.try_intern(string))
+    }
+
+    /// Intern a string into the [`SymbolTable`], unless that would exceed the
+    /// table's limits, or the string is invalid.
+    ///
+    /// See [`with_max_str_len`](Self::with_max_str_len),
+    /// [`with_max_entries`](Self::with_max_entries) and
+    /// [`with_validator`](Self::with_validator). Without any limits or
+    /// validator, this never fails.
+    pub fn try_intern(&self, string: &str) -> Result<K, InternError> {
+        match self.transform.as_ref().map(|f| f(string)) {
+            None => self.try_intern_internable(string),
+            Some(Cow::Borrowed(s)) => self.try_intern_internable(s),
+            Some(Cow::Owned(s)) => self.try_intern_internable(s),
+        }
+    }
+
+    /// The symbol of a string, if it's already in the table.
+    ///
+    /// Unlike [`intern`](Self::intern), this never adds the string to the
+    /// table. The [transform](Self::with_transform) is applied first, like
+    /// when interning.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let foo = table.intern("foo");
+    /// assert_eq!(table.lookup("foo"), Some(foo));
+    /// assert_eq!(table.lookup("bar"), None);
+    /// ```
+    pub fn lookup(&self, string: &str) -> Option<K> {
+        match self.transform.as_ref().map(|f| f(string)) {
+            None => self.lookup_bytes(string.as_bytes()),
+            Some(s) => self.lookup_bytes(s.as_bytes()),
+        }
+    }
+
+    /// Intern an owned string into the [`SymbolTable`].
+    ///
+    /// This is like [`intern`](Self::intern), but if the string isn't already
+    /// in the table, the backend may reuse its allocation instead of copying
+    /// it (the default [`StringBackend`] does). A `Box<str>` can be passed
+    /// with [`into_string`](str::into_string), which doesn't allocate.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let name = format!("tmp_{}", 42);
+    /// let sym = table.intern_owned(name);
+    /// assert_eq!(sym, table.intern("tmp_42"));
+    /// ```
+    pub fn intern_owned(&self, string: String) -> K {
+        let transformed = match &self.transform {
+            Some(f) => f(&string),
+            None => return self.intern_internable(string),
+        };
+        unwrap_interned(match transformed {
+            // unchanged, so it can still be moved into the table
+            Cow::Borrowed(s) if s == string => self.try_intern_internable(string),
+            Cow::Borrowed(s) => self.try_intern_internable(s),
+            Cow::Owned(s) => self.try_intern_internable(s),
+        })
+    }
+
+    /// Intern a string that lives for the rest of the program.
+    ///
+    /// This is like [`intern`](Self::intern), but if the string isn't
+    /// already in the table, the backend may point to it instead of copying
+    /// it (the default [`StringBackend`] does). A string that the
+    /// [transform](Self::with_transform) borrows from is still `'static`, so
+    /// it isn't copied either.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let keyword: &'static str = "return";
+    /// let sym = table.intern_static(keyword);
+    /// assert_eq!(table.resolve(sym).as_ptr(), keyword.as_ptr());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string exceeds the table's limits, like
+    /// [`intern`](Self::intern).
+    pub fn intern_static(&self, string: &'static str) -> K {
+        unwrap_interned(match self.transform.as_ref().map(|f| f(string)) {
+            None => self.try_intern_internable(Static(string.as_bytes())),
+            Some(Cow::Borrowed(s)) => self.try_intern_internable(Static(s.as_bytes())),
+            Some(Cow::Owned(s)) => self.try_intern_internable(s),
+        })
+    }
+
+    /// Intern a string that's either `'static` or owned, without copying
+    /// it if the backend doesn't have to.
+    ///
+    /// A borrowed string is interned like with
+    /// [`intern_static`](Self::intern_static), and an owned one like with
+    /// [`intern_owned`](Self::intern_owned).
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// let table = symbol_table::SymbolTable::new();
+    /// let name: Cow<'static, str> = Cow::Owned(format!("tmp_{}", 42));
+    /// let sym = table.intern_cow(name);
+    /// assert_eq!(sym, table.intern_cow(Cow::Borrowed("tmp_42")));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string exceeds the table's limits, like
+    /// [`intern`](Self::intern).
+    pub fn intern_cow(&self, string: Cow<'static, str>) -> K {
+        match string {
+            Cow::Borrowed(s) => self.intern_static(s),
+            Cow::Owned(s) => self.intern_owned(s),
+        }
+    }
+
+    /// Intern a copy of a part of the string of a symbol, e.g. a component
+    /// of an interned path.
+    ///
+    /// This is only a shorthand for interning `&self.resolve(parent)[range]`,
+    /// and doesn't save any memory: if the part is new, its bytes are copied
+    /// into the table like any other string's, since the parent's string may
+    /// be dropped first, e.g. by [`compact`](Self::compact). To store the
+    /// parts of strings without copying them, intern the strings into a
+    /// [`BorrowedSymbolTable`], whose
+    /// [`intern_slice`](BorrowedSymbolTable::intern_slice) points into the
+    /// parent's string instead.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let path = table.intern("/usr/bin");
+    /// let usr = table.intern_slice(path, 1..4);
+    /// assert_eq!(usr, table.intern("usr"));
+    /// assert_eq!(table.intern_slice(path, 5..), table.intern("bin"));
+    /// // a copy, not a pointer into "/usr/bin"
+    /// assert_ne!(table.resolve(usr).as_ptr(), table.resolve(path)[1..].as_ptr());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds of the string, or doesn't start
+    /// and end on `char` boundaries, like indexing a `str`, or if the part
+    /// exceeds the limits of the table, like [`intern`](Self::intern).
+    pub fn intern_slice(&self, parent: K, range: impl SliceIndex<str, Output = str>) -> K {
+        self.intern(&self.resolve(parent)[range])
+    }
+
+    /// Intern a new string that starts with `prefix`, e.g. for a generated
+    /// name that mustn't clash with any other.
+    ///
+    /// The string is `prefix` followed by a number, counting up for every
+    /// call, and skipping strings that are already in the table. Checking
+    /// and interning happen under one lock, so even concurrent calls never
+    /// return the same symbol, nor one that [`intern`](Self::intern) has
+    /// returned before. The [transform](Self::with_transform) is applied to
+    /// the string, and [inline](Self::with_inline_strings) strings are
+    /// skipped, since they aren't stored.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// table.intern("tmp1");
+    ///
+    /// let a = table.fresh("tmp");
+    /// let b = table.fresh("tmp");
+    /// assert_eq!(table.resolve(a), "tmp0");
+    /// assert_eq!(table.resolve(b), "tmp2");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string exceeds the table's limits, like
+    /// [`intern`](Self::intern).
+    pub fn fresh(&self, prefix: &str) -> K {
+        loop {
+            let n = self.fresh.fetch_add(1, Ordering::Relaxed);
+            let name = format_args!("{prefix}{n}");
+            let fresh = format::with_formatted(name, |name| match &self.transform {
+                Some(f) => self.intern_if_new(&f(name)),
+                None => self.intern_if_new(name),
+            });
+            if let Some(sym) = unwrap_interned(fresh) {
+                return sym;
+            }
+        }
+    }
+
+    /// Intern a string, unless it's already in the table or inline.
+    fn intern_if_new(&self, string: &str) -> Result<Option<K>, InternError> {
+        self.check_str(string.as_bytes())?;
+        if self.inline_symbol(string.as_bytes()).is_some() {
+            return Ok(None);
+        }
+
+        let hash = hash_one(&self.build_hasher, string.as_bytes());
+        let shard_i = shard_of_hash(hash, self.n_shards);
+        let mut locked = self.lock_shard(shard_i);
+        if locked.find(hash, string.as_bytes()).is_some()
+            || Self::aliased(&locked, hash, string.as_bytes()).is_some()
+        {
+            return Ok(None);
+        }
+        let idx = self.intern_locked(&mut locked, shard_i, hash, string)?;
+        drop(locked);
+        Ok(Some(Self::make_symbol(shard_i, idx)))
+    }
+
+    /// The hash of a string in this table, as expected by
+    /// [`intern_prehashed`](Self::intern_prehashed).
+    ///
+    /// This is always the table's [`BuildHasher::hash_one`] of the `&str`,
+    /// so code that already hashes strings with the same hasher, e.g. a
+    /// lexer sharing the hasher given to [`with_hasher`](Self::with_hasher),
+    /// can pass those hashes on instead of calling this.
+    ///
+    /// ```
+    /// use std::hash::{BuildHasher, RandomState};
+    /// use symbol_table::SymbolTable;
+    ///
+    /// let hasher = RandomState::new();
+    /// let table: SymbolTable<16, _> = SymbolTable::with_hasher(hasher.clone());
+    /// assert_eq!(table.hash_str("foo"), hasher.hash_one("foo"));
+    /// ```
+    ///
+    /// With the default [`DeterministicHashBuilder`], the hash of a string
+    /// only depends on the version of this crate and of `foldhash`, and on
+    /// the pointer width and endianness of the target, so it can be computed
+    /// ahead of time, as long as the program checks that it hashes the same
+    /// way, like the `symbol!` macro of `symbol_table_macros` does.
+    pub fn hash_str(&self, string: &str) -> u64 {
+        hash_one(&self.build_hasher, string.as_bytes())
+    }
+
+    /// The [`hash_str`](Self::hash_str) of the string of `sym`.
+    ///
+    /// The hashes of stored strings are kept in the table, so this doesn't
+    /// hash anything, only [inline](Self::with_inline_strings) strings are
+    /// hashed again. Maps keyed by the strings of symbols can use it to
+    /// skip hashing them once more, e.g. with a
+    /// [`hashbrown::HashTable`](https://docs.rs/hashbrown/latest/hashbrown/struct.HashTable.html).
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let foo = table.intern("foo");
+    /// assert_eq!(table.hash_of(foo), table.hash_str("foo"));
+    /// ```
+    pub fn hash_of(&self, sym: K) -> u64 {
+        match Self::split_symbol(sym) {
+            Some((shard_i, idx)) => self.lock_shard(shard_i).hashes[idx as usize],
+            None => hash_one(&self.build_hasher, self.resolve_bytes(sym)),
+        }
+    }
+
+    /// Intern a string whose [`hash_str`](Self::hash_str) is already known,
+    /// without hashing it again.
+    ///
+    /// If `hash` isn't the string's hash, the string may be interned again,
+    /// under a new symbol; debug builds check it. The hash can't be used if
+    /// the table has a [transform](Self::with_transform), so then this is
+    /// just [`intern`](Self::intern).
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let hash = table.hash_str("foo");
+    /// assert_eq!(table.intern_prehashed(hash, "foo"), table.intern("foo"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string exceeds the table's limits, like
+    /// [`intern`](Self::intern).
+    pub fn intern_prehashed(&self, hash: u64, string: &str) -> K {
+        if self.transform.is_some() {
+            return self.intern(string);
+        }
+        debug_assert_eq!(hash, self.hash_str(string), "wrong hash for {string:?}");
+        unwrap_interned(self.try_intern_hashed(string, |_| hash))
+    }
+
+    /// Make room for at least `additional` more strings.
+    ///
+    /// The room is split evenly over the shards, so interning that many
+    /// strings usually won't have to grow any of them.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// table.reserve(100_000);
+    /// ```
+    pub fn reserve(&self, additional: usize) {
+        let per_shard = additional.div_ceil(self.n_shards);
+        for (shard_i, shard) in self.shards[..self.n_shards].iter().enumerate() {
+            let mut shard = shard.lock().unwrap();
+            let before = shard.capacities();
+            shard.reserve(per_shard);
+            before.trace_growth(shard_i, &shard);
+        }
+    }
+
+    /// Leak this table, so it lives for the rest of the program.
+    ///
+    /// Resolving symbols through the returned reference gives `&'static str`s,
+    /// just like [`GlobalSymbol`](crate::GlobalSymbol) does for the global table.
+    /// The table's memory is never freed.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new().leak();
+    /// let foo = table.intern("foo");
+    /// let s: &'static str = table.resolve(foo);
+    /// assert_eq!(s, "foo");
+    /// ```
+    pub fn leak(self) -> &'static Self
+    where
+        Self: 'static,
+    {
+        Box::leak(Box::new(self))
+    }
+
+    // Drop every string and count, keeping the configuration. Only sound
+    // if none of the table's symbols or strings are used afterwards.
+    #[cfg(feature = "test-util")]
+    pub(crate) fn reset(&self) {
+        for shard in &self.shards {
+            *shard.lock().unwrap() = Shard::new();
+        }
+        self.entries.store(0, Ordering::Relaxed);
+        self.fresh.store(0, Ordering::Relaxed);
+    }
+
+    pub(crate) fn intern_internable(&self, string: impl Internable) -> K {
+        unwrap_interned(self.try_intern_internable(string))
+    }
+
+    pub(crate) fn try_intern_internable(&self, string: impl Internable) -> Result<K, InternError> {
+        self.try_intern_hashed(string, |bytes| hash_one(&self.build_hasher, bytes))
+    }
+
+    /// Intern a string, hashing it with `hash` unless it's inlined.
+    fn try_intern_hashed(
+        &self,
+        string: impl Internable,
+        hash: impl FnOnce(&[u8]) -> u64,
+    ) -> Result<K, InternError> {
+        self.check_str(string.as_ref())?;
+
+        if let Some(sym) = self.inline_symbol(string.as_ref()) {
+            return Ok(sym);
+        }
+
+        let hash = hash(string.as_ref());
+        let shard_i = shard_of_hash(hash, self.n_shards);
         // println!("Interning into shard {shard_i}");
 
-        let mut locked = self.shards[shard_i].lock().unwrap();
-        let i = locked.intern(hash, string, &self.build_hasher) + 1;
+        let mut locked = self.lock_shard(shard_i);
+        if let Some(sym) = Self::aliased(&locked, hash, string.as_ref()) {
+            return Ok(sym);
+        }
+        if self.thread_affinity && locked.find(hash, string.as_ref()).is_none() {
+            let thread_i = self.thread_shard();
+            if thread_i != shard_i {
+                drop(locked);
+                return self.try_intern_pinned_hashed(thread_i, hash, string);
+            }
+        }
+        let idx = self.intern_locked(&mut locked, shard_i, hash, string)?;
         drop(locked);
 
-        assert!(i < Self::MAX_IDX, "Can't represent index {} in a Symbol", i);
-        let shard_bits: u32 = (shard_i as u32) << (32 - Self::SHARD_BITS);
-        // println!("shard_bits = {shard_bits:x}");
-        Symbol(NonZeroU32::new(shard_bits | i).unwrap())
+        Ok(Self::make_symbol(shard_i, idx))
+    }
+
+    /// Check a string against the table's length limit and validator,
+    /// before interning it.
+    pub(crate) fn check_str(&self, string: &[u8]) -> Result<(), InternError> {
+        let len = string.len();
+        if len > self.max_str_len {
+            let max = self.max_str_len;
+            return Err(InternError::TooLong { len, max });
+        }
+        if let Some(validator) = &self.validator {
+            // SAFETY: only `str`s are interned through the public API of a
+            // `SymbolTable`, see `resolve`, and the wrappers that intern
+            // other bytes can't set a validator
+            let string = unsafe { std::str::from_utf8_unchecked(string) };
+            validator(string).map_err(|reason| InternError::Invalid { reason })?;
+        }
+        Ok(())
+    }
+
+    /// Intern many strings, returning their symbols in the same order.
+    ///
+    /// This gives the same symbols as interning the strings one by one, but
+    /// faster: the strings are hashed in batches, and every batch locks each
+    /// shard only once. So it's a good fit for the words of a big document,
+    /// split however it needs to be, see also
+    /// [`intern_words`](Self::intern_words).
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let syms = table.intern_many("a,b,,a".split(','));
+    /// assert_eq!(syms[0], syms[3]);
+    /// assert_eq!(table.resolve(syms[2]), "");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if a string exceeds the table's limits, like
+    /// [`intern`](Self::intern). The strings before it may or may not have
+    /// been interned.
+    pub fn intern_many<'a>(&self, strings: impl IntoIterator<Item = &'a str>) -> Vec<K> {
+        let mut syms = Vec::new();
+        self.intern_all(strings, |i, sym| {
+            if syms.len() <= i {
+                syms.resize(i + 1, None);
+            }
+            syms[i] = Some(sym);
+        });
+        syms.into_iter().map(Option::unwrap).collect()
+    }
+
+    /// Intern the words of `text`, as split by [`str::split_whitespace`],
+    /// returning their symbols in order.
+    ///
+    /// This is [`intern_many`](Self::intern_many) for whitespace-separated
+    /// words; use that with another splitter for other kinds of tokens.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let syms = table.intern_words("the cat and the hat");
+    /// assert_eq!(syms.len(), 5);
+    /// assert_eq!(syms[0], syms[3]);
+    /// assert_eq!(syms[4], table.intern("hat"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`intern_many`](Self::intern_many).
+    pub fn intern_words(&self, text: &str) -> Vec<K> {
+        self.intern_many(text.split_whitespace())
+    }
+
+    /// Intern every line that `reader` reads, lazily, returning their
+    /// symbols in order.
+    ///
+    /// The lines are read one at a time into a buffer that's reused, so this
+    /// works for files much bigger than memory. Lines end at a `\n` or a
+    /// `\r\n`, which aren't part of the interned strings, like with
+    /// [`BufRead::lines`](std::io::BufRead::lines). Reading errors and lines
+    /// that aren't UTF-8 are given as errors, after which the iterator may
+    /// go on if `reader` does. So are lines that exceed the table's limits,
+    /// as [`InvalidData`](std::io::ErrorKind::InvalidData) errors wrapping
+    /// the [`InternError`], like [`try_intern`](Self::try_intern) gives.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let input = "foo\nbar\r\nfoo";
+    /// let syms: Vec<_> = table.intern_lines(input.as_bytes()).collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(syms, [table.intern("foo"), table.intern("bar"), table.intern("foo")]);
+    /// ```
+    pub fn intern_lines<'a>(
+        &'a self,
+        mut reader: impl std::io::BufRead + 'a,
+    ) -> impl Iterator<Item = std::io::Result<K>> + 'a {
+        let mut line = String::new();
+        std::iter::from_fn(move || {
+            line.clear();
+            match reader.read_line(&mut line) {
+                Ok(0) => None,
+                Ok(_) => {
+                    let line = line.strip_suffix('\n').unwrap_or(&line);
+                    let line = line.strip_suffix('\r').unwrap_or(line);
+                    let invalid = |err| std::io::Error::new(std::io::ErrorKind::InvalidData, err);
+                    Some(self.try_intern(line).map_err(invalid))
+                }
+                Err(err) => Some(Err(err)),
+            }
+        })
+    }
+
+    /// Intern all the strings, a batch at a time, grouped by shard so that
+    /// every shard is only locked once per batch. `interned` gets the
+    /// position and symbol of every string.
+    ///
+    /// Panics like [`intern`](Self::intern) if a string exceeds the
+    /// table's limits.
+    fn intern_all<'a>(
+        &self,
+        strings: impl IntoIterator<Item = &'a str>,
+        mut interned: impl FnMut(usize, K),
+    ) {
+        // bounds the memory for the grouped strings of a huge iterator
+        const BATCH_LEN: usize = 4096;
+
+        if self.thread_affinity {
+            for (i, string) in strings.into_iter().enumerate() {
+                interned(i, unwrap_interned(self.try_intern(string)));
+            }
+            return;
+        }
+
+        let mut strings = strings.into_iter().enumerate().peekable();
+        let mut grouped: [Vec<(usize, u64, Cow<'a, str>)>; N] = std::array::from_fn(|_| Vec::new());
+        while strings.peek().is_some() {
+            for (i, string) in strings.by_ref().take(BATCH_LEN) {
+                let string = match &self.transform {
+                    Some(f) => f(string),
+                    None => Cow::Borrowed(string),
+                };
+                unwrap_interned(self.check_str(string.as_bytes()));
+                if let Some(sym) = self.inline_symbol(string.as_bytes()) {
+                    interned(i, sym);
+                    continue;
+                }
+                let hash = hash_one(&self.build_hasher, string.as_bytes());
+                grouped[shard_of_hash(hash, self.n_shards)].push((i, hash, string));
+            }
+
+            for (shard_i, strings) in grouped.iter_mut().enumerate() {
+                let mut locked = self.lock_shard(shard_i);
+                let result = strings.drain(..).try_for_each(|(i, hash, string)| {
+                    if let Some(sym) = Self::aliased(&locked, hash, string.as_bytes()) {
+                        interned(i, sym);
+                        return Ok(());
+                    }
+                    let idx = match string {
+                        Cow::Borrowed(s) => self.intern_locked(&mut locked, shard_i, hash, s),
+                        Cow::Owned(s) => self.intern_locked(&mut locked, shard_i, hash, s),
+                    }?;
+                    interned(i, Self::make_symbol(shard_i, idx));
+                    Ok(())
+                });
+                // don't poison the lock by panicking while holding it
+                drop(locked);
+                unwrap_interned(result);
+            }
+        }
+    }
+
+    // Lock a shard to intern, look up or resolve a string. With the `stats`
+    // feature, this counts the locks, and the ones that had to wait.
+    fn lock_shard(&self, shard_i: usize) -> impl DerefMut<Target = Shard<B>> + '_ {
+        Self::lock(&self.shards[shard_i])
+    }
+
+    fn lock(shard: &CachePadded<Mutex<Shard<B>>>) -> impl DerefMut<Target = Shard<B>> + '_ {
+        #[cfg(feature = "stats")]
+        {
+            let (mut locked, contended) = match shard.try_lock() {
+                Ok(locked) => (locked, false),
+                // blocks, or panics if the lock is poisoned
+                Err(_) => (shard.lock().unwrap(), true),
+            };
+            locked.counters.locks += 1;
+            locked.counters.contended += contended as u64;
+            locked
+        }
+        #[cfg(not(feature = "stats"))]
+        shard.lock().unwrap()
+    }
+
+    /// The symbol of a string, if it's already in the table.
+    pub(crate) fn lookup_bytes(&self, string: &[u8]) -> Option<K> {
+        if let Some(sym) = self.inline_symbol(string) {
+            return Some(sym);
+        }
+
+        let hash = hash_one(&self.build_hasher, string);
+        let shard_i = shard_of_hash(hash, self.n_shards);
+        let locked = self.lock_shard(shard_i);
+        if let Some(sym) = Self::aliased(&locked, hash, string) {
+            return Some(sym);
+        }
+        let idx = locked.find(hash, string)?;
+        Some(Self::make_symbol(shard_i, idx))
+    }
+
+    /// Every symbol whose string is stored in the table, shard by shard in
+    /// interning order.
+    ///
+    /// Each shard is only locked to read its length, so strings interned
+    /// concurrently may or may not be included.
+    pub(crate) fn stored_symbols(&self) -> impl Iterator<Item = K> + '_ {
+        (0..N).flat_map(move |shard_i| {
+            let len = self.shards[shard_i].lock().unwrap().strs.len() as u32;
+            (0..len).map(move |idx| Self::make_symbol(shard_i, idx))
+        })
+    }
+
+    // Intern a string into its locked shard, and pass it to the `on_intern`
+    // callback if it's new. With the `tracing` feature, this also traces the
+    // shard growing.
+    fn intern_locked(
+        &self,
+        shard: &mut Shard<B>,
+        shard_i: usize,
+        hash: u64,
+        string: impl Internable,
+    ) -> Result<u32, InternError> {
+        let mut new = false;
+        let before = shard.capacities();
+        let idx = shard.intern(hash, string, |bytes| {
+            new = true;
+            self.reserve_entry(bytes)
+        })?;
+        before.trace_growth(shard_i, shard);
+        if let (true, Some(on_intern)) = (new, &self.on_intern) {
+            // SAFETY: only `str`s are interned through the public API of a
+            // `SymbolTable`, see `resolve`
+            let string = unsafe { std::str::from_utf8_unchecked(shard.strs.get(idx)) };
+            on_intern(Self::make_symbol(shard_i, idx), string);
+        }
+        Ok(idx)
+    }
+
+    /// Make room for a new string: count it towards the `max_entries`
+    /// limit, and append it to the log.
+    ///
+    /// This is called with the lock of the string's shard held.
+    fn reserve_entry(&self, string: &[u8]) -> Result<(), InternError> {
+        let max = self.max_entries;
+        if max != usize::MAX {
+            self.entries
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                    (n < max).then_some(n + 1)
+                })
+                .map_err(|_| InternError::TooManyEntries { max })?;
+        }
+
+        let logged = self.append_to_log(string);
+        if logged.is_err() && max != usize::MAX {
+            self.entries.fetch_sub(1, Ordering::Relaxed);
+        }
+        logged.map_err(|err| InternError::Log { kind: err.kind() })
     }
 
     /// Resolve a symbol to the interned string.
@@ -174,26 +1664,198 @@ impl<const N: usize, S: BuildHasher> SymbolTable<N, S> {
     /// let foo = table.intern("foo");
     /// assert_eq!(table.resolve(foo), "foo");
     /// ```
-    pub fn resolve(&self, sym: Symbol) -> &str {
-        let shard_i = sym.0.get() >> (32 - Self::SHARD_BITS);
-        debug_assert!(shard_i < N as u32);
+    pub fn resolve(&self, sym: K) -> &str {
+        let bytes = self.resolve_bytes(sym);
+        // SAFETY: only `str`s can be interned through the public API of a
+        // `SymbolTable`, and the `Backend` gives back the same bytes.
+        unsafe { std::str::from_utf8_unchecked(bytes) }
+    }
+
+    /// Resolve a symbol to the interned string, without checking that the
+    /// symbol is valid.
+    ///
+    /// [`resolve`](Self::resolve) checks that the shard and index of the
+    /// symbol are in bounds. This skips those checks, for hot loops over
+    /// symbols that are already known to be valid. It still locks the
+    /// symbol's shard, since interning into the shard on another thread can
+    /// move the backend's index of strings at any time.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let syms = table.intern_words("the cat and the hat");
+    /// // SAFETY: the symbols were just interned into this table
+    /// let words: Vec<&str> = syms.iter().map(|&sym| unsafe { table.resolve_unchecked(sym) }).collect();
+    /// assert_eq!(words, ["the", "cat", "and", "the", "hat"]);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// [`resolve`](Self::resolve) must not panic for `sym`, as checked by
+    /// [`contains_symbol`](Self::contains_symbol). That holds for every
+    /// symbol interned into this table, or into the table it was
+    /// [cloned](Clone) from before the clone, except for symbols of strings
+    /// that were discarded since, by [`rollback_to`](Self::rollback_to) or
+    /// [`compact`](Self::compact). It doesn't hold
+    /// for symbols from other tables, or made up from numbers.
+    pub unsafe fn resolve_unchecked(&self, sym: K) -> &str {
+        let raw = sym.into_usize();
+        if raw & Self::INLINE_TAG != 0 {
+            debug_assert!(self.inline || raw & !Self::INLINE_TAG == inline::EMPTY as usize);
+            return inline::decode((raw & !Self::INLINE_TAG) as u32);
+        }
+
+        let shard_i = raw >> Self::SHARD_SHIFT;
+        let i = (raw & Self::MAX_IDX).wrapping_sub(1) as u32;
+        debug_assert!(shard_i < N);
+        // SAFETY: the caller guarantees the shard and index are in bounds
+        let shard = Self::lock(unsafe { self.shards.get_unchecked(shard_i) });
+        debug_assert!((i as usize) < shard.strs.len());
+        let bytes = unsafe { shard.strs.get_unchecked(i) };
+        // SAFETY: the string outlives the lock like in `resolve_bytes`, and
+        // it's a `str` like in `resolve`
+        unsafe { std::str::from_utf8_unchecked(backend::detach(bytes)) }
+    }
+
+    /// Whether `sym` is a symbol of this table, so resolving it won't panic.
+    ///
+    /// This checks that the shard and index of the symbol are in range for
+    /// the strings in the table, or that it encodes a string if it's
+    /// [inline](Self::with_inline_strings), so symbols from an untrusted
+    /// source can be rejected before resolving them. A symbol of another
+    /// table, or of a string that was [rolled back](Self::rollback_to),
+    /// can still happen to be in range, and then resolves to some other
+    /// string.
+    ///
+    /// ```
+    /// use symbol_table::{Symbol, SymbolTable};
+    ///
+    /// let table = SymbolTable::new();
+    /// let foo = table.intern("foo");
+    /// assert!(table.contains_symbol(foo));
+    /// assert!(table.contains_symbol(Symbol::EMPTY));
+    /// assert!(!table.contains_symbol(Symbol::try_from_u32(12345).unwrap()));
+    /// ```
+    pub fn contains_symbol(&self, sym: K) -> bool {
+        let raw = sym.into_usize();
+        if raw & Self::INLINE_TAG != 0 {
+            let payload = (raw & !Self::INLINE_TAG) as u32;
+            return (self.inline || payload == inline::EMPTY)
+                && inline::try_decode(payload).is_some();
+        }
+        // the stored index is one more than the index, so `0` is never valid
+        let (shard_i, idx) = (raw >> Self::SHARD_SHIFT, raw & Self::MAX_IDX);
+        shard_i < N && idx != 0 && idx <= self.lock_shard(shard_i).strs.len()
+    }
+
+    /// Resolve a symbol to a copy of its string.
+    ///
+    /// The same as `resolve(sym).to_owned()`, for when the string has to
+    /// outlive the table.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let foo = table.intern("foo");
+    /// let owned: String = table.resolve_owned(foo);
+    /// drop(table);
+    /// assert_eq!(owned, "foo");
+    /// ```
+    pub fn resolve_owned(&self, sym: K) -> String {
+        self.resolve(sym).to_owned()
+    }
+
+    /// Compare the strings of two symbols, without copying them.
+    ///
+    /// This locks at most two shards, one after the other, and none if the
+    /// symbols are the same.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let mut syms = vec![table.intern("foo"), table.intern("bar"), table.intern("baz")];
+    /// syms.sort_by(|&a, &b| table.cmp_str(a, b));
+    /// assert_eq!(syms, [table.intern("bar"), table.intern("baz"), table.intern("foo")]);
+    /// ```
+    pub fn cmp_str(&self, a: K, b: K) -> std::cmp::Ordering {
+        if a == b {
+            return std::cmp::Ordering::Equal;
+        }
+        // the bytes of `str`s compare like the `str`s do
+        self.resolve_bytes(a).cmp(self.resolve_bytes(b))
+    }
+
+    /// Whether two symbols have the same string.
+    ///
+    /// Every string is only stored once, so for symbols from this table,
+    /// this is the same as comparing the symbols themselves, and doesn't
+    /// lock anything.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let foo = table.intern("foo");
+    /// assert!(table.eq_str(foo, table.intern("foo")));
+    /// assert!(!table.eq_str(foo, table.intern("bar")));
+    /// ```
+    pub fn eq_str(&self, a: K, b: K) -> bool {
+        a == b
+    }
+
+    /// Iterate over the symbols of all the strings stored in the table.
+    ///
+    /// This is a snapshot: strings interned while iterating aren't included.
+    /// Neither are [inline](Self::with_inline_strings) strings, which aren't
+    /// stored in the table.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let foo = table.intern("foo");
+    /// let bar = table.intern("bar");
+    ///
+    /// let mut syms: Vec<_> = table.symbols().collect();
+    /// syms.sort_by_key(|&sym| table.resolve(sym));
+    /// assert_eq!(syms, [bar, foo]);
+    /// ```
+    pub fn symbols(&self) -> impl Iterator<Item = K> {
+        let lens: Vec<u32> = (0..N)
+            .map(|i| self.shards[i].lock().unwrap().strs.len() as u32)
+            .collect();
+        lens.into_iter()
+            .enumerate()
+            .flat_map(|(shard_i, len)| (0..len).map(move |idx| Self::make_symbol(shard_i, idx)))
+    }
+
+    pub(crate) fn resolve_bytes(&self, sym: K) -> &[u8] {
+        let raw = sym.into_usize();
+        if raw & Self::INLINE_TAG != 0 {
+            // wrappers like `CStrSymbolTable` rely on every string coming
+            // from their own backend, except for the empty one, which they
+            // have to handle themselves
+            let payload = (raw & !Self::INLINE_TAG) as u32;
+            assert!(
+                self.inline || payload == inline::EMPTY,
+                "Can't resolve an inline symbol in a table without inline strings"
+            );
+            return inline::decode(payload).as_bytes();
+        }
+
+        let shard_i = raw >> Self::SHARD_SHIFT;
+        debug_assert!(shard_i < N);
         // println!("Resolving from shard {shard_i}");
-        let i = sym.0.get() & (u32::MAX >> Self::SHARD_BITS);
+        let i = raw & Self::MAX_IDX;
         debug_assert!(i > 0);
-        let i = i - 1; // undo the + 1 from interning
-        let shard = self.shards[shard_i as usize].lock().unwrap();
+        let i = (i - 1) as u32; // undo the + 1 from interning
+        let shard = self.lock_shard(shard_i);
         debug_assert!(
             !shard.strs.is_empty(),
             "Shard shouldn't be empty when resolving!"
         );
-        let str: &str = &shard.strs[i as usize];
+        let str: &[u8] = shard.strs.get(i);
 
         // SAFETY:
         // We can "extend" the lifetime of str outside the mutex lock
-        // because we know it will never move or be mutated. The only thing to
+        // because we know it will never move or be mutated, which is
+        // guaranteed by the `Backend` implementation. The only thing to
         // worry about is it getting dropped, but that's ok because it's
         // lifetime is less than `self`.
-        unsafe { &*(str as *const str) }
+        unsafe { backend::detach(str) }
     }
 }
 
@@ -207,17 +1869,91 @@ impl<const N: usize, S: BuildHasher> SymbolTable<N, S> {
 /// # use std::mem::size_of; use symbol_table::Symbol;
 /// assert_eq!(size_of::<Symbol>(), size_of::<u32>());
 /// ```
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///
+/// A [`Symbol`] is `#[repr(transparent)]`, so it has the same layout as a
+/// `u32`, and an array of them can be handed over FFI as an array of
+/// `u32`s. To store symbols as plain numbers, use [`to_u32`](Self::to_u32)
+/// and [`try_from_u32`](Self::try_from_u32):
+///
+/// ```
+/// use symbol_table::{Symbol, SymbolTable};
+///
+/// let table = SymbolTable::new();
+/// let foo = table.intern("foo");
+/// let raw: u32 = foo.to_u32();
+/// assert_eq!(Symbol::try_from_u32(raw), Some(foo));
+/// assert_eq!(Symbol::try_from_u32(0), None);
+/// ```
+///
+/// The number only means something to the table that handed out the
+/// symbol.
+///
+/// To cast buffers of symbols to and from bytes without converting every
+/// one, the `bytemuck` feature implements `NoUninit`, `CheckedBitPattern`,
+/// `ZeroableInOption` and `PodInOption` for every [`SymbolKey`], and the
+/// `zerocopy` feature derives `IntoBytes`, `TryFromBytes`, `Immutable` and
+/// `KnownLayout`. Symbols are never zero, so casting bytes to symbols is
+/// checked, but an `Option<Symbol>` is `Pod`, with zero for `None`.
+///
+/// A symbol [debugs](fmt::Debug) as its shard and index, which
+/// [`set_debug_table`](Self::set_debug_table) can extend with its string.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(
+        zerocopy::IntoBytes,
+        zerocopy::Immutable,
+        zerocopy::KnownLayout,
+        zerocopy::TryFromBytes
+    )
+)]
+#[repr(transparent)]
 pub struct Symbol(NonZeroU32);
 
-impl From<NonZeroU32> for Symbol {
-    fn from(i: NonZeroU32) -> Self {
-        Symbol(i)
+impl Symbol {
+    /// The symbol of the empty string, in every table.
+    ///
+    /// The empty string is never stored: like an
+    /// [inline](SymbolTable::with_inline_strings) string, it's encoded in its
+    /// symbol, even in tables without inline strings. So comparing with this
+    /// is the cheap way to check for it, without interning it.
+    ///
+    /// ```
+    /// use symbol_table::{Symbol, SymbolTable};
+    ///
+    /// let table = SymbolTable::new();
+    /// assert_eq!(table.intern(""), Symbol::EMPTY);
+    /// assert_eq!(table.resolve(Symbol::EMPTY), "");
+    /// ```
+    // the inline tag, with the payload of the empty string
+    pub const EMPTY: Self = match Self::try_from_u32(1 << 31 | inline::EMPTY) {
+        Some(sym) => sym,
+        None => unreachable!(),
+    };
+
+    /// The raw number of this symbol, which is never zero.
+    #[inline]
+    pub const fn to_u32(self) -> u32 {
+        self.0.get()
     }
-}
 
-impl From<Symbol> for NonZeroU32 {
-    fn from(sym: Symbol) -> Self {
-        sym.0
+    /// The symbol with the raw number `raw`, or `None` if it's zero.
+    #[inline]
+    pub const fn try_from_u32(raw: u32) -> Option<Self> {
+        match NonZeroU32::new(raw) {
+            Some(raw) => Some(Symbol(raw)),
+            None => None,
+        }
+    }
+
+    /// The symbol with the raw number `raw`, without checking it.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must not be zero.
+    #[inline]
+    pub const unsafe fn from_u32_unchecked(raw: u32) -> Self {
+        // SAFETY: guaranteed by the caller
+        Symbol(unsafe { NonZeroU32::new_unchecked(raw) })
     }
 }
@@ -0,0 +1,177 @@
+use crate::*;
+
+use std::{cell::RefCell, convert::Infallible};
+
+/// A [`SymbolTable`] for a single thread, without any locks.
+///
+/// The shards of a [`SymbolTable`] are locked on every intern and resolve,
+/// and padded to their own cache lines, which is wasted work when only one
+/// thread uses the table, as in many command-line tools. This table keeps
+/// the same API, with [`intern`](Self::intern) taking `&self`, but its
+/// shards are [`RefCell`]s, so it isn't [`Sync`].
+///
+/// It gives the same symbols as a [`SymbolTable`] with the same generic
+/// arguments, using [all](SymbolTable::with_shards) of its shards, that the
+/// same strings were interned into in the same order,
+/// so code that only deals in symbols doesn't need to know which one it got.
+///
+/// ```
+/// use symbol_table::LocalSymbolTable;
+///
+/// let table = LocalSymbolTable::new();
+/// let foo = table.intern("foo");
+/// assert_eq!(table.intern("foo"), foo);
+/// assert_eq!(table.resolve(foo), "foo");
+/// assert_eq!(foo, symbol_table::SymbolTable::new().intern("foo"));
+/// ```
+///
+/// It can still be sent to another thread, but not shared between threads:
+///
+/// ```compile_fail
+/// let table = symbol_table::LocalSymbolTable::new();
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| table.intern("foo"));
+/// });
+/// ```
+pub struct LocalSymbolTable<
+    const N: usize = DEFAULT_N_SHARDS,
+    S = DeterministicHashBuilder,
+    B = StringBackend,
+    K = Symbol,
+> {
+    build_hasher: S,
+    inline: bool,
+    shards: [RefCell<Shard<B>>; N],
+    _key: PhantomData<K>,
+}
+
+impl LocalSymbolTable<DEFAULT_N_SHARDS, DeterministicHashBuilder> {
+    /// Creates a new [`LocalSymbolTable`] with the default generic arguments.
+    pub const fn new() -> Self {
+        Self::with_hasher(DeterministicHashBuilder)
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> LocalSymbolTable<N, S, B, K> {
+    /// Creates a new [`LocalSymbolTable`] with a custom hasher.
+    #[allow(clippy::assertions_on_constants)]
+    pub const fn with_hasher(build_hasher: S) -> Self {
+        assert!(0 < N);
+        assert!(Self::SHARD_BITS < K::BITS - 1);
+        Self {
+            build_hasher,
+            inline: false,
+            shards: [const { RefCell::new(Shard::new()) }; N],
+            _key: PhantomData,
+        }
+    }
+
+    const SHARD_BITS: u32 = SymbolTable::<N, S, B, K>::SHARD_BITS;
+    const INLINE_TAG: usize = SymbolTable::<N, S, B, K>::INLINE_TAG;
+
+    /// Encode very short strings directly in their symbols, see
+    /// [`SymbolTable::with_inline_strings`].
+    ///
+    /// # Panics
+    ///
+    /// Inline symbols need at least 17 bits, so this panics with a 16-bit
+    /// [`SymbolKey`].
+    pub const fn with_inline_strings(mut self) -> Self {
+        assert!(K::BITS > 16, "Inline symbols need more than 16 bits");
+        self.inline = true;
+        self
+    }
+
+    /// Intern a string into the [`LocalSymbolTable`].
+    pub fn intern(&self, string: &str) -> K {
+        self.intern_internable(string)
+    }
+
+    /// Intern an owned string, reusing its allocation if it's new, see
+    /// [`SymbolTable::intern_owned`].
+    pub fn intern_owned(&self, string: String) -> K {
+        self.intern_internable(string)
+    }
+
+    fn intern_internable(&self, string: impl Internable) -> K {
+        if let Some(sym) = self.inline_symbol(string.as_ref()) {
+            return sym;
+        }
+        let hash = hash_one(&self.build_hasher, string.as_ref());
+        let shard_i = shard_of_hash(hash, N);
+        let mut shard = self.shards[shard_i].borrow_mut();
+        let idx = match shard.intern(hash, string, |_| Ok::<_, Infallible>(())) {
+            Ok(idx) => idx,
+            Err(never) => match never {},
+        };
+        SymbolTable::<N, S, B, K>::make_symbol(shard_i, idx)
+    }
+
+    /// The symbol of a string, if it's already in the table.
+    pub fn lookup(&self, string: &str) -> Option<K> {
+        if let Some(sym) = self.inline_symbol(string.as_bytes()) {
+            return Some(sym);
+        }
+        let hash = hash_one(&self.build_hasher, string.as_bytes());
+        let shard_i = shard_of_hash(hash, N);
+        let idx = self.shards[shard_i]
+            .borrow()
+            .find(hash, string.as_bytes())?;
+        Some(SymbolTable::<N, S, B, K>::make_symbol(shard_i, idx))
+    }
+
+    fn inline_symbol(&self, string: &[u8]) -> Option<K> {
+        let payload = inline::encode(string).filter(|&p| self.inline || p == inline::EMPTY)?;
+        Some(K::try_from_usize(Self::INLINE_TAG | payload as usize).unwrap())
+    }
+
+    /// Resolve a symbol to the interned string.
+    ///
+    /// The resolved string is immutable and will live as long as the
+    /// [`LocalSymbolTable`].
+    pub fn resolve(&self, sym: K) -> &str {
+        let Some((shard_i, idx)) = SymbolTable::<N, S, B, K>::split_symbol(sym) else {
+            let payload = (sym.into_usize() & !Self::INLINE_TAG) as u32;
+            assert!(
+                self.inline || payload == inline::EMPTY,
+                "Can't resolve an inline symbol in a table without inline strings"
+            );
+            return inline::decode(payload);
+        };
+        let shard = self.shards[shard_i].borrow();
+        let bytes: &[u8] = shard.strs.get(idx);
+        // SAFETY: the `Backend` guarantees the string never moves, and it
+        // lives as long as `self`, see `SymbolTable::resolve`. Only `str`s
+        // are ever pushed, so it's still valid UTF-8.
+        unsafe { std::str::from_utf8_unchecked(backend::detach(bytes)) }
+    }
+
+    /// The number of strings stored in the table.
+    ///
+    /// Like [`SymbolTable::symbols`], this doesn't count inline strings.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|s| s.borrow().strs.len()).sum()
+    }
+
+    /// Whether no strings are stored in the table.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every symbol whose string is stored in the table, shard by shard in
+    /// interning order.
+    pub fn symbols(&self) -> impl Iterator<Item = K> + '_ {
+        (0..N).flat_map(move |shard_i| {
+            let len = self.shards[shard_i].borrow().strs.len() as u32;
+            (0..len).map(move |idx| SymbolTable::<N, S, B, K>::make_symbol(shard_i, idx))
+        })
+    }
+}
+
+impl<const N: usize, S: Default + BuildHasher, B: Backend, K: SymbolKey> Default
+    for LocalSymbolTable<N, S, B, K>
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
@@ -0,0 +1,144 @@
+//! Lock-free stand-ins for the shard locks, on targets without threads.
+//!
+//! On `wasm32` without the `atomics` target feature there can't be more than
+//! one thread, so locking a shard and padding it to its own cache line is
+//! pure overhead. These types have the same API as the [`std::sync::Mutex`],
+//! [`std::sync::RwLock`] and [`crossbeam_utils::CachePadded`] used everywhere
+//! else, so the tables don't need to know which ones they get.
+
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    convert::Infallible,
+    ops::{Deref, DerefMut},
+    panic::{RefUnwindSafe, UnwindSafe},
+    sync::LockResult,
+};
+
+/// A [`std::sync::Mutex`] that is only ever used by one thread.
+///
+/// Locking it while it's locked panics, instead of deadlocking.
+#[derive(Debug, Default)]
+pub(crate) struct Mutex<T: ?Sized>(RefCell<T>);
+
+// SAFETY: without threads, there's nothing to share the value with
+unsafe impl<T: ?Sized> Sync for Mutex<T> {}
+
+// like the real one, so the tables are unwind-safe on every target
+impl<T: ?Sized> UnwindSafe for Mutex<T> {}
+impl<T: ?Sized> RefUnwindSafe for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self(RefCell::new(value))
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    // These never fail, but return a `Result` like the real ones do.
+
+    pub(crate) fn lock(&self) -> Result<RefMut<'_, T>, Infallible> {
+        Ok(self.0.borrow_mut())
+    }
+
+    pub(crate) fn try_lock(&self) -> Result<RefMut<'_, T>, std::cell::BorrowMutError> {
+        self.0.try_borrow_mut()
+    }
+
+    pub(crate) fn get_mut(&mut self) -> Result<&mut T, Infallible> {
+        Ok(self.0.get_mut())
+    }
+}
+
+/// A [`std::sync::RwLock`] that is only ever used by one thread.
+///
+/// Writing to it while it's read or written panics, instead of deadlocking.
+#[derive(Debug, Default)]
+pub(crate) struct RwLock<T: ?Sized>(RefCell<T>);
+
+// SAFETY: without threads, there's nothing to share the value with
+unsafe impl<T: ?Sized> Sync for RwLock<T> {}
+
+impl<T: ?Sized> UnwindSafe for RwLock<T> {}
+impl<T: ?Sized> RefUnwindSafe for RwLock<T> {}
+
+impl<T> RwLock<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self(RefCell::new(value))
+    }
+}
+
+impl<T: ?Sized> RwLock<T> {
+    // These are never poisoned, but return a `LockResult` like the real ones
+    // do, so callers can recover from poisoning the same way on every target.
+
+    pub(crate) fn read(&self) -> LockResult<Ref<'_, T>> {
+        Ok(self.0.borrow())
+    }
+
+    pub(crate) fn write(&self) -> LockResult<RefMut<'_, T>> {
+        Ok(self.0.borrow_mut())
+    }
+}
+
+/// A [`crossbeam_utils::CachePadded`] that doesn't pad.
+#[derive(Debug, Default)]
+pub(crate) struct CachePadded<T>(T);
+
+impl<T> CachePadded<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for CachePadded<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock() {
+        let mut shard = CachePadded::new(Mutex::new(Vec::new()));
+        shard.lock().unwrap().push("foo");
+        shard.get_mut().unwrap().push("bar");
+        assert_eq!(*shard.lock().unwrap(), ["foo", "bar"]);
+    }
+
+    #[test]
+    fn test_try_lock() {
+        let shard = Mutex::new(0);
+        let guard = shard.try_lock().unwrap();
+        assert!(shard.try_lock().is_err());
+        drop(guard);
+        *shard.try_lock().unwrap() += 1;
+        assert_eq!(*shard.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_rw_lock() {
+        let lock = RwLock::new(0);
+        *lock.write().unwrap() += 1;
+        let (a, b) = (lock.read().unwrap(), lock.read().unwrap());
+        assert_eq!((*a, *b), (1, 1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_lock_twice() {
+        let shard = Mutex::new(0);
+        let _guard = shard.lock().unwrap();
+        let _ = shard.lock();
+    }
+}
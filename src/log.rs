@@ -0,0 +1,118 @@
+use crate::*;
+
+use std::io::{self, Read};
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Append every new string to a log, so the table can be restored by
+    /// [replaying](Self::replay_log) it.
+    ///
+    /// Each string is written as a little-endian `u32` length and then its
+    /// bytes, while its shard is locked, and the log is flushed after every
+    /// string. That keeps the strings of each shard in order, which is all
+    /// that's needed to reproduce the same symbols, and makes the log
+    /// crash-safe: at worst, the last string is only partially written.
+    ///
+    /// If writing to the log fails, the string isn't interned, and
+    /// [`try_intern`](Self::try_intern) returns [`InternError::Log`].
+    ///
+    /// ```
+    /// use std::fs::{File, OpenOptions};
+    /// use symbol_table::SymbolTable;
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("symbols.log");
+    /// let log = OpenOptions::new().create(true).append(true).open(&path).unwrap();
+    /// let table = SymbolTable::new().with_log(log);
+    /// let foo = table.intern("foo");
+    ///
+    /// // after a restart
+    /// let restored = SymbolTable::new();
+    /// let len = restored.replay_log(File::open(&path).unwrap()).unwrap();
+    /// assert_eq!(restored.resolve(foo), "foo");
+    ///
+    /// // drop a partially written string, and keep logging
+    /// let log = OpenOptions::new().append(true).open(&path).unwrap();
+    /// log.set_len(len).unwrap();
+    /// let restored = restored.with_log(log);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table has [thread affinity](Self::with_thread_affinity),
+    /// since the log would replay its strings into the shards they hash to.
+    pub fn with_log(mut self, log: impl Write + Send + 'static) -> Self {
+        assert!(
+            !self.thread_affinity,
+            "Can't log a table with thread affinity"
+        );
+        self.log = Some(Mutex::new(Box::new(log)));
+        self
+    }
+
+    /// Intern all the strings in a log written by a table
+    /// [`with_log`](Self::with_log).
+    ///
+    /// Replaying into an empty table with the same shard count and hasher as
+    /// the logged one gives every string the same symbol it had. The strings
+    /// aren't transformed again.
+    ///
+    /// Replay the log before attaching it to this table, or every string
+    /// would be logged again.
+    ///
+    /// Returns the length of the complete strings in the log. If the log ends
+    /// in a partially written string, e.g. after a crash, that string is
+    /// skipped; truncate the log to the returned length before appending to
+    /// it again.
+    pub fn replay_log(&self, mut log: impl Read) -> io::Result<u64> {
+        let mut valid = 0;
+        let mut buf = Vec::new();
+        loop {
+            let mut len = [0; 4];
+            if read_full(&mut log, &mut len)? < len.len() {
+                return Ok(valid);
+            }
+            let len = u32::from_le_bytes(len) as u64;
+            buf.clear();
+            (&mut log).take(len).read_to_end(&mut buf)?;
+            if buf.len() as u64 != len {
+                return Ok(valid);
+            }
+
+            let string = std::str::from_utf8(&buf)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            self.try_intern_internable(string)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            valid += 4 + len;
+        }
+    }
+
+    /// Append a new string to the log, if there is one.
+    pub(crate) fn append_to_log(&self, string: &[u8]) -> io::Result<()> {
+        let Some(log) = &self.log else {
+            return Ok(());
+        };
+        let mut log = log.lock().unwrap();
+        let len: u32 = string
+            .len()
+            .try_into()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "string too long to log"))?;
+        log.write_all(&len.to_le_bytes())?;
+        log.write_all(string)?;
+        log.flush()
+    }
+}
+
+/// Read as many bytes as possible into `buf`, stopping early only at the end
+/// of the reader.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(read)
+}
@@ -0,0 +1,248 @@
+use crate::*;
+
+use hashbrown::HashTable;
+use std::num::NonZeroU64;
+
+/// A symbol table that holds at most a fixed number of strings, evicting
+/// the least recently used ones to make room.
+///
+/// This is for cache-like workloads, like deduplicating the header values
+/// of a proxy, where a [`SymbolTable`] would grow without bounds. Evicting
+/// a string invalidates its symbols, so instead of `&str`s, resolving gives
+/// `Option<Arc<str>>`s, which are `None` for evicted strings. Every symbol
+/// is tagged with a generation, so a symbol of an evicted string never
+/// resolves to the string that took its place; interning the evicted string
+/// again gives a new symbol.
+///
+/// The table is sharded `N` times like a [`SymbolTable`], and each shard
+/// holds an equal part of the capacity and evicts its own least recently
+/// used string, so the eviction order is only approximately LRU overall.
+/// Interning and resolving both count as using a string.
+///
+/// ```
+/// use symbol_table::{DeterministicHashBuilder, LruSymbolTable};
+///
+/// // one shard, to evict in exact LRU order
+/// let table = LruSymbolTable::<1, _>::with_hasher(2, DeterministicHashBuilder);
+/// let a = table.intern("a");
+/// let b = table.intern("b");
+/// assert_eq!(table.intern("a"), a);
+///
+/// // "b" is the least recently used, so it makes room for "c"
+/// let c = table.intern("c");
+/// assert_eq!(table.resolve(b), None);
+/// assert_eq!(table.resolve(a).as_deref(), Some("a"));
+/// assert_eq!(table.resolve(c).as_deref(), Some("c"));
+/// assert_ne!(table.intern("b"), b);
+/// ```
+pub struct LruSymbolTable<const N: usize = DEFAULT_N_SHARDS, S = DeterministicHashBuilder> {
+    build_hasher: S,
+    shard_capacity: u32,
+    shards: [CachePadded<Mutex<LruShard>>; N],
+}
+
+/// A symbol from an [`LruSymbolTable`].
+///
+/// It's the number of the slot the string is stored in, tagged with how
+/// many times that slot was reused before, so it only resolves while its
+/// string isn't evicted. A slot can be reused 2^32 times before its
+/// symbols repeat.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LruSymbol(NonZeroU64);
+
+// the end of a list of slots
+const NIL: u32 = u32::MAX;
+
+struct LruShard {
+    // the index of every string in `slots`, by hash
+    map: HashTable<u32>,
+    slots: Vec<Slot>,
+    // the most and least recently used slots
+    head: u32,
+    tail: u32,
+}
+
+struct Slot {
+    string: Arc<str>,
+    hash: u64,
+    generation: u32,
+    // the slots used right before and after this one
+    prev: u32,
+    next: u32,
+}
+
+impl LruSymbolTable<DEFAULT_N_SHARDS, DeterministicHashBuilder> {
+    /// Creates a new [`LruSymbolTable`] that holds at most about `capacity`
+    /// strings, with the default generic arguments.
+    ///
+    /// # Panics
+    ///
+    /// See [`with_hasher`](Self::with_hasher).
+    pub fn new(capacity: usize) -> Self {
+        Self::with_hasher(capacity, DeterministicHashBuilder)
+    }
+}
+
+impl<const N: usize, S: BuildHasher> LruSymbolTable<N, S> {
+    /// Creates a new [`LruSymbolTable`] with a custom hasher.
+    ///
+    /// The capacity is split evenly over the shards, rounding up, so the
+    /// table may hold up to `N - 1` more strings than `capacity`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero, or if the table would hold 2^32 strings
+    /// or more.
+    pub fn with_hasher(capacity: usize, build_hasher: S) -> Self {
+        assert!(0 < N);
+        assert!(capacity > 0, "An LruSymbolTable needs room for a string");
+        let shard_capacity = capacity.div_ceil(N);
+        assert!(
+            shard_capacity
+                .checked_mul(N)
+                .is_some_and(|c| c < u32::MAX as usize),
+            "Can't hold {capacity} strings in an LruSymbolTable"
+        );
+        Self {
+            build_hasher,
+            shard_capacity: shard_capacity as u32,
+            shards: std::array::from_fn(|_| {
+                CachePadded::new(Mutex::new(LruShard {
+                    map: HashTable::new(),
+                    slots: Vec::new(),
+                    head: NIL,
+                    tail: NIL,
+                }))
+            }),
+        }
+    }
+
+    /// The most strings this table holds at once.
+    pub fn capacity(&self) -> usize {
+        self.shard_capacity as usize * N
+    }
+
+    /// Intern a string into the [`LruSymbolTable`].
+    ///
+    /// If the string is new and its shard is full, this evicts the least
+    /// recently used string of the shard, whose symbols won't resolve
+    /// anymore.
+    pub fn intern(&self, string: &str) -> LruSymbol {
+        let hash = hash_one(&self.build_hasher, string.as_bytes());
+        let shard_i = hash as usize % N;
+        let mut shard = self.shards[shard_i].lock().unwrap();
+        let shard = &mut *shard;
+        let slots = &shard.slots;
+        let found = shard
+            .map
+            .find(hash, |&idx| *slots[idx as usize].string == *string);
+        let idx = match found {
+            Some(&idx) => {
+                shard.unlink(idx);
+                idx
+            }
+            None if shard.slots.len() < self.shard_capacity as usize => {
+                shard.slots.push(Slot {
+                    string: string.into(),
+                    hash,
+                    generation: 0,
+                    prev: NIL,
+                    next: NIL,
+                });
+                let idx = shard.slots.len() as u32 - 1;
+                let slots = &shard.slots;
+                shard
+                    .map
+                    .insert_unique(hash, idx, |&idx| slots[idx as usize].hash);
+                idx
+            }
+            None => {
+                let idx = shard.tail;
+                shard.unlink(idx);
+                let old_hash = shard.slots[idx as usize].hash;
+                match shard.map.find_entry(old_hash, |&i| i == idx) {
+                    Ok(entry) => drop(entry.remove()),
+                    Err(_) => unreachable!(),
+                }
+                let slot = &mut shard.slots[idx as usize];
+                slot.string = string.into();
+                slot.hash = hash;
+                slot.generation = slot.generation.wrapping_add(1);
+                let slots = &shard.slots;
+                shard
+                    .map
+                    .insert_unique(hash, idx, |&idx| slots[idx as usize].hash);
+                idx
+            }
+        };
+        shard.push_front(idx);
+        self.make_symbol(shard_i, idx, shard.slots[idx as usize].generation)
+    }
+
+    /// Resolve a symbol to its string, unless the string was evicted.
+    ///
+    /// The string is shared with the table, so it stays alive even if it's
+    /// evicted afterwards. This counts as using the string, so it won't be
+    /// the next one evicted.
+    pub fn resolve(&self, sym: LruSymbol) -> Option<Arc<str>> {
+        let raw = sym.0.get();
+        let (generation, slot) = ((raw >> 32) as u32, (raw as u32) - 1);
+        let shard_i = (slot / self.shard_capacity) as usize;
+        let idx = slot % self.shard_capacity;
+        let mut shard = self.shards.get(shard_i)?.lock().unwrap();
+        let string = match shard.slots.get(idx as usize) {
+            Some(s) if s.generation == generation => s.string.clone(),
+            _ => return None,
+        };
+        shard.unlink(idx);
+        shard.push_front(idx);
+        Some(string)
+    }
+
+    /// The number of strings in this table.
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|s| s.lock().unwrap().slots.len())
+            .sum()
+    }
+
+    /// Whether this table holds no strings.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn make_symbol(&self, shard_i: usize, idx: u32, generation: u32) -> LruSymbol {
+        let slot = shard_i as u32 * self.shard_capacity + idx + 1;
+        LruSymbol(NonZeroU64::new((generation as u64) << 32 | slot as u64).unwrap())
+    }
+}
+
+impl LruShard {
+    /// Take a slot out of the recency list.
+    fn unlink(&mut self, idx: u32) {
+        let Slot { prev, next, .. } = self.slots[idx as usize];
+        match prev {
+            NIL => self.head = next,
+            prev => self.slots[prev as usize].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.slots[next as usize].prev = prev,
+        }
+    }
+
+    /// Put a slot that isn't in the recency list first, as the most
+    /// recently used.
+    fn push_front(&mut self, idx: u32) {
+        let head = self.head;
+        let slot = &mut self.slots[idx as usize];
+        slot.prev = NIL;
+        slot.next = head;
+        match head {
+            NIL => self.tail = idx,
+            head => self.slots[head as usize].prev = idx,
+        }
+        self.head = idx;
+    }
+}
@@ -0,0 +1,170 @@
+use crate::*;
+
+// for decoding symbols, which doesn't depend on the hasher or the backend
+pub(crate) type Table<const N: usize, K> = SymbolTable<N, (), (), K>;
+
+/// A map from the symbols of a [`SymbolTable`] to values, without hashing.
+///
+/// A symbol is made of the shard of its string and the string's index in
+/// that shard, so this keeps a `Vec` of values per shard, indexed by the
+/// symbol, like a secondary map. Every operation is a bounds check and an
+/// index, and each `Vec` is as long as the largest index of a key in its
+/// shard, so this is best for side data about most of a table's symbols.
+///
+/// The keys must come from a table with the same number of shards `N` and
+/// the same [`SymbolKey`] `K`. [Inline](SymbolTable::with_inline_strings)
+/// symbols don't have an index, so they can't be keys.
+///
+/// ```
+/// use symbol_table::{SymbolMap, SymbolTable};
+///
+/// let table = SymbolTable::new();
+/// let (x, y) = (table.intern("x"), table.intern("y"));
+///
+/// let mut types: SymbolMap<&str> = SymbolMap::new();
+/// types.insert(x, "int");
+/// assert_eq!(types.get(x), Some(&"int"));
+/// assert_eq!(types.get(y), None);
+/// assert_eq!(types[x], "int");
+/// ```
+pub struct SymbolMap<V, const N: usize = DEFAULT_N_SHARDS, K = Symbol> {
+    shards: [Vec<Option<V>>; N],
+    len: usize,
+    _key: PhantomData<K>,
+}
+
+impl<V, const N: usize, K: SymbolKey> SymbolMap<V, N, K> {
+    /// Creates an empty [`SymbolMap`].
+    pub const fn new() -> Self {
+        Self {
+            shards: [const { Vec::new() }; N],
+            len: 0,
+            _key: PhantomData,
+        }
+    }
+
+    /// The number of symbols in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether there are no symbols in the map.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether `sym` has a value in the map.
+    pub fn contains_key(&self, sym: K) -> bool {
+        self.get(sym).is_some()
+    }
+
+    /// The value of `sym`, if it has one.
+    pub fn get(&self, sym: K) -> Option<&V> {
+        let (shard_i, idx) = Table::<N, K>::split_symbol(sym)?;
+        self.shards.get(shard_i)?.get(idx as usize)?.as_ref()
+    }
+
+    /// The value of `sym`, mutably, if it has one.
+    pub fn get_mut(&mut self, sym: K) -> Option<&mut V> {
+        let (shard_i, idx) = Table::<N, K>::split_symbol(sym)?;
+        self.shards
+            .get_mut(shard_i)?
+            .get_mut(idx as usize)?
+            .as_mut()
+    }
+
+    /// Set the value of `sym`, returning its old value, if it had one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sym` is an inline symbol.
+    pub fn insert(&mut self, sym: K, value: V) -> Option<V> {
+        let (shard_i, idx) =
+            Table::<N, K>::split_symbol(sym).expect("Inline symbols can't be keys of a SymbolMap");
+        let shard = &mut self.shards[shard_i];
+        if shard.len() <= idx as usize {
+            shard.resize_with(idx as usize + 1, || None);
+        }
+        let old = shard[idx as usize].replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Remove the value of `sym`, returning it, if it had one.
+    pub fn remove(&mut self, sym: K) -> Option<V> {
+        let (shard_i, idx) = Table::<N, K>::split_symbol(sym)?;
+        let old = self.shards.get_mut(shard_i)?.get_mut(idx as usize)?.take();
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    /// Remove all the values, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        for shard in &mut self.shards {
+            shard.clear();
+        }
+        self.len = 0;
+    }
+
+    /// Iterate over the symbols and their values, shard by shard in index
+    /// order.
+    pub fn iter(&self) -> impl Iterator<Item = (K, &V)> {
+        self.shards.iter().enumerate().flat_map(|(shard_i, shard)| {
+            shard.iter().enumerate().filter_map(move |(idx, value)| {
+                let sym = Table::<N, K>::make_symbol(shard_i, idx as u32);
+                Some((sym, value.as_ref()?))
+            })
+        })
+    }
+}
+
+impl<V, const N: usize, K: SymbolKey> Default for SymbolMap<V, N, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Clone, const N: usize, K: SymbolKey> Clone for SymbolMap<V, N, K> {
+    fn clone(&self) -> Self {
+        Self {
+            shards: self.shards.clone(),
+            len: self.len,
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<V: fmt::Debug, const N: usize, K: SymbolKey> fmt::Debug for SymbolMap<V, N, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<V, const N: usize, K: SymbolKey> std::ops::Index<K> for SymbolMap<V, N, K> {
+    type Output = V;
+
+    fn index(&self, sym: K) -> &V {
+        self.get(sym)
+            .expect("No value for the symbol in the SymbolMap")
+    }
+}
+
+impl<V, const N: usize, K: SymbolKey> Extend<(K, V)> for SymbolMap<V, N, K> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, entries: I) {
+        for (sym, value) in entries {
+            self.insert(sym, value);
+        }
+    }
+}
+
+impl<V, const N: usize, K: SymbolKey> FromIterator<(K, V)> for SymbolMap<V, N, K> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(entries: I) -> Self {
+        let mut map = Self::new();
+        map.extend(entries);
+        map
+    }
+}
@@ -0,0 +1,80 @@
+use crate::*;
+
+use std::{ffi::OsStr, path::Path};
+
+/// A table in which you can intern [`OsStr`]s and [`Path`]s.
+///
+/// File paths aren't necessarily valid UTF-8, so interning them into a
+/// [`SymbolTable`] would need a lossy conversion. This table stores the
+/// platform's own encoding of the string instead, so paths round-trip
+/// exactly.
+///
+/// ```
+/// use std::path::Path;
+/// use symbol_table::OsSymbolTable;
+///
+/// let table = OsSymbolTable::new();
+/// let sym = table.intern_path(Path::new("src/lib.rs"));
+/// assert_eq!(table.intern_os("src/lib.rs".as_ref()), sym);
+/// assert_eq!(table.resolve_path(sym), Path::new("src/lib.rs"));
+/// ```
+pub struct OsSymbolTable<
+    const N: usize = DEFAULT_N_SHARDS,
+    S = DeterministicHashBuilder,
+    B = StringBackend,
+    K = Symbol,
+> {
+    // Only ever holds bytes from `OsStr::as_encoded_bytes`.
+    table: SymbolTable<N, S, B, K>,
+}
+
+impl OsSymbolTable<DEFAULT_N_SHARDS, DeterministicHashBuilder> {
+    /// Creates a new [`OsSymbolTable`] with the default generic arguments.
+    pub const fn new() -> Self {
+        Self::with_hasher(DeterministicHashBuilder)
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> OsSymbolTable<N, S, B, K> {
+    /// Creates a new [`OsSymbolTable`] with a custom hasher.
+    pub const fn with_hasher(build_hasher: S) -> Self {
+        Self {
+            table: SymbolTable::with_hasher(build_hasher),
+        }
+    }
+
+    #[cfg(feature = "test-util")]
+    pub(crate) fn reset(&self) {
+        self.table.reset()
+    }
+
+    /// Intern an [`OsStr`] into the [`OsSymbolTable`].
+    pub fn intern_os(&self, string: &OsStr) -> K {
+        self.table.intern_internable(string.as_encoded_bytes())
+    }
+
+    /// Intern a [`Path`] into the [`OsSymbolTable`].
+    pub fn intern_path(&self, path: &Path) -> K {
+        self.intern_os(path.as_os_str())
+    }
+
+    /// Resolve a symbol to the interned [`OsStr`].
+    pub fn resolve_os(&self, sym: K) -> &OsStr {
+        let bytes = self.table.resolve_bytes(sym);
+        // SAFETY: the bytes came from `OsStr::as_encoded_bytes` in this process
+        unsafe { OsStr::from_encoded_bytes_unchecked(bytes) }
+    }
+
+    /// Resolve a symbol to the interned [`Path`].
+    pub fn resolve_path(&self, sym: K) -> &Path {
+        Path::new(self.resolve_os(sym))
+    }
+}
+
+impl<const N: usize, S: Default + BuildHasher, B: Backend, K: SymbolKey> Default
+    for OsSymbolTable<N, S, B, K>
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
@@ -0,0 +1,219 @@
+use crate::*;
+
+use std::io::{self, Read, Write};
+
+/// The first bytes of a table saved by [`SymbolTable::save_to`].
+const MAGIC: &[u8; 8] = b"symtab\0\0";
+
+/// The version of the format written by [`SymbolTable::save_to`].
+const VERSION: u32 = 1;
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Save the table to a writer, so [`load_from`](Self::load_from) can
+    /// restore it with the exact same symbols.
+    ///
+    /// The format is versioned binary: a header with the format version,
+    /// shard count, symbol size, and [shards in use](Self::with_shards),
+    /// followed by the length-prefixed strings of every shard in order. The
+    /// table's transform and limits aren't saved.
+    ///
+    /// This does many small writes, so `writer` should be buffered.
+    ///
+    /// ```
+    /// use symbol_table::SymbolTable;
+    ///
+    /// let table = SymbolTable::new();
+    /// let foo = table.intern("foo");
+    /// let mut bytes = Vec::new();
+    /// table.save_to(&mut bytes).unwrap();
+    ///
+    /// let loaded: SymbolTable = SymbolTable::load_from(&bytes[..]).unwrap();
+    /// assert_eq!(loaded.resolve(foo), "foo");
+    /// ```
+    pub fn save_to(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(N as u32).to_le_bytes())?;
+        writer.write_all(&K::BITS.to_le_bytes())?;
+        writer.write_all(&[self.inline as u8])?;
+        writer.write_all(&(self.n_shards as u32).to_le_bytes())?;
+        for shard_i in 0..N {
+            let len = self.shards[shard_i].lock().unwrap().strs.len() as u32;
+            writer.write_all(&len.to_le_bytes())?;
+            for idx in 0..len {
+                let bytes = self.resolve_bytes(Self::make_symbol(shard_i, idx));
+                writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+                writer.write_all(bytes)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Load a table saved by [`save_to`](Self::save_to).
+    ///
+    /// Every symbol of the saved table resolves to the same string in the
+    /// loaded one, and strings that were [interned into another
+    /// shard](Self::intern_in_shard) than they hash to stay there, as do all
+    /// the strings of a table with another hasher. This fails with
+    /// [`io::ErrorKind::InvalidData`] if the data wasn't saved by a table
+    /// with the same shard count and symbol type.
+    pub fn load_from(mut reader: impl Read) -> io::Result<Self>
+    where
+        S: Default,
+    {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a saved symbol table"));
+        }
+        let version = read_u32(&mut reader)?;
+        if version != VERSION {
+            return Err(invalid_data(format!("unknown format version {version}")));
+        }
+        let n_shards = read_u32(&mut reader)?;
+        if n_shards as usize != N {
+            return Err(invalid_data(format!(
+                "saved with {n_shards} shards, not {N}"
+            )));
+        }
+        let bits = read_u32(&mut reader)?;
+        if bits != K::BITS {
+            return Err(invalid_data(format!(
+                "saved with {bits}-bit symbols, not {}",
+                K::BITS
+            )));
+        }
+        let mut inline = [0];
+        reader.read_exact(&mut inline)?;
+        let in_use = read_u32(&mut reader)? as usize;
+        if !(1..=N).contains(&in_use) {
+            return Err(invalid_data(format!(
+                "saved with {in_use} of {N} shards in use"
+            )));
+        }
+
+        let mut table = Self::with_hasher(S::default()).with_shards(in_use);
+        table.inline = inline[0] != 0;
+        let mut buf = Vec::new();
+        for shard_i in 0..N {
+            for _ in 0..read_u32(&mut reader)? {
+                // don't trust the length enough to allocate it up front
+                let len = read_u32(&mut reader)? as u64;
+                buf.clear();
+                (&mut reader).take(len).read_to_end(&mut buf)?;
+                if buf.len() as u64 != len {
+                    return Err(io::ErrorKind::UnexpectedEof.into());
+                }
+                let string = std::str::from_utf8(&buf).map_err(invalid_data)?;
+                table.restore(shard_i, string).map_err(invalid_data)?;
+            }
+        }
+        Ok(table)
+    }
+
+    /// Take the stored strings out of the table, as the raw dictionary to
+    /// rebuild it with [`from_vec`](Self::from_vec).
+    ///
+    /// The strings are in the order of [`symbols`](Self::symbols): shard by
+    /// shard, in the order they were interned into each. That order only
+    /// depends on the strings that were interned, so the same table always
+    /// gives the same vector. [Inline](Self::with_inline_strings) strings
+    /// aren't stored, so they're left out.
+    ///
+    /// Only the strings are taken, not the settings of the table, so
+    /// [`from_vec`](Self::from_vec) only rebuilds the same symbols for a
+    /// table with all `N` [shards in use](Self::with_shards), no inline
+    /// strings, and no strings [interned into another
+    /// shard](Self::intern_in_shard). Use [`save_to`](Self::save_to) for
+    /// other tables.
+    ///
+    /// ```
+    /// use symbol_table::SymbolTable;
+    ///
+    /// let table = SymbolTable::new();
+    /// let syms = table.intern_many(["foo", "bar", "foo"]);
+    /// let strings = table.into_vec();
+    /// assert_eq!(strings.len(), 2);
+    ///
+    /// let (rebuilt, rebuilt_syms): (SymbolTable, _) = SymbolTable::from_vec(strings);
+    /// assert_eq!(rebuilt.resolve(syms[1]), "bar");
+    /// assert!(rebuilt_syms.iter().all(|sym| syms.contains(sym)));
+    /// ```
+    pub fn into_vec(self) -> Vec<Box<str>> {
+        self.stored_symbols()
+            .map(|sym| self.resolve(sym).into())
+            .collect()
+    }
+
+    /// Build a table from the strings in a vector, along with the symbol of
+    /// every string.
+    ///
+    /// The strings are interned in order into a table with all `N` shards
+    /// in use and no inline strings, so for a vector from
+    /// [`into_vec`](Self::into_vec) of such a table with the same shard
+    /// count, symbol type, and hasher, every symbol of the old table
+    /// resolves to the same string in the new one, and the returned symbols
+    /// are the ones the strings had. Duplicate strings get the same symbol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more strings than fit in the symbol type, like
+    /// [`intern`](Self::intern).
+    pub fn from_vec(strings: Vec<Box<str>>) -> (Self, Vec<K>)
+    where
+        S: Default,
+    {
+        let table = Self::with_hasher(S::default());
+        let syms = strings
+            .into_iter()
+            .map(|string| table.intern_owned(string.into_string()))
+            .collect();
+        (table, syms)
+    }
+
+    /// Add a string to the given shard of a table that's being restored, so
+    /// it gets the next index in that shard.
+    ///
+    /// Strings in other shards than they hash to were [interned
+    /// there](Self::intern_in_shard), and are pinned again. Fails if that
+    /// wouldn't give the string the same symbol as in the original, e.g.
+    /// because it's in a shard that isn't in use, or in the table twice.
+    pub(crate) fn restore(&mut self, shard_i: usize, string: &str) -> Result<(), String> {
+        let bytes = string.as_bytes();
+        if self.inline_symbol(bytes).is_some() {
+            return Err(format!("inline string {string:?} is stored in the table"));
+        }
+        if shard_i >= self.n_shards {
+            return Err(format!(
+                "string {string:?} is in shard {shard_i}, which isn't in use"
+            ));
+        }
+        let hash = hash_one(&self.build_hasher, bytes);
+        let home_i = shard_of_hash(hash, self.n_shards);
+        let home = self.shards[home_i].get_mut().unwrap();
+        if home.find(hash, bytes).is_some() || Self::aliased(home, hash, bytes).is_some() {
+            return Err(format!("string {string:?} is in the table twice"));
+        }
+
+        let shard = self.shards[shard_i].get_mut().unwrap();
+        if shard.strs.len() + 1 >= Self::MAX_IDX {
+            return Err(format!("shard {shard_i} has too many strings"));
+        }
+        let idx = shard.insert_new(hash, bytes);
+        if home_i != shard_i {
+            let home = self.shards[home_i].get_mut().unwrap();
+            Self::pin(home, hash, bytes, Self::make_symbol(shard_i, idx));
+        }
+        Ok(())
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn invalid_data(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
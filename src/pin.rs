@@ -0,0 +1,178 @@
+use crate::*;
+
+// numbers the threads in the order they first intern into a table with
+// thread affinity
+static NEXT_THREAD: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+thread_local! {
+    static THREAD_INDEX: usize = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Put new strings into a shard picked by the thread interning them,
+    /// instead of the one they hash to.
+    ///
+    /// Threads are numbered in the order they first intern into such a
+    /// table, and each thread interns into shard `thread_index % n` of the
+    /// `n` [shards in use](Self::with_shards), like with
+    /// [`intern_in_shard`](Self::intern_in_shard). So with as many shards as
+    /// threads, threads interning mostly their own strings rarely wait for
+    /// each other's shards while their strings grow, and their strings end
+    /// up in their own [per-shard side tables](Self::index_in_shard).
+    ///
+    /// Strings are still only interned once: a string already in the table
+    /// takes one lock to find, like without this, but a new string takes
+    /// the locks of both the shard it hashes to, where it's
+    /// [pinned](Self::intern_in_shard), and the thread's shard. This applies
+    /// to [`intern`](Self::intern) and [`intern_many`](Self::intern_many)
+    /// and their variants, which then intern one string at a time; other
+    /// ways of interning, like [`fresh`](Self::fresh), still use the shard
+    /// the string hashes to.
+    ///
+    /// ```
+    /// use symbol_table::SymbolTable;
+    ///
+    /// let table: SymbolTable<4> = SymbolTable::default().with_thread_affinity();
+    /// let foo = table.intern("foo");
+    /// let shard = table.shard_of(foo);
+    /// assert_eq!(table.shard_of(table.intern("bar")), shard);
+    /// std::thread::scope(|s| {
+    ///     s.spawn(|| assert_eq!(table.intern("foo"), foo));
+    /// });
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table has a [log](Self::with_log), which would replay
+    /// the strings into the shards they hash to.
+    pub fn with_thread_affinity(mut self) -> Self {
+        assert!(
+            self.log.is_none(),
+            "Can't intern into a given shard of a table with a log"
+        );
+        self.thread_affinity = true;
+        self
+    }
+
+    /// The shard the calling thread interns new strings into, with
+    /// [`with_thread_affinity`](Self::with_thread_affinity).
+    pub(crate) fn thread_shard(&self) -> usize {
+        THREAD_INDEX.with(|&i| i % self.n_shards)
+    }
+
+    /// Intern a string into the given shard, instead of the one it hashes
+    /// to.
+    ///
+    /// This is for code that already partitions its work, e.g. one shard per
+    /// compilation unit, so it controls which shard, and so which
+    /// [per-shard side tables](Self::index_in_shard), its strings end up in.
+    /// Strings are still only interned once: if the string is already in
+    /// the table, or an [alias](Self::alias), this gives its symbol,
+    /// wherever it is, and once it's pinned, interning or
+    /// [looking it up](Self::lookup) gives the pinned symbol. Tables with
+    /// pinned strings are [saved](Self::save_to) and serialized with them in
+    /// place.
+    ///
+    /// Strings are still [transformed](Self::with_transform), and
+    /// [inline](Self::with_inline_strings) strings have their own symbols,
+    /// in no shard.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let foo = table.intern_in_shard(3, "foo");
+    /// assert_eq!(table.shard_of(foo), 3);
+    /// assert_eq!(table.intern_in_shard(3, "foo"), foo);
+    /// assert_eq!(table.intern("foo"), foo);
+    /// assert_eq!(table.resolve(foo), "foo");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shard` isn't one of the [shards in use](Self::with_shards),
+    /// if the table has a [log](Self::with_log), which would replay the
+    /// string into the shard it hashes to, or if the string exceeds the
+    /// table's limits; use [`try_intern_in_shard`](Self::try_intern_in_shard)
+    /// to handle that instead.
+    pub fn intern_in_shard(&self, shard: usize, string: &str) -> K {
+        unwrap_interned(self.try_intern_in_shard(shard, string))
+    }
+
+    /// Intern a string into the given shard like
+    /// [`intern_in_shard`](Self::intern_in_shard), unless that would exceed
+    /// the table's limits, or the string is invalid.
+    ///
+    /// This fails like [`try_intern`](Self::try_intern).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shard` isn't one of the [shards in use](Self::with_shards),
+    /// or if the table has a [log](Self::with_log).
+    pub fn try_intern_in_shard(&self, shard: usize, string: &str) -> Result<K, InternError> {
+        assert!(
+            shard < self.n_shards,
+            "Can't intern into shard {shard} of {} in use",
+            self.n_shards
+        );
+        assert!(
+            self.log.is_none(),
+            "Can't intern into a given shard of a table with a log"
+        );
+        match self.transform.as_ref().map(|f| f(string)) {
+            None => self.try_intern_pinned(shard, string),
+            Some(Cow::Borrowed(s)) => self.try_intern_pinned(shard, s),
+            Some(Cow::Owned(s)) => self.try_intern_pinned(shard, s),
+        }
+    }
+
+    fn try_intern_pinned(&self, shard_i: usize, string: impl Internable) -> Result<K, InternError> {
+        self.check_str(string.as_ref())?;
+
+        if let Some(sym) = self.inline_symbol(string.as_ref()) {
+            return Ok(sym);
+        }
+
+        let hash = hash_one(&self.build_hasher, string.as_ref());
+        self.try_intern_pinned_hashed(shard_i, hash, string)
+    }
+
+    /// Intern a string with the given hash into the given shard, once it's
+    /// been checked and isn't inline.
+    pub(crate) fn try_intern_pinned_hashed(
+        &self,
+        shard_i: usize,
+        hash: u64,
+        string: impl Internable,
+    ) -> Result<K, InternError> {
+        let home_i = shard_of_hash(hash, self.n_shards);
+        if home_i == shard_i {
+            let mut locked = self.lock_shard(shard_i);
+            if let Some(sym) = Self::aliased(&locked, hash, string.as_ref()) {
+                return Ok(sym);
+            }
+            let idx = self.intern_locked(&mut locked, shard_i, hash, string)?;
+            drop(locked);
+            return Ok(Self::make_symbol(shard_i, idx));
+        }
+
+        // lock both shards in order, so this can't deadlock with another pin
+        let (mut home, mut locked) = if home_i < shard_i {
+            let home = self.lock_shard(home_i);
+            (home, self.lock_shard(shard_i))
+        } else {
+            let locked = self.lock_shard(shard_i);
+            (self.lock_shard(home_i), locked)
+        };
+        if let Some(sym) = Self::aliased(&home, hash, string.as_ref()) {
+            return Ok(sym);
+        }
+        if home.find(hash, string.as_ref()).is_some() {
+            drop(locked);
+            let idx = self.intern_locked(&mut home, home_i, hash, string)?;
+            return Ok(Self::make_symbol(home_i, idx));
+        }
+        let idx = self.intern_locked(&mut locked, shard_i, hash, string)?;
+        let sym = Self::make_symbol(shard_i, idx);
+        Self::pin(&mut home, hash, locked.strs.get(idx), sym);
+        Ok(sym)
+    }
+}
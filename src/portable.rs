@@ -0,0 +1,117 @@
+use crate::*;
+
+use std::hash::Hasher;
+
+/// A `BuildHasher` whose hashes are the same on every platform, and in
+/// every version of this crate.
+///
+/// A [`SymbolTable`] picks the shard of a string by its hash, so the hash
+/// decides the symbols. The default [`DeterministicHashBuilder`] only gives
+/// the same hashes for the same version of `foldhash` and on targets with
+/// the same pointer width and endianness. With this hasher instead, the same
+/// strings interned in the same order give the same symbols on every
+/// platform and with every future version of this crate, so symbols can be
+/// saved, and shared between e.g. x86_64 and aarch64 machines. That takes
+/// tables of the same shard count `N`, [symbol type](SymbolKey),
+/// [shards in use](SymbolTable::with_shards), and
+/// [inline strings](SymbolTable::with_inline_strings) setting, and any
+/// [transform](SymbolTable::with_transform) has to be deterministic too.
+/// Strings interned concurrently are only in the same order within each
+/// shard if their threads take turns the same way, so intern from one
+/// thread, or in a fixed order.
+///
+/// The hash function is fixed, and changing it would be a breaking change:
+/// starting from `0xcbf29ce484222325`, every [`write`](Hasher::write)
+/// mixes in its bytes as little-endian 64-bit words, the last one padded
+/// with zeros, and then its length, each with
+/// `h = (h.rotl(5) ^ word) * 0x517cc1b727220a95`, wrapping. Integers are
+/// written as their little-endian bytes, with `usize` as a `u64`, and
+/// [`finish`](Hasher::finish) is the `fmix64` finalizer of MurmurHash3. It's
+/// simple and fast, but not resistant to collisions crafted on purpose.
+///
+/// ```
+/// use symbol_table::PortableSymbolTable;
+///
+/// let table: PortableSymbolTable = PortableSymbolTable::default();
+/// let foo = table.intern("foo");
+/// assert_eq!(table.hash_of(foo), 0x5176_edfc_00a5_6e90);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PortableHashBuilder;
+
+/// The [`Hasher`] of a [`PortableHashBuilder`].
+#[derive(Debug, Clone, Copy)]
+pub struct PortableHasher(u64);
+
+/// A [`SymbolTable`] with the [`PortableHashBuilder`], for symbols that are
+/// the same on every platform.
+pub type PortableSymbolTable<const N: usize = DEFAULT_N_SHARDS, B = StringBackend, K = Symbol> =
+    SymbolTable<N, PortableHashBuilder, B, K>;
+
+impl BuildHasher for PortableHashBuilder {
+    type Hasher = PortableHasher;
+
+    fn build_hasher(&self) -> PortableHasher {
+        PortableHasher(PortableHasher::SEED)
+    }
+}
+
+impl PortableHasher {
+    // the offset basis of FNV-1a
+    const SEED: u64 = 0xcbf2_9ce4_8422_2325;
+    // the multiplier of the original FxHash
+    const MULTIPLIER: u64 = 0x517c_c1b7_2722_0a95;
+
+    fn add(&mut self, word: u64) {
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(Self::MULTIPLIER);
+    }
+}
+
+impl Hasher for PortableHasher {
+    fn finish(&self) -> u64 {
+        let mut h = self.0;
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        h ^ (h >> 33)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let mut words = bytes.chunks_exact(8);
+        for word in words.by_ref() {
+            self.add(u64::from_le_bytes(word.try_into().unwrap()));
+        }
+        let rest = words.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add(u64::from_le_bytes(word));
+        }
+        self.add(bytes.len() as u64);
+    }
+
+    fn write_u8(&mut self, n: u8) {
+        self.write(&[n]);
+    }
+
+    fn write_u16(&mut self, n: u16) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, n: u128) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.write_u64(n as u64);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::*;
+
+use hashbrown::HashTable;
+use std::{
+    fmt::{self, Debug, Display},
+    ops::Deref,
+    sync::Arc,
+};
+
+/// A symbol table whose strings can be reclaimed once they're unused.
+///
+/// A [`SymbolTable`] never frees a string, which is what lets it hand out
+/// `&str`s that live as long as the table. That's a leak for long-running
+/// processes that intern lots of short-lived strings, like request IDs.
+/// This table instead hands out reference-counted [`RcSymbol`]s, and
+/// [`collect`](Self::collect) frees every string that no [`RcSymbol`] refers
+/// to anymore.
+///
+/// The price is the stable `&str` guarantee: a string resolved from an
+/// [`RcSymbol`] only lives as long as that [`RcSymbol`], not as long as the
+/// table. [`RcSymbol`]s also aren't `Copy`, and cloning or dropping one
+/// touches an atomic reference count.
+///
+/// ```
+/// use symbol_table::RcSymbolTable;
+///
+/// let table = RcSymbolTable::new();
+/// let foo = table.intern("foo");
+/// assert_eq!(table.intern("foo"), foo);
+/// assert_eq!(foo.as_str(), "foo");
+///
+/// // `foo` is still alive, so nothing is reclaimed
+/// assert_eq!(table.collect(), 0);
+/// drop(foo);
+/// assert_eq!(table.collect(), 1);
+/// assert!(table.is_empty());
+/// ```
+pub struct RcSymbolTable<const N: usize = DEFAULT_N_SHARDS, S = DeterministicHashBuilder> {
+    build_hasher: S,
+    shards: [CachePadded<Mutex<RcShard>>; N],
+}
+
+struct RcShard {
+    // (hash, string) of every string that lives in this shard
+    map: HashTable<(u64, Arc<str>)>,
+}
+
+/// A reference-counted symbol from an [`RcSymbolTable`].
+///
+/// While an [`RcSymbol`] is alive, its string won't be reclaimed. Two
+/// [`RcSymbol`]s from the same table are equal exactly if their strings are,
+/// and comparing or hashing them doesn't look at the string.
+#[derive(Clone)]
+pub struct RcSymbol {
+    str: Arc<str>,
+}
+
+impl RcSymbolTable<DEFAULT_N_SHARDS, DeterministicHashBuilder> {
+    /// Creates a new [`RcSymbolTable`] with the default generic arguments.
+    pub const fn new() -> Self {
+        Self::with_hasher(DeterministicHashBuilder)
+    }
+}
+
+impl<const N: usize, S: BuildHasher> RcSymbolTable<N, S> {
+    /// Creates a new [`RcSymbolTable`] with a custom hasher.
+    #[allow(clippy::assertions_on_constants)]
+    pub const fn with_hasher(build_hasher: S) -> Self {
+        assert!(0 < N);
+        Self {
+            build_hasher,
+            shards: [const {
+                CachePadded::new(Mutex::new(RcShard {
+                    map: HashTable::new(),
+                }))
+            }; N],
+        }
+    }
+
+    /// Intern a string into the [`RcSymbolTable`].
+    ///
+    /// Interning the same string gives an equal symbol, as long as the string
+    /// hasn't been reclaimed in between.
+    pub fn intern(&self, string: &str) -> RcSymbol {
+        let hash = hash_one(&self.build_hasher, string.as_bytes());
+        let mut shard = self.shards[hash as usize % N].lock().unwrap();
+        let str = match shard.map.find(hash, |(_, s)| **s == *string) {
+            Some((_, str)) => str.clone(),
+            None => {
+                let str: Arc<str> = Arc::from(string);
+                shard
+                    .map
+                    .insert_unique(hash, (hash, str.clone()), |&(hash, _)| hash);
+                str
+            }
+        };
+        RcSymbol { str }
+    }
+
+    /// Free every string that isn't referred to by any [`RcSymbol`].
+    ///
+    /// Returns the number of strings that were freed. Interning one of them
+    /// again allocates it anew.
+    pub fn collect(&self) -> usize {
+        let mut freed = 0;
+        for shard in &self.shards {
+            let mut shard = shard.lock().unwrap();
+            // With the lock held, nobody can get a new reference to a string
+            // that only the table refers to.
+            shard.map.retain(|(_, str)| {
+                let unused = Arc::strong_count(str) == 1;
+                freed += unused as usize;
+                !unused
+            });
+        }
+        freed
+    }
+
+    /// The number of strings in this table, including unused ones that
+    /// haven't been [collected](Self::collect) yet.
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|s| s.lock().unwrap().map.len())
+            .sum()
+    }
+
+    /// Whether this table holds no strings.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<const N: usize, S: Default + BuildHasher> Default for RcSymbolTable<N, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl RcSymbol {
+    /// The interned string.
+    ///
+    /// It lives as long as this [`RcSymbol`], even if the table is dropped.
+    pub fn as_str(&self) -> &str {
+        &self.str
+    }
+}
+
+impl Deref for RcSymbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for RcSymbol {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq for RcSymbol {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.str, &other.str)
+    }
+}
+
+impl Eq for RcSymbol {}
+
+impl Hash for RcSymbol {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.str).cast::<u8>().hash(state)
+    }
+}
+
+impl Debug for RcSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Display for RcSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
@@ -0,0 +1,165 @@
+use crate::*;
+
+/// A mapping from old symbols to new ones.
+///
+/// This is returned by operations that rebuild or combine tables, like
+/// [`SymbolTable::compact`], [`SymbolTable::absorb`] and
+/// [`StagingTable::publish`], so that symbols
+/// stored elsewhere can be translated with [`get`](Self::get).
+#[derive(Debug, Clone)]
+pub struct SymbolRemap<K = Symbol> {
+    pub(crate) shard_shift: u32,
+    pub(crate) inline_tag: usize,
+    // the new symbol of every old one, by shard and index
+    pub(crate) shards: Vec<Vec<Option<K>>>,
+}
+
+impl<K: SymbolKey> SymbolRemap<K> {
+    /// The new symbol for an old one, or `None` if it's not in the new table.
+    ///
+    /// Inline symbols (see [`SymbolTable::with_inline_strings`]) always map to
+    /// themselves.
+    pub fn get(&self, old: K) -> Option<K> {
+        let raw = old.into_usize();
+        if raw & self.inline_tag != 0 {
+            return Some(old);
+        }
+        let shard_i = raw >> self.shard_shift;
+        let i = raw & ((1 << self.shard_shift) - 1);
+        *self.shards.get(shard_i)?.get(i.checked_sub(1)?)?
+    }
+
+    /// Iterate over all pairs of old and new symbols, skipping inline ones and
+    /// the ones that aren't in the new table.
+    pub fn iter(&self) -> impl Iterator<Item = (K, K)> + '_ {
+        self.shards
+            .iter()
+            .enumerate()
+            .flat_map(move |(shard_i, news)| {
+                news.iter().enumerate().filter_map(move |(idx, &new)| {
+                    let raw = shard_i << self.shard_shift | (idx + 1);
+                    Some((K::try_from_usize(raw).unwrap(), new?))
+                })
+            })
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Drop the strings that aren't needed anymore, and renumber the rest.
+    ///
+    /// `keep` is called on every string stored in the table; the ones it
+    /// returns `false` for are dropped, so their memory is freed. The
+    /// remaining strings get new symbols, packed together again, so all
+    /// symbols minted before compacting must be translated with the returned
+    /// [`SymbolRemap`].
+    ///
+    /// ```
+    /// let mut table = symbol_table::SymbolTable::new();
+    /// let syms: Vec<_> = (0..100).map(|i| table.intern(&format!("tmp{i}"))).collect();
+    /// let keep = table.intern("keep");
+    ///
+    /// let remap = table.compact(|_, s| !s.starts_with("tmp"));
+    /// assert!(syms.iter().all(|&sym| remap.get(sym).is_none()));
+    /// let keep = remap.get(keep).unwrap();
+    /// assert_eq!(table.resolve(keep), "keep");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// A [log](Self::with_log) can't be compacted, and replaying it would
+    /// then give different symbols, so this panics if the table has one.
+    pub fn compact(&mut self, mut keep: impl FnMut(K, &str) -> bool) -> SymbolRemap<K> {
+        assert!(self.log.is_none(), "Can't compact a table with a log");
+        let mut shards = Vec::with_capacity(N);
+        let mut kept = 0;
+        for (shard_i, shard) in self.shards.iter_mut().enumerate() {
+            let shard = shard.get_mut().unwrap();
+            let mut old = std::mem::replace(shard, Shard::new());
+            // remapped once all the shards are rebuilt
+            shard.aliases = std::mem::take(&mut old.aliases);
+            #[cfg(feature = "stats")]
+            {
+                shard.counters = old.counters;
+            }
+            let news = (0..old.strs.len() as u32)
+                .map(|idx| {
+                    let bytes = old.strs.get(idx);
+                    // SAFETY: only `str`s are interned through the public API
+                    // of a `SymbolTable`, see `resolve`
+                    let string = unsafe { std::str::from_utf8_unchecked(bytes) };
+                    keep(Self::make_symbol(shard_i, idx), string).then(|| {
+                        kept += 1;
+                        let new_idx = shard.insert_new(old.hashes[idx as usize], bytes);
+                        #[cfg(feature = "counts")]
+                        {
+                            shard.counts[new_idx as usize] = old.counts[idx as usize];
+                        }
+                        Self::make_symbol(shard_i, new_idx)
+                    })
+                })
+                .collect();
+            shards.push(news);
+        }
+
+        if self.max_entries != usize::MAX {
+            self.entries.store(kept, Ordering::Relaxed);
+        }
+        let remap = SymbolRemap {
+            shard_shift: Self::SHARD_SHIFT,
+            inline_tag: Self::INLINE_TAG,
+            shards,
+        };
+        self.remap_aliases(|sym| remap.get(sym));
+        remap
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Intern all the strings of `other` into this table.
+    ///
+    /// Returns the [`SymbolRemap`] that translates symbols from `other` into
+    /// symbols of this table.
+    ///
+    /// ```
+    /// use symbol_table::SymbolTable;
+    ///
+    /// let table = SymbolTable::new();
+    /// let other = SymbolTable::new();
+    /// let foo = other.intern("foo");
+    ///
+    /// let remap = table.absorb(&other);
+    /// assert_eq!(remap.get(foo), Some(table.intern("foo")));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Inline symbols map to themselves, so this panics if `other` uses
+    /// [inline strings](Self::with_inline_strings) but this table doesn't.
+    pub fn absorb<const M: usize, S2: BuildHasher, B2: Backend>(
+        &self,
+        other: &SymbolTable<M, S2, B2, K>,
+    ) -> SymbolRemap<K> {
+        assert!(
+            self.inline || !other.inline,
+            "Can't absorb a table with inline strings into one without"
+        );
+        let shards = (0..M)
+            .map(|shard_i| {
+                // don't hold the lock while interning, `other` may be `self`
+                let len = other.shards[shard_i].lock().unwrap().strs.len() as u32;
+                (0..len)
+                    .map(|idx| {
+                        let sym = SymbolTable::<M, S2, B2, K>::make_symbol(shard_i, idx);
+                        Some(self.intern(other.resolve(sym)))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        SymbolRemap {
+            shard_shift: SymbolTable::<M, S2, B2, K>::SHARD_SHIFT,
+            inline_tag: SymbolTable::<M, S2, B2, K>::INLINE_TAG,
+            shards,
+        }
+    }
+}
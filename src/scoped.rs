@@ -0,0 +1,124 @@
+use crate::*;
+
+use std::ops::Deref;
+
+/// A [`SymbolTable`] with nested scopes, whose strings are discarded when
+/// the scope is popped.
+///
+/// This mirrors lexical scoping in an interpreter: names interned while
+/// evaluating a block go away with the block, instead of all piling up in
+/// one flat table. Symbols interned in outer scopes stay valid, and since
+/// every scope shares the one table, interning a string that an outer scope
+/// already has gives the outer symbol.
+///
+/// [`push_scope`](Self::push_scope) and [`pop_scope`](Self::pop_scope) take
+/// `&mut self`, like [`SymbolTable::rollback_to`] which they're built on.
+/// Everything else is available through [`Deref`], with `&self`. Symbols
+/// interned in a popped scope must not be used anymore: resolving them
+/// panics, or gives a string interned after the pop.
+///
+/// ```
+/// let mut table = symbol_table::ScopedSymbolTable::new();
+/// let x = table.intern("x");
+///
+/// table.push_scope();
+/// let y = table.intern("y");
+/// assert_eq!(table.intern("x"), x);
+/// assert_eq!(table.scope_of(y), 1);
+///
+/// table.pop_scope();
+/// assert_eq!(table.resolve(x), "x");
+/// assert_eq!(table.lookup("y"), None);
+/// ```
+pub struct ScopedSymbolTable<
+    const N: usize = DEFAULT_N_SHARDS,
+    S = DeterministicHashBuilder,
+    B = StringBackend,
+    K = Symbol,
+> {
+    table: SymbolTable<N, S, B, K>,
+    // where every open scope starts
+    scopes: Vec<Checkpoint>,
+}
+
+impl ScopedSymbolTable<DEFAULT_N_SHARDS, DeterministicHashBuilder> {
+    /// Creates a new [`ScopedSymbolTable`] with the default generic
+    /// arguments, in its outermost scope.
+    pub const fn new() -> Self {
+        Self::from_table(SymbolTable::new())
+    }
+}
+
+impl<const N: usize, S, B, K> ScopedSymbolTable<N, S, B, K> {
+    /// Wrap a [`SymbolTable`], whose strings all go in the outermost scope.
+    pub const fn from_table(table: SymbolTable<N, S, B, K>) -> Self {
+        Self {
+            table,
+            scopes: Vec::new(),
+        }
+    }
+
+    /// The underlying table, with the strings of every scope.
+    pub fn into_inner(self) -> SymbolTable<N, S, B, K> {
+        self.table
+    }
+
+    /// How many scopes are pushed, so `0` in the outermost scope.
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> ScopedSymbolTable<N, S, B, K> {
+    /// Open a new scope, nested in the current one.
+    pub fn push_scope(&mut self) {
+        self.scopes.push(self.table.checkpoint());
+    }
+
+    /// Close the innermost scope, discarding the strings interned in it.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the outermost scope, or like
+    /// [`rollback_to`](SymbolTable::rollback_to) if the table has a
+    /// [log](SymbolTable::with_log).
+    pub fn pop_scope(&mut self) {
+        let scope = self.scopes.pop().expect("No scope to pop");
+        self.table.rollback_to(&scope);
+    }
+
+    /// The depth of the scope that `sym` was interned in.
+    ///
+    /// [Inline](SymbolTable::with_inline_strings) symbols aren't stored, so
+    /// they belong to the outermost scope, `0`.
+    pub fn scope_of(&self, sym: K) -> usize {
+        match SymbolTable::<N, S, B, K>::split_symbol(sym) {
+            Some((shard_i, idx)) => self
+                .scopes
+                .partition_point(|scope| scope.lens[shard_i] <= idx as usize),
+            None => 0,
+        }
+    }
+}
+
+impl<const N: usize, S, B, K> Deref for ScopedSymbolTable<N, S, B, K> {
+    type Target = SymbolTable<N, S, B, K>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.table
+    }
+}
+
+impl<const N: usize, S: Default + BuildHasher, B: Backend, K: SymbolKey> Default
+    for ScopedSymbolTable<N, S, B, K>
+{
+    fn default() -> Self {
+        Self::from_table(SymbolTable::default())
+    }
+}
+
+impl<const N: usize, S, B, K> From<SymbolTable<N, S, B, K>> for ScopedSymbolTable<N, S, B, K> {
+    fn from(table: SymbolTable<N, S, B, K>) -> Self {
+        Self::from_table(table)
+    }
+}
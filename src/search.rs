@@ -0,0 +1,77 @@
+use crate::*;
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Iterate over the strings in the table that start with `prefix`,
+    /// with their symbols.
+    ///
+    /// This looks at every string in the table, one shard at a time, so it
+    /// takes time linear in the size of the table, but it doesn't need any
+    /// memory besides the matches. Like [`symbols`](Self::symbols), it's a
+    /// snapshot, and doesn't include [inline](Self::with_inline_strings)
+    /// strings. The matches aren't in any particular order. For many
+    /// queries, keep a [`SortedIndex`] instead, which finds the matches in
+    /// order without looking at the other strings.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let foo = table.intern("foo");
+    /// let food = table.intern("food");
+    /// table.intern("bar");
+    ///
+    /// let mut matches: Vec<_> = table.symbols_with_prefix("foo").collect();
+    /// matches.sort_by_key(|&(_, s)| s);
+    /// assert_eq!(matches, [(foo, "foo"), (food, "food")]);
+    /// ```
+    pub fn symbols_with_prefix<'a>(&'a self, prefix: &str) -> impl Iterator<Item = (K, &'a str)> {
+        let prefix = prefix.as_bytes();
+        self.matching_bytes(|bytes| bytes.starts_with(prefix))
+            .into_iter()
+            // SAFETY: only `str`s can be interned through the public API of
+            // a `SymbolTable`, and the `Backend` gives back the same bytes.
+            .map(|(sym, bytes)| (sym, unsafe { std::str::from_utf8_unchecked(bytes) }))
+    }
+
+    /// Iterate over the strings in the table that `pred` accepts, with their
+    /// symbols.
+    ///
+    /// `pred` is called on every string in the table, one shard at a time,
+    /// while that shard is locked, so it must not use the table. Like
+    /// [`symbols`](Self::symbols), this is a snapshot, and doesn't include
+    /// [inline](Self::with_inline_strings) strings.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let tmp = table.intern("x__tmp1");
+    /// table.intern("x");
+    ///
+    /// let found: Vec<_> = table.find(|s| s.contains("__tmp")).collect();
+    /// assert_eq!(found, [(tmp, "x__tmp1")]);
+    /// ```
+    pub fn find(&self, pred: impl Fn(&str) -> bool) -> impl Iterator<Item = (K, &str)> {
+        self.matching_bytes(|bytes| {
+            // SAFETY: see `symbols_with_prefix`
+            pred(unsafe { std::str::from_utf8_unchecked(bytes) })
+        })
+        .into_iter()
+        // SAFETY: see `symbols_with_prefix`
+        .map(|(sym, bytes)| (sym, unsafe { std::str::from_utf8_unchecked(bytes) }))
+    }
+
+    // The symbols and strings that `pred` accepts, shard by shard in index
+    // order.
+    pub(crate) fn matching_bytes(&self, mut pred: impl FnMut(&[u8]) -> bool) -> Vec<(K, &[u8])> {
+        let mut matches = Vec::new();
+        for (shard_i, shard) in self.shards.iter().enumerate() {
+            let shard = shard.lock().unwrap();
+            for idx in 0..shard.strs.len() as u32 {
+                let bytes = shard.strs.get(idx);
+                if pred(bytes) {
+                    // SAFETY: the bytes never move, as in `resolve_bytes`
+                    let bytes = unsafe { backend::detach(bytes) };
+                    matches.push((Self::make_symbol(shard_i, idx), bytes));
+                }
+            }
+        }
+        matches
+    }
+}
@@ -0,0 +1,232 @@
+/*!
+[Serde](https://serde.rs) support for symbol tables.
+
+This requires the `serde` feature on the crate.
+
+A [`Symbol`] only means something together with the table it came from, so
+it serializes as its raw integer. To make those integers meaningful again
+after deserializing, serialize the [`SymbolTable`] along with them: a
+deserialized table resolves every symbol of the original to the same string.
+
+To serialize symbols as their strings instead, e.g. for a human-readable
+format, wrap them in a [`SymbolSerializer`], and deserialize them with a
+[`SymbolSeed`] that interns the strings into a table again.
+
+With the `global` feature, the [`dict`] module serializes collections of
+[`GlobalSymbol`]s compactly, and the [`registry`] module serializes them as
+numbers in binary formats.
+
+```
+use symbol_table::{Symbol, SymbolTable};
+
+let table = SymbolTable::new();
+let syms = vec![table.intern("foo"), table.intern("bar")];
+let json = serde_json::to_string(&(&table, &syms)).unwrap();
+
+let (table, syms): (SymbolTable, Vec<Symbol>) = serde_json::from_str(&json).unwrap();
+assert_eq!(table.resolve(syms[0]), "foo");
+assert_eq!(table.resolve(syms[1]), "bar");
+```
+*/
+
+use crate::*;
+
+#[cfg(feature = "global")]
+pub mod dict;
+#[cfg(feature = "global")]
+pub mod registry;
+
+use ::serde::{
+    de::{self, Deserialize, DeserializeSeed, Deserializer, Visitor},
+    ser::{Serialize, SerializeStruct, Serializer},
+};
+
+/// Serializes the strings of every shard in order, and how many shards are
+/// [in use](SymbolTable::with_shards), so the symbols can be restored
+/// exactly.
+///
+/// The table's transform and limits aren't serialized.
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> Serialize
+    for SymbolTable<N, S, B, K>
+{
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let mut table = serializer.serialize_struct("SymbolTable", 3)?;
+        table.serialize_field("inline", &self.inline)?;
+        table.serialize_field("n_shards", &self.n_shards)?;
+        table.serialize_field("shards", &SerializeShards(self))?;
+        table.end()
+    }
+}
+
+struct SerializeShards<'a, const N: usize, S, B, K>(&'a SymbolTable<N, S, B, K>);
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> Serialize
+    for SerializeShards<'_, N, S, B, K>
+{
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_seq((0..N).map(|shard_i| SerializeShard(self.0, shard_i)))
+    }
+}
+
+struct SerializeShard<'a, const N: usize, S, B, K>(&'a SymbolTable<N, S, B, K>, usize);
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> Serialize
+    for SerializeShard<'_, N, S, B, K>
+{
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let Self(table, shard_i) = *self;
+        let len = table.shards[shard_i].lock().unwrap().strs.len() as u32;
+        let syms = (0..len).map(|idx| SymbolTable::<N, S, B, K>::make_symbol(shard_i, idx));
+        serializer.collect_seq(syms.map(|sym| table.resolve(sym)))
+    }
+}
+
+#[derive(::serde::Deserialize)]
+#[serde(rename = "SymbolTable")]
+struct TableRepr<'a> {
+    inline: bool,
+    n_shards: usize,
+    #[serde(borrow)]
+    shards: Vec<Vec<Cow<'a, str>>>,
+}
+
+/// Restores a table serialized by the [`Serialize`] impl.
+///
+/// This fails if the table had a different number of shards. Strings that
+/// were [interned into another shard](SymbolTable::intern_in_shard) than
+/// they hash to stay there, as do all the strings of a table with a
+/// different hasher.
+impl<'de, const N: usize, S, B, K> Deserialize<'de> for SymbolTable<N, S, B, K>
+where
+    S: Default + BuildHasher,
+    B: Backend,
+    K: SymbolKey,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = TableRepr::deserialize(deserializer)?;
+        if repr.shards.len() != N {
+            let expected = format!("{N} shards");
+            return Err(de::Error::invalid_length(repr.shards.len(), &&*expected));
+        }
+        if repr.inline && K::BITS <= 16 {
+            return Err(de::Error::custom("inline symbols need more than 16 bits"));
+        }
+
+        let n_shards = repr.n_shards;
+        if !(1..=N).contains(&n_shards) {
+            let expected = format!("at most {N} shards in use");
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(n_shards as u64),
+                &&*expected,
+            ));
+        }
+
+        let mut table = Self::with_hasher(S::default()).with_shards(n_shards);
+        table.inline = repr.inline;
+        for (shard_i, strs) in repr.shards.into_iter().enumerate() {
+            for string in strs {
+                table.restore(shard_i, &string).map_err(de::Error::custom)?;
+            }
+        }
+        Ok(table)
+    }
+}
+
+/// Serializes a symbol as its string, resolved in a table.
+///
+/// ```
+/// use symbol_table::{serde::SymbolSerializer, SymbolTable};
+///
+/// let table = SymbolTable::new();
+/// let foo = table.intern("foo");
+/// let json = serde_json::to_string(&SymbolSerializer::new(&table, foo)).unwrap();
+/// assert_eq!(json, r#""foo""#);
+/// ```
+pub struct SymbolSerializer<
+    'a,
+    const N: usize = DEFAULT_N_SHARDS,
+    S = DeterministicHashBuilder,
+    B = StringBackend,
+    K = Symbol,
+> {
+    table: &'a SymbolTable<N, S, B, K>,
+    sym: K,
+}
+
+impl<'a, const N: usize, S, B, K> SymbolSerializer<'a, N, S, B, K> {
+    /// Serialize `sym` as its string in `table`.
+    pub fn new(table: &'a SymbolTable<N, S, B, K>, sym: K) -> Self {
+        Self { table, sym }
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> Serialize
+    for SymbolSerializer<'_, N, S, B, K>
+{
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.serialize_str(self.table.resolve(self.sym))
+    }
+}
+
+/// Deserializes a string and interns it into a table.
+///
+/// This is the [`DeserializeSeed`] counterpart to [`SymbolSerializer`].
+///
+/// ```
+/// use serde::de::DeserializeSeed;
+/// use symbol_table::{serde::SymbolSeed, SymbolTable};
+///
+/// let table = SymbolTable::new();
+/// let mut de = serde_json::Deserializer::from_str(r#""foo""#);
+/// let foo = SymbolSeed::new(&table).deserialize(&mut de).unwrap();
+/// assert_eq!(table.resolve(foo), "foo");
+/// ```
+pub struct SymbolSeed<
+    'a,
+    const N: usize = DEFAULT_N_SHARDS,
+    S = DeterministicHashBuilder,
+    B = StringBackend,
+    K = Symbol,
+> {
+    table: &'a SymbolTable<N, S, B, K>,
+}
+
+impl<'a, const N: usize, S, B, K> SymbolSeed<'a, N, S, B, K> {
+    /// Intern the deserialized strings into `table`.
+    pub fn new(table: &'a SymbolTable<N, S, B, K>) -> Self {
+        Self { table }
+    }
+}
+
+// Copy, so a seed can be reused for every symbol in a structure
+impl<const N: usize, S, B, K> Clone for SymbolSeed<'_, N, S, B, K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<const N: usize, S, B, K> Copy for SymbolSeed<'_, N, S, B, K> {}
+
+impl<'de, const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> DeserializeSeed<'de>
+    for SymbolSeed<'_, N, S, B, K>
+{
+    type Value = K;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<K, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> Visitor<'_>
+    for SymbolSeed<'_, N, S, B, K>
+{
+    type Value = K;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<K, E> {
+        self.table.try_intern(v).map_err(E::custom)
+    }
+}
@@ -0,0 +1,86 @@
+/*!
+Serialize collections of [`GlobalSymbol`]s as a dictionary.
+
+A [`GlobalSymbol`] serializes as its string, so a collection of them repeats
+every string as often as it occurs. This module serializes each distinct
+string once instead, followed by an index into those strings for every
+symbol. Use it with `#[serde(with = "symbol_table::serde::dict")]` on any
+collection of [`GlobalSymbol`]s that can be iterated by reference and
+collected from an iterator, like a `Vec` or a `HashSet`.
+
+This requires the `global` and `serde` features on the crate.
+
+```
+use serde::{Deserialize, Serialize};
+use symbol_table::GlobalSymbol;
+
+#[derive(Serialize, Deserialize)]
+struct Program {
+    #[serde(with = "symbol_table::serde::dict")]
+    tokens: Vec<GlobalSymbol>,
+}
+
+let tokens = "a b a a b".split(' ').map(GlobalSymbol::from).collect();
+let json = serde_json::to_string(&Program { tokens }).unwrap();
+assert_eq!(json, r#"{"tokens":{"strings":["a","b"],"symbols":[0,1,0,0,1]}}"#);
+
+let program: Program = serde_json::from_str(&json).unwrap();
+assert_eq!(program.tokens[2], "a");
+```
+*/
+
+use crate::*;
+
+use ::serde::{de, ser::SerializeStruct, Deserialize, Deserializer, Serializer};
+use std::collections::HashMap;
+
+/// Serialize the symbols as a dictionary of strings and indices into it.
+pub fn serialize<'a, T, S>(symbols: &'a T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    &'a T: IntoIterator<Item = &'a GlobalSymbol>,
+    S: Serializer,
+{
+    let mut indices = HashMap::new();
+    let mut strings = Vec::new();
+    let symbols: Vec<u32> = symbols
+        .into_iter()
+        .map(|&sym| {
+            *indices.entry(sym).or_insert_with(|| {
+                strings.push(sym.as_str());
+                strings.len() as u32 - 1
+            })
+        })
+        .collect();
+
+    let mut dict = serializer.serialize_struct("Dict", 2)?;
+    dict.serialize_field("strings", &strings)?;
+    dict.serialize_field("symbols", &symbols)?;
+    dict.end()
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Dict")]
+struct Dict<'a> {
+    #[serde(borrow)]
+    strings: Vec<Cow<'a, str>>,
+    symbols: Vec<u32>,
+}
+
+/// Deserialize symbols that were serialized with [`serialize`].
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromIterator<GlobalSymbol>,
+    D: Deserializer<'de>,
+{
+    let dict = Dict::deserialize(deserializer)?;
+    let strings: Vec<GlobalSymbol> = dict.strings.iter().map(GlobalSymbol::new).collect();
+    dict.symbols
+        .iter()
+        .map(|&i| {
+            strings.get(i as usize).copied().ok_or_else(|| {
+                let expected = format!("an index below {}", strings.len());
+                de::Error::invalid_value(de::Unexpected::Unsigned(i.into()), &&*expected)
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,179 @@
+/*!
+Serialize [`GlobalSymbol`]s as numbers in binary formats.
+
+A [`GlobalSymbol`] serializes as its string, which keeps human-readable
+formats like JSON readable, but makes binary formats like bincode repeat
+every string as often as it occurs. While a [`SymbolRegistry`] is in
+[scope](SymbolRegistry::scope), symbols instead serialize as their index in
+the registry, which collects every distinct string once. Serialize the
+registry separately, and deserialize the symbols in the scope of the
+deserialized registry again.
+
+Formats that are [human-readable](::serde::Serializer::is_human_readable)
+keep using strings even in a scope, so the same types work with both kinds.
+Unlike the [`dict`](super::dict) module, this works for symbols anywhere in
+a structure, without any attributes.
+
+This requires the `global` and `serde` features on the crate.
+
+```
+use symbol_table::{serde::registry::SymbolRegistry, GlobalSymbol};
+
+let tokens: Vec<GlobalSymbol> = "a b a a b".split(' ').map(GlobalSymbol::from).collect();
+let mut registry = SymbolRegistry::new();
+let bytes = registry.scope(|| bincode::serialize(&tokens)).unwrap();
+assert_eq!(registry.len(), 2);
+let registry_bytes = bincode::serialize(&registry).unwrap();
+
+let mut registry: SymbolRegistry = bincode::deserialize(&registry_bytes).unwrap();
+let decoded: Vec<GlobalSymbol> = registry.scope(|| bincode::deserialize(&bytes)).unwrap();
+assert_eq!(decoded, tokens);
+```
+*/
+
+use crate::*;
+
+use ::serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{any::Any, cell::RefCell, collections::HashMap};
+
+thread_local! {
+    // the registry in scope on this thread, if any
+    static ACTIVE: RefCell<Option<Box<dyn Any>>> = const { RefCell::new(None) };
+}
+
+/// The distinct symbols serialized or deserialized in its
+/// [scope](Self::scope), by index.
+///
+/// A registry only applies to symbols of its own [`Namespace`]; symbols of
+/// other namespaces still serialize as strings in its scope. It serializes
+/// as the sequence of its strings, and deserializing it interns them into
+/// the table of the namespace.
+pub struct SymbolRegistry<Ns = DefaultNamespace> {
+    symbols: Vec<NsSymbol<Ns>>,
+    indices: HashMap<NsSymbol<Ns>, u32>,
+}
+
+impl SymbolRegistry<DefaultNamespace> {
+    /// Creates an empty registry for plain [`GlobalSymbol`]s.
+    ///
+    /// Use [`default`](Default::default) for other namespaces.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<Ns: Namespace> SymbolRegistry<Ns> {
+    /// Run `f` with this registry in scope on this thread.
+    ///
+    /// While `f` runs, symbols of this namespace serialize to binary formats
+    /// as their index in this registry, adding them to it if they're new,
+    /// and deserialize from binary formats as the symbol at their index.
+    /// Scopes can be nested, and only the innermost one applies.
+    pub fn scope<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        // put the registry back even if `f` panics
+        struct Restore<'a, Ns: Namespace> {
+            registry: &'a mut SymbolRegistry<Ns>,
+            outer: Option<Box<dyn Any>>,
+        }
+
+        impl<Ns: Namespace> Drop for Restore<'_, Ns> {
+            fn drop(&mut self) {
+                let active =
+                    ACTIVE.with_borrow_mut(|active| std::mem::replace(active, self.outer.take()));
+                *self.registry = *active.unwrap().downcast().unwrap();
+            }
+        }
+
+        let active: Box<dyn Any> = Box::new(std::mem::take(self));
+        let outer = ACTIVE.with_borrow_mut(|slot| slot.replace(active));
+        let _restore = Restore {
+            registry: self,
+            outer,
+        };
+        f()
+    }
+
+    /// The number of distinct symbols in this registry.
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Whether this registry has no symbols.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// The symbols in this registry, by index.
+    pub fn symbols(&self) -> &[NsSymbol<Ns>] {
+        &self.symbols
+    }
+
+    fn insert(&mut self, sym: NsSymbol<Ns>) -> u32 {
+        *self.indices.entry(sym).or_insert_with(|| {
+            self.symbols.push(sym);
+            self.symbols.len() as u32 - 1
+        })
+    }
+}
+
+impl<Ns: Namespace> Default for SymbolRegistry<Ns> {
+    fn default() -> Self {
+        Self {
+            symbols: Vec::new(),
+            indices: HashMap::new(),
+        }
+    }
+}
+
+impl<Ns: Namespace> Serialize for SymbolRegistry<Ns> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.symbols.iter().map(|sym| sym.as_str()))
+    }
+}
+
+impl<'de, Ns: Namespace> Deserialize<'de> for SymbolRegistry<Ns> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let strings = Vec::<Cow<'de, str>>::deserialize(deserializer)?;
+        let mut registry = Self::default();
+        for (i, string) in strings.into_iter().enumerate() {
+            let sym = NsSymbol::intern(string);
+            // keep the indices of duplicates, even though `insert` never
+            // makes any
+            registry.symbols.push(sym);
+            registry.indices.entry(sym).or_insert(i as u32);
+        }
+        Ok(registry)
+    }
+}
+
+/// The index of a symbol in the registry in scope for its namespace,
+/// adding it if it's new, or `None` if there is no such registry.
+pub(crate) fn index_of<Ns: Namespace>(sym: NsSymbol<Ns>) -> Option<u32> {
+    ACTIVE.with_borrow_mut(|active| {
+        let registry = active.as_mut()?.downcast_mut::<SymbolRegistry<Ns>>()?;
+        Some(registry.insert(sym))
+    })
+}
+
+/// Whether a registry for the namespace is in scope.
+pub(crate) fn in_scope<Ns: Namespace>() -> bool {
+    ACTIVE.with_borrow(|active| {
+        active
+            .as_ref()
+            .is_some_and(|a| a.is::<SymbolRegistry<Ns>>())
+    })
+}
+
+/// The symbol at `index` in the registry in scope for the namespace.
+pub(crate) fn get<Ns: Namespace, E: de::Error>(index: u32) -> Result<NsSymbol<Ns>, E> {
+    let sym = ACTIVE.with_borrow(|active| {
+        let registry = active.as_ref()?.downcast_ref::<SymbolRegistry<Ns>>()?;
+        registry.symbols.get(index as usize).copied()
+    });
+    sym.ok_or_else(|| {
+        E::invalid_value(
+            de::Unexpected::Unsigned(index.into()),
+            &"an index into the symbol registry",
+        )
+    })
+}
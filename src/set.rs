@@ -0,0 +1,177 @@
+use crate::{map::Table, *};
+
+/// A set of the symbols of a [`SymbolTable`], as a bitset.
+///
+/// Like [`SymbolMap`], this uses the index of a symbol in its shard: every
+/// shard has a bitvector with a bit per index, so membership takes no
+/// hashing, and [`union_with`](Self::union_with) and
+/// [`intersect_with`](Self::intersect_with) work a word of 64 symbols at a
+/// time.
+///
+/// The symbols must come from a table with the same number of shards `N`
+/// and the same [`SymbolKey`] `K`. [Inline](SymbolTable::with_inline_strings)
+/// symbols don't have an index, so they can't be in the set.
+///
+/// ```
+/// use symbol_table::{SymbolSet, SymbolTable};
+///
+/// let table = SymbolTable::new();
+/// let (x, y, z) = (table.intern("x"), table.intern("y"), table.intern("z"));
+///
+/// let mut live: SymbolSet = [x, y].into_iter().collect();
+/// let used: SymbolSet = [y, z].into_iter().collect();
+/// live.intersect_with(&used);
+/// assert!(live.contains(y));
+/// assert!(!live.contains(x) && !live.contains(z));
+/// ```
+pub struct SymbolSet<const N: usize = DEFAULT_N_SHARDS, K = Symbol> {
+    shards: [Vec<u64>; N],
+    _key: PhantomData<K>,
+}
+
+impl<const N: usize, K: SymbolKey> SymbolSet<N, K> {
+    /// Creates an empty [`SymbolSet`].
+    pub const fn new() -> Self {
+        Self {
+            shards: [const { Vec::new() }; N],
+            _key: PhantomData,
+        }
+    }
+
+    /// The number of symbols in the set.
+    ///
+    /// This counts the bits, so it takes time linear in the size of the set.
+    pub fn len(&self) -> usize {
+        let words = self.shards.iter().flatten();
+        words.map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Whether there are no symbols in the set.
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().flatten().all(|&word| word == 0)
+    }
+
+    /// Whether `sym` is in the set.
+    pub fn contains(&self, sym: K) -> bool {
+        let Some((shard_i, idx)) = Table::<N, K>::split_symbol(sym) else {
+            return false;
+        };
+        let word = self
+            .shards
+            .get(shard_i)
+            .and_then(|shard| shard.get(idx as usize / 64));
+        word.is_some_and(|word| word & (1 << (idx % 64)) != 0)
+    }
+
+    /// Add `sym` to the set, returning whether it was new.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sym` is an inline symbol.
+    pub fn insert(&mut self, sym: K) -> bool {
+        let (shard_i, idx) =
+            Table::<N, K>::split_symbol(sym).expect("Inline symbols can't be in a SymbolSet");
+        let shard = &mut self.shards[shard_i];
+        let word_i = idx as usize / 64;
+        if shard.len() <= word_i {
+            shard.resize(word_i + 1, 0);
+        }
+        let bit = 1 << (idx % 64);
+        let new = shard[word_i] & bit == 0;
+        shard[word_i] |= bit;
+        new
+    }
+
+    /// Remove `sym` from the set, returning whether it was in it.
+    pub fn remove(&mut self, sym: K) -> bool {
+        let Some((shard_i, idx)) = Table::<N, K>::split_symbol(sym) else {
+            return false;
+        };
+        let shard = self.shards.get_mut(shard_i);
+        let Some(word) = shard.and_then(|shard| shard.get_mut(idx as usize / 64)) else {
+            return false;
+        };
+        let bit = 1 << (idx % 64);
+        let was = *word & bit != 0;
+        *word &= !bit;
+        was
+    }
+
+    /// Remove all the symbols, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        for shard in &mut self.shards {
+            shard.clear();
+        }
+    }
+
+    /// Add all the symbols of `other` to this set.
+    pub fn union_with(&mut self, other: &Self) {
+        for (shard, other) in self.shards.iter_mut().zip(&other.shards) {
+            if shard.len() < other.len() {
+                shard.resize(other.len(), 0);
+            }
+            for (word, other) in shard.iter_mut().zip(other) {
+                *word |= other;
+            }
+        }
+    }
+
+    /// Remove all the symbols that aren't in `other` from this set.
+    pub fn intersect_with(&mut self, other: &Self) {
+        for (shard, other) in self.shards.iter_mut().zip(&other.shards) {
+            shard.truncate(other.len());
+            for (word, other) in shard.iter_mut().zip(other) {
+                *word &= other;
+            }
+        }
+    }
+
+    /// Iterate over the symbols in the set, shard by shard in index order.
+    pub fn iter(&self) -> impl Iterator<Item = K> + '_ {
+        self.shards.iter().enumerate().flat_map(|(shard_i, shard)| {
+            shard.iter().enumerate().flat_map(move |(word_i, &word)| {
+                (0..64)
+                    .filter(move |bit| word & (1 << bit) != 0)
+                    .map(move |bit| (word_i * 64 + bit) as u32)
+                    .map(move |idx| Table::<N, K>::make_symbol(shard_i, idx))
+            })
+        })
+    }
+}
+
+impl<const N: usize, K: SymbolKey> Default for SymbolSet<N, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, K: SymbolKey> Clone for SymbolSet<N, K> {
+    fn clone(&self) -> Self {
+        Self {
+            shards: self.shards.clone(),
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<const N: usize, K: SymbolKey> fmt::Debug for SymbolSet<N, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const N: usize, K: SymbolKey> Extend<K> for SymbolSet<N, K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, syms: I) {
+        for sym in syms {
+            self.insert(sym);
+        }
+    }
+}
+
+impl<const N: usize, K: SymbolKey> FromIterator<K> for SymbolSet<N, K> {
+    fn from_iter<I: IntoIterator<Item = K>>(syms: I) -> Self {
+        let mut set = Self::new();
+        set.extend(syms);
+        set
+    }
+}
@@ -0,0 +1,314 @@
+use crate::*;
+
+use std::{
+    io,
+    ptr::NonNull,
+    sync::atomic::{AtomicU32, AtomicU64},
+};
+
+/// Marks memory that holds a [`SharedSymbolTable`], followed by the version
+/// of its layout.
+const MAGIC: u64 = u64::from_le_bytes(*b"symshm\0\x02");
+
+// The layout of the header, in bytes from the start of the memory.
+const MAGIC_AT: usize = 0;
+const N_SLOTS_AT: usize = 8;
+const MAX_ENTRIES_AT: usize = 12;
+const COUNT_AT: usize = 16;
+const DATA_LEN_AT: usize = 24;
+const SLOTS_AT: usize = 32;
+
+/// The most hash slots a table can have, so that the symbols of the slots
+/// never have the top bit set.
+const MAX_SLOTS: usize = 1 << 30;
+
+/// A lock-free symbol table that lives in memory shared between processes.
+///
+/// A [`SymbolTable`] keeps its strings on the heap of one process. This
+/// table instead lays everything out in one fixed-size block of memory, only
+/// ever touched through atomics, so processes that map the same memory (e.g.
+/// a shared file with `mmap`) can all intern into it and exchange the
+/// [`Symbol`]s directly.
+///
+/// The symbol of a string is the hash slot it was put in, so a failed or
+/// lost insert never uses up a symbol. The table can't grow: it holds at
+/// most the `max_entries` given to [`init`](Self::init), and as many bytes
+/// of strings as fit in the rest of the memory. All processes have to run
+/// the same build of this crate, so they hash the strings the same way.
+/// The empty string is [`Symbol::EMPTY`], like in every table, so it isn't
+/// stored.
+///
+/// ```
+/// use symbol_table::SharedSymbolTable;
+///
+/// // this would usually be shared memory, mapped by every process
+/// let mut memory = vec![0u64; 1 << 12];
+/// let (ptr, len) = (memory.as_mut_ptr().cast(), memory.len() * 8);
+/// let table = unsafe { SharedSymbolTable::init(ptr, len, 100) }.unwrap();
+/// let foo = table.intern("foo");
+///
+/// let other = unsafe { SharedSymbolTable::attach(ptr, len) }.unwrap();
+/// assert_eq!(other.intern("foo"), foo);
+/// assert_eq!(other.resolve(foo), "foo");
+/// ```
+pub struct SharedSymbolTable<'a> {
+    memory: NonNull<u8>,
+    n_slots: usize,
+    max_entries: usize,
+    // where the string data starts, and how many bytes of it there are
+    data_at: usize,
+    data_cap: usize,
+    _memory: PhantomData<&'a [AtomicU64]>,
+}
+
+// SAFETY: the memory is only accessed through atomics, or read after a
+// string was published by one.
+unsafe impl Send for SharedSymbolTable<'_> {}
+unsafe impl Sync for SharedSymbolTable<'_> {}
+
+impl<'a> SharedSymbolTable<'a> {
+    /// Set up a new, empty table in `len` bytes of memory at `memory`.
+    ///
+    /// The table has room for `max_entries` strings, and has at least twice
+    /// as many hash slots. Whatever memory is left after those is used for
+    /// the string data. Fails with [`io::ErrorKind::InvalidInput`] if that doesn't fit.
+    ///
+    /// # Safety
+    ///
+    /// `memory` must be aligned to 8 bytes, and valid for reads and writes
+    /// of `len` bytes for `'a`. While this runs, nothing else may access the
+    /// memory; after that, it may only be accessed through
+    /// [`SharedSymbolTable`]s.
+    pub unsafe fn init(memory: *mut u8, len: usize, max_entries: u32) -> io::Result<Self> {
+        let n_slots = (max_entries as usize * 2).next_power_of_two();
+        let invalid = |msg| io::Error::new(io::ErrorKind::InvalidInput, msg);
+        // the top bit of a symbol tags inline strings, like `Symbol::EMPTY`
+        if n_slots > MAX_SLOTS {
+            return Err(invalid("too many entries"));
+        }
+        let table = Self::new(memory, len, n_slots, max_entries as usize)
+            .ok_or_else(|| invalid("not enough memory for the table"))?;
+
+        // SAFETY: nobody else uses the memory yet
+        unsafe {
+            let slots = table.data_at;
+            std::ptr::write_bytes(memory, 0, slots);
+            memory.add(N_SLOTS_AT).cast::<u32>().write(n_slots as u32);
+            memory.add(MAX_ENTRIES_AT).cast::<u32>().write(max_entries);
+        }
+        // publish the table to anyone attaching
+        table.atomic_u64(MAGIC_AT).store(MAGIC, Ordering::Release);
+        Ok(table)
+    }
+
+    /// Use a table that [`init`](Self::init) has set up in `len` bytes of
+    /// memory at `memory`, e.g. from another process.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if there's no table in the
+    /// memory.
+    ///
+    /// # Safety
+    ///
+    /// `memory` must be aligned to 8 bytes, valid for reads and writes of
+    /// `len` bytes for `'a`, and only be accessed through
+    /// [`SharedSymbolTable`]s.
+    pub unsafe fn attach(memory: *mut u8, len: usize) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "no shared symbol table");
+        if len < SLOTS_AT {
+            return Err(invalid());
+        }
+        // SAFETY: the header fits, and is only written before the magic
+        let (magic, n_slots, max_entries) = unsafe {
+            let magic = (*memory.add(MAGIC_AT).cast::<AtomicU64>()).load(Ordering::Acquire);
+            let n_slots = memory.add(N_SLOTS_AT).cast::<u32>().read();
+            let max_entries = memory.add(MAX_ENTRIES_AT).cast::<u32>().read();
+            (magic, n_slots as usize, max_entries as usize)
+        };
+        if magic != MAGIC
+            || !n_slots.is_power_of_two()
+            || n_slots > MAX_SLOTS
+            || n_slots < max_entries * 2
+        {
+            return Err(invalid());
+        }
+        Self::new(memory, len, n_slots, max_entries).ok_or_else(invalid)
+    }
+
+    fn new(memory: *mut u8, len: usize, n_slots: usize, max_entries: usize) -> Option<Self> {
+        let memory = NonNull::new(memory)?;
+        assert_eq!(memory.as_ptr() as usize % 8, 0, "memory must be 8-aligned");
+        let data_at = SLOTS_AT.checked_add(n_slots.checked_mul(8)?)?;
+        Some(Self {
+            memory,
+            n_slots,
+            max_entries,
+            data_at,
+            data_cap: len.checked_sub(data_at)?,
+            _memory: PhantomData,
+        })
+    }
+
+    fn atomic_u32(&self, at: usize) -> &AtomicU32 {
+        // SAFETY: only called with aligned offsets in the header
+        unsafe { &*self.memory.as_ptr().add(at).cast() }
+    }
+
+    fn atomic_u64(&self, at: usize) -> &AtomicU64 {
+        // SAFETY: only called with aligned offsets in the header or slots
+        unsafe { &*self.memory.as_ptr().add(at).cast() }
+    }
+
+    /// The `i`th hash slot: `0` while it's empty, and then one more than the
+    /// offset of its string's record in the data.
+    fn slot(&self, i: usize) -> &AtomicU64 {
+        assert!(i < self.n_slots, "symbol isn't in the shared table");
+        self.atomic_u64(SLOTS_AT + 8 * i)
+    }
+
+    /// Intern a string into the [`SharedSymbolTable`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table is full; use [`try_intern`](Self::try_intern) to
+    /// handle that instead.
+    pub fn intern(&self, string: &str) -> Symbol {
+        unwrap_interned(self.try_intern(string))
+    }
+
+    /// Intern a string into the [`SharedSymbolTable`], unless it's full.
+    ///
+    /// Fails with [`InternError::TooManyEntries`] if the table already holds
+    /// its `max_entries`, or [`InternError::OutOfSpace`] if the string
+    /// doesn't fit in the rest of the memory.
+    pub fn try_intern(&self, string: &str) -> Result<Symbol, InternError> {
+        if string.is_empty() {
+            return Ok(Symbol::EMPTY);
+        }
+        let bytes = string.as_bytes();
+        let hash = hash_one(&DeterministicHashBuilder, bytes);
+        let mut i = hash as usize & (self.n_slots - 1);
+        // the record of the string, once it's pushed
+        let mut pushed = None;
+        loop {
+            let mut record = self.slot(i).load(Ordering::Acquire);
+            if record == 0 {
+                // Slots never become empty again, so the string can't be
+                // further along: it's new.
+                let at = match pushed {
+                    Some(at) => at,
+                    None => *pushed.insert(self.push(bytes)?),
+                };
+                let exchanged =
+                    self.slot(i)
+                        .compare_exchange(0, at + 1, Ordering::AcqRel, Ordering::Acquire);
+                match exchanged {
+                    Ok(_) => return Ok(Self::symbol(i)),
+                    Err(other) => record = other,
+                }
+            }
+
+            if self.record_bytes(record - 1) == bytes {
+                // someone else interned the same string here meanwhile
+                if let Some(at) = pushed {
+                    self.unpush(at, bytes);
+                }
+                return Ok(Self::symbol(i));
+            }
+            i = (i + 1) & (self.n_slots - 1);
+        }
+    }
+
+    fn symbol(slot: usize) -> Symbol {
+        Symbol(NonZeroU32::new(slot as u32 + 1).unwrap())
+    }
+
+    /// Count a new string, and store its record in the data, returning the
+    /// record's offset.
+    ///
+    /// The string only gets a symbol once its record is put in a slot.
+    fn push(&self, bytes: &[u8]) -> Result<u64, InternError> {
+        let max = self.max_entries;
+        self.atomic_u32(COUNT_AT)
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                ((n as usize) < max).then_some(n + 1)
+            })
+            .map_err(|_| InternError::TooManyEntries { max })?;
+
+        let record_len = 4 + bytes.len() as u64;
+        let capacity = self.data_cap;
+        let reserved =
+            self.atomic_u64(DATA_LEN_AT)
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                    (n + record_len <= capacity as u64).then_some(n + record_len)
+                });
+        let Ok(at) = reserved else {
+            self.atomic_u32(COUNT_AT).fetch_sub(1, Ordering::Relaxed);
+            return Err(InternError::OutOfSpace { capacity });
+        };
+
+        // SAFETY: the `record_len` bytes at `at` were just reserved for us,
+        // and nobody reads them before the record is published in a slot
+        unsafe {
+            let record = self.memory.as_ptr().add(self.data_at + at as usize);
+            let len = (bytes.len() as u32).to_le_bytes();
+            std::ptr::copy_nonoverlapping(len.as_ptr(), record, 4);
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), record.add(4), bytes.len());
+        }
+        Ok(at)
+    }
+
+    /// Undo a [`push`](Self::push) whose record didn't make it into a slot.
+    fn unpush(&self, at: u64, bytes: &[u8]) {
+        self.atomic_u32(COUNT_AT).fetch_sub(1, Ordering::Relaxed);
+        // Give the data back if nothing was reserved after it. Otherwise,
+        // its bytes are lost, but no symbol is.
+        let end = at + 4 + bytes.len() as u64;
+        let data_len = self.atomic_u64(DATA_LEN_AT);
+        let _ = data_len.compare_exchange(end, at, Ordering::Relaxed, Ordering::Relaxed);
+    }
+
+    /// Resolve a symbol to the interned string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the symbol isn't from this table.
+    pub fn resolve(&self, sym: Symbol) -> &'a str {
+        std::str::from_utf8(self.resolve_bytes(sym)).expect("corrupted shared table")
+    }
+
+    fn resolve_bytes(&self, sym: Symbol) -> &'a [u8] {
+        if sym == Symbol::EMPTY {
+            return b"";
+        }
+        let record = self.slot(sym.0.get() as usize - 1).load(Ordering::Acquire);
+        assert!(record != 0, "symbol isn't in the shared table");
+        self.record_bytes(record - 1)
+    }
+
+    /// The string of the published record at `at`.
+    fn record_bytes(&self, at: u64) -> &'a [u8] {
+        let at = at as usize;
+        assert!(at + 4 <= self.data_cap, "corrupted shared table");
+        // SAFETY: the string was published before its symbol, and is never
+        // written again
+        unsafe {
+            let record = self.memory.as_ptr().add(self.data_at + at);
+            let len = u32::from_le_bytes(record.cast::<[u8; 4]>().read()) as usize;
+            assert!(at + 4 + len <= self.data_cap, "corrupted shared table");
+            std::slice::from_raw_parts(record.add(4), len)
+        }
+    }
+
+    /// The number of strings in this table.
+    ///
+    /// In the rare case that two processes intern the same new string at the
+    /// same time, both copies are counted until one of them gives up.
+    pub fn len(&self) -> usize {
+        self.atomic_u32(COUNT_AT).load(Ordering::Relaxed) as usize
+    }
+
+    /// Whether no strings have been interned in this table.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,102 @@
+use crate::*;
+
+/// How many strings a [`TableSnapshot`] resolves per lock of a shard.
+const BATCH: u32 = 64;
+
+/// A point-in-time view of the strings in a [`SymbolTable`].
+///
+/// Created by [`SymbolTable::snapshot`]. The snapshot only holds the number
+/// of strings in every shard, and the table keeps interning while it's
+/// iterated: the shards are only locked for a batch of strings at a time,
+/// and the strings interned after the snapshot was taken are left out.
+pub struct TableSnapshot<'a, const N: usize, S, B, K> {
+    table: &'a SymbolTable<N, S, B, K>,
+    // the number of strings in every shard
+    lens: Vec<u32>,
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Take a [`TableSnapshot`] of the strings in the table right now.
+    ///
+    /// Unlike [`checkpoint`](Self::checkpoint), this locks every shard at
+    /// once, so the snapshot is consistent: if a string interned after
+    /// another is in it, so is the other one. That only stalls interning
+    /// for as long as it takes to count the strings.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// let foo = table.intern("foo");
+    /// let snapshot = table.snapshot();
+    /// table.intern("bar");
+    ///
+    /// assert_eq!(snapshot.len(), 1);
+    /// assert_eq!(snapshot.iter().collect::<Vec<_>>(), [(foo, "foo")]);
+    /// ```
+    pub fn snapshot(&self) -> TableSnapshot<'_, N, S, B, K> {
+        let shards: Vec<_> = (0..N).map(|shard_i| self.lock_shard(shard_i)).collect();
+        let lens = shards.iter().map(|s| s.strs.len() as u32).collect();
+        TableSnapshot { table: self, lens }
+    }
+}
+
+impl<const N: usize, S, B, K> Clone for TableSnapshot<'_, N, S, B, K> {
+    fn clone(&self) -> Self {
+        Self {
+            table: self.table,
+            lens: self.lens.clone(),
+        }
+    }
+}
+
+impl<'a, const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> TableSnapshot<'a, N, S, B, K> {
+    /// The number of strings in the snapshot.
+    ///
+    /// [Inline](SymbolTable::with_inline_strings) strings aren't stored,
+    /// so they aren't included.
+    pub fn len(&self) -> usize {
+        self.lens.iter().map(|&len| len as usize).sum()
+    }
+
+    /// Whether the table had no strings.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the string of `sym` was in the table when the snapshot was
+    /// taken.
+    pub fn contains(&self, sym: K) -> bool {
+        match SymbolTable::<N, S, B, K>::split_symbol(sym) {
+            Some((shard_i, idx)) => self.lens.get(shard_i).is_some_and(|&len| idx < len),
+            None => false,
+        }
+    }
+
+    /// Iterate over the symbols and strings in the snapshot.
+    ///
+    /// They're in the order of [`symbols`](SymbolTable::symbols): shard by
+    /// shard, in the order they were interned into each.
+    pub fn iter(&self) -> impl Iterator<Item = (K, &'a str)> + '_ {
+        let table = self.table;
+        self.lens
+            .iter()
+            .enumerate()
+            .flat_map(move |(shard_i, &len)| {
+                (0..len).step_by(BATCH as usize).flat_map(move |start| {
+                    let shard = table.lock_shard(shard_i);
+                    let batch: Vec<_> = (start..len.min(start + BATCH))
+                        .map(|idx| {
+                            // SAFETY: the string outlives the lock like in
+                            // `resolve_bytes`, and it's a `str` like in `resolve`.
+                            // The table can't be rolled back or compacted while
+                            // it's borrowed, so the string is still there.
+                            let string = unsafe {
+                                std::str::from_utf8_unchecked(backend::detach(shard.strs.get(idx)))
+                            };
+                            (SymbolTable::<N, S, B, K>::make_symbol(shard_i, idx), string)
+                        })
+                        .collect();
+                    batch
+                })
+            })
+    }
+}
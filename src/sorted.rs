@@ -0,0 +1,114 @@
+use crate::*;
+
+use std::{collections::BTreeMap, ops::RangeBounds};
+
+/// The strings of a [`SymbolTable`] in sorted order, for range queries.
+///
+/// Created by [`SymbolTable::sorted_index`]. The index borrows the strings
+/// from the table, so it doesn't copy them, and it keeps up with the table
+/// on its own: every query first adds the strings that were interned since
+/// the last one, so keeping the index around is much cheaper than sorting
+/// all the strings for every listing. Like
+/// [`symbols`](SymbolTable::symbols), it doesn't include
+/// [inline](SymbolTable::with_inline_strings) strings.
+///
+/// The strings are ordered like `str`s, i.e. byte by byte.
+///
+/// ```
+/// let table = symbol_table::SymbolTable::new();
+/// let mut index = table.sorted_index();
+/// let b = table.intern("b");
+/// let a = table.intern("a");
+/// assert_eq!(index.iter().collect::<Vec<_>>(), [(a, "a"), (b, "b")]);
+///
+/// let c = table.intern("c");
+/// let ab = table.intern("ab");
+/// assert_eq!(index.range("ab"..="b").collect::<Vec<_>>(), [(ab, "ab"), (b, "b")]);
+/// assert_eq!(index.with_prefix("a").count(), 2);
+/// assert_eq!(index.len(), 4);
+/// # let _ = c;
+/// ```
+pub struct SortedIndex<'a, const N: usize, S, B, K> {
+    table: &'a SymbolTable<N, S, B, K>,
+    // the number of strings of every shard that are in `sorted`
+    indexed: [u32; N],
+    sorted: BTreeMap<&'a str, K>,
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Create a [`SortedIndex`] of the strings in the table.
+    ///
+    /// The index is empty until it's first queried.
+    pub fn sorted_index(&self) -> SortedIndex<'_, N, S, B, K> {
+        SortedIndex {
+            table: self,
+            indexed: [0; N],
+            sorted: BTreeMap::new(),
+        }
+    }
+}
+
+impl<'a, const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SortedIndex<'a, N, S, B, K> {
+    /// Add the strings that were interned into the table since the last
+    /// update.
+    ///
+    /// The queries do this themselves, so this is only useful to do the
+    /// work ahead of time.
+    pub fn update(&mut self) {
+        for (shard_i, indexed) in self.indexed.iter_mut().enumerate() {
+            let len = self.table.shards[shard_i].lock().unwrap().strs.len() as u32;
+            for idx in *indexed..len {
+                let sym = SymbolTable::<N, S, B, K>::make_symbol(shard_i, idx);
+                self.sorted.insert(self.table.resolve(sym), sym);
+            }
+            *indexed = len;
+        }
+    }
+
+    /// The number of strings in the index, after an [`update`](Self::update).
+    pub fn len(&mut self) -> usize {
+        self.update();
+        self.sorted.len()
+    }
+
+    /// Whether the table has no strings, after an [`update`](Self::update).
+    pub fn is_empty(&mut self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over all the strings in the table, in sorted order, with
+    /// their symbols.
+    pub fn iter(&mut self) -> impl Iterator<Item = (K, &'a str)> + '_ {
+        self.update();
+        self.sorted.iter().map(|(&s, &sym)| (sym, s))
+    }
+
+    /// Iterate over the strings in `range`, in sorted order, with their
+    /// symbols.
+    pub fn range<'r>(
+        &mut self,
+        range: impl RangeBounds<&'r str>,
+    ) -> impl Iterator<Item = (K, &'a str)> + '_ {
+        self.update();
+        let bounds = (
+            range.start_bound().map(|s| *s),
+            range.end_bound().map(|s| *s),
+        );
+        self.sorted
+            .range::<str, _>(bounds)
+            .map(|(&s, &sym)| (sym, s))
+    }
+
+    /// Iterate over the strings that start with `prefix`, in sorted order,
+    /// with their symbols.
+    ///
+    /// Unlike [`SymbolTable::symbols_with_prefix`], this only looks at the
+    /// matching strings.
+    pub fn with_prefix<'s>(
+        &'s mut self,
+        prefix: &'s str,
+    ) -> impl Iterator<Item = (K, &'a str)> + 's {
+        self.range(prefix..)
+            .take_while(move |(_, s)| s.starts_with(prefix))
+    }
+}
@@ -0,0 +1,153 @@
+use crate::*;
+
+use std::convert::Infallible;
+
+/// A private table for one thread, to be merged into a shared
+/// [`SymbolTable`] later.
+///
+/// Created by [`SymbolTable::staging`]. Interning into a staging table
+/// doesn't take any locks, and gives provisional symbols, which only
+/// resolve in the staging table. [`publish`](Self::publish) then interns all
+/// the strings into the shared table, locking each of its shards only once,
+/// and returns the [`SymbolRemap`] from the provisional symbols to the
+/// shared ones. This suits work that splits into independent parts, like
+/// tokenizing many files, where the threads only need to agree on symbols at
+/// the end.
+///
+/// The strings are hashed like in the shared table, and the
+/// [transform](SymbolTable::with_transform) and
+/// [inline strings](SymbolTable::with_inline_strings) of the shared table
+/// apply right away, so inline symbols are already final.
+///
+/// ```
+/// let table = symbol_table::SymbolTable::new();
+/// let foo = table.intern("foo");
+///
+/// let (syms, remap) = std::thread::scope(|scope| {
+///     scope
+///         .spawn(|| {
+///             let mut staging = table.staging();
+///             let syms: Vec<_> = "foo bar foo".split(' ').map(|s| staging.intern(s)).collect();
+///             assert_eq!(staging.resolve(syms[1]), "bar");
+///             (syms, staging.publish())
+///         })
+///         .join()
+///         .unwrap()
+/// });
+/// let syms: Vec<_> = syms.iter().map(|&sym| remap.get(sym).unwrap()).collect();
+/// assert_eq!(syms, [foo, table.intern("bar"), foo]);
+/// ```
+pub struct StagingTable<'a, const N: usize, S, B, K> {
+    table: &'a SymbolTable<N, S, B, K>,
+    shard: Shard<B>,
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Create an empty [`StagingTable`] to publish into this table.
+    pub fn staging(&self) -> StagingTable<'_, N, S, B, K> {
+        StagingTable {
+            table: self,
+            shard: Shard::new(),
+        }
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> StagingTable<'_, N, S, B, K> {
+    /// Intern a string, giving a provisional symbol.
+    ///
+    /// # Panics
+    ///
+    /// Like [`SymbolTable::intern`], this panics if the string is longer
+    /// than the shared table's [limit](SymbolTable::with_max_str_len), or
+    /// [invalid](SymbolTable::with_validator) for it. The
+    /// [entry limit](SymbolTable::with_max_entries) is only checked when
+    /// publishing.
+    pub fn intern(&mut self, string: &str) -> K {
+        match self.table.transform.as_ref().map(|f| f(string)) {
+            None => self.intern_transformed(string),
+            Some(s) => self.intern_transformed(&s),
+        }
+    }
+
+    fn intern_transformed(&mut self, string: &str) -> K {
+        unwrap_interned(self.table.check_str(string.as_bytes()));
+        if let Some(sym) = self.table.inline_symbol(string.as_bytes()) {
+            return sym;
+        }
+
+        let hash = hash_one(&self.table.build_hasher, string.as_bytes());
+        let idx = match self.shard.intern(hash, string, |_| Ok::<_, Infallible>(())) {
+            Ok(idx) => idx,
+            Err(never) => match never {},
+        };
+        SymbolTable::<1, S, B, K>::make_symbol(0, idx)
+    }
+
+    /// Resolve a provisional symbol.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the symbol isn't from this staging table.
+    pub fn resolve(&self, sym: K) -> &str {
+        let Some((shard_i, idx)) = SymbolTable::<1, S, B, K>::split_symbol(sym) else {
+            let payload = sym.into_usize() & !SymbolTable::<1, S, B, K>::INLINE_TAG;
+            return inline::decode(payload as u32);
+        };
+        assert!(
+            shard_i == 0 && idx < self.shard.strs.len() as u32,
+            "Symbol isn't from this staging table"
+        );
+        let bytes = self.shard.strs.get(idx);
+        // SAFETY: only `str`s are interned, see `intern`
+        unsafe { std::str::from_utf8_unchecked(bytes) }
+    }
+
+    /// The number of strings stored in the staging table.
+    pub fn len(&self) -> usize {
+        self.shard.strs.len()
+    }
+
+    /// Whether no strings are stored in the staging table.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Intern all the strings into the shared table.
+    ///
+    /// Returns the [`SymbolRemap`] from the provisional symbols to the
+    /// shared ones. Inline symbols map to themselves.
+    ///
+    /// # Panics
+    ///
+    /// Like [`SymbolTable::intern`], this panics if the shared table can't
+    /// take any more strings.
+    pub fn publish(self) -> SymbolRemap<K> {
+        let table = self.table;
+        let len = self.shard.strs.len();
+        let mut grouped: [Vec<u32>; N] = std::array::from_fn(|_| Vec::new());
+        for idx in 0..len as u32 {
+            grouped[shard_of_hash(self.shard.hashes[idx as usize], self.table.n_shards)].push(idx);
+        }
+
+        let mut news = vec![None; len];
+        for (shard_i, idxs) in grouped.iter().enumerate() {
+            let mut locked = table.lock_shard(shard_i);
+            let result = idxs.iter().try_for_each(|&idx| {
+                let hash = self.shard.hashes[idx as usize];
+                let bytes = self.shard.strs.get(idx);
+                let new = table.intern_locked(&mut locked, shard_i, hash, bytes)?;
+                news[idx as usize] = Some(SymbolTable::<N, S, B, K>::make_symbol(shard_i, new));
+                Ok(())
+            });
+            // don't poison the lock by panicking while holding it
+            drop(locked);
+            unwrap_interned(result);
+        }
+
+        SymbolRemap {
+            shard_shift: SymbolTable::<1, S, B, K>::SHARD_SHIFT,
+            inline_tag: SymbolTable::<1, S, B, K>::INLINE_TAG,
+            shards: vec![news],
+        }
+    }
+}
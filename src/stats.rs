@@ -0,0 +1,110 @@
+use crate::*;
+
+/// How much a [`SymbolTable`] holds, e.g. for monitoring.
+///
+/// Created by [`SymbolTable::stats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TableStats {
+    /// The number of strings stored in the table.
+    pub len: usize,
+    /// The total length of those strings, in bytes.
+    pub bytes: usize,
+    /// The number of strings stored in every shard.
+    pub shard_lens: Vec<usize>,
+    /// The number of times a string was interned that was already in the
+    /// table.
+    #[cfg(feature = "stats")]
+    pub hits: u64,
+    /// The number of times a string was interned that was new, i.e. stored.
+    #[cfg(feature = "stats")]
+    pub inserts: u64,
+    /// The number of times every shard was locked to intern, look up or
+    /// resolve a string.
+    #[cfg(feature = "stats")]
+    pub shard_locks: Vec<u64>,
+    /// How many of those locks had to wait for another thread.
+    #[cfg(feature = "stats")]
+    pub shard_contended: Vec<u64>,
+}
+
+// What every shard counts with the `stats` feature. They're only updated
+// while the shard is locked, so they don't need to be atomic.
+#[cfg(feature = "stats")]
+#[derive(Clone, Copy)]
+pub(crate) struct ShardCounters {
+    pub(crate) hits: u64,
+    pub(crate) inserts: u64,
+    pub(crate) locks: u64,
+    pub(crate) contended: u64,
+}
+
+#[cfg(feature = "stats")]
+impl ShardCounters {
+    pub(crate) const NEW: Self = Self {
+        hits: 0,
+        inserts: 0,
+        locks: 0,
+        contended: 0,
+    };
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> SymbolTable<N, S, B, K> {
+    /// Count the strings stored in the table, and their bytes.
+    ///
+    /// Like [`symbols`](Self::symbols), this doesn't count
+    /// [inline](Self::with_inline_strings) strings. Summing up the bytes
+    /// takes time linear in the number of strings, and the shards are
+    /// counted one after the other, so strings interned concurrently may or
+    /// may not be included.
+    ///
+    /// With the `stats` feature, the shards also count how often strings
+    /// are found or stored when interning, and how often they're locked,
+    /// which this reports too. That's cheap, since the counts are only
+    /// updated while the shard is locked anyway, but not free, so the
+    /// feature is off by default. Cloning a table clones the counts, and
+    /// [`compact`](Self::compact) keeps them.
+    ///
+    /// ```
+    /// let table = symbol_table::SymbolTable::new();
+    /// table.intern("foo");
+    /// table.intern("barbaz");
+    ///
+    /// let stats = table.stats();
+    /// assert_eq!(stats.len, 2);
+    /// assert_eq!(stats.bytes, 9);
+    /// assert_eq!(stats.shard_lens.iter().sum::<usize>(), 2);
+    /// ```
+    pub fn stats(&self) -> TableStats {
+        let mut bytes = 0;
+        #[cfg(feature = "stats")]
+        let mut counters = Vec::with_capacity(N);
+        let shard_lens: Vec<usize> = self
+            .shards
+            .iter()
+            .map(|shard| {
+                let shard = shard.lock().unwrap();
+                let len = shard.strs.len();
+                bytes += (0..len as u32)
+                    .map(|idx| shard.strs.get(idx).len())
+                    .sum::<usize>();
+                #[cfg(feature = "stats")]
+                counters.push(shard.counters);
+                len
+            })
+            .collect();
+        TableStats {
+            len: shard_lens.iter().sum(),
+            bytes,
+            shard_lens,
+            #[cfg(feature = "stats")]
+            hits: counters.iter().map(|c| c.hits).sum(),
+            #[cfg(feature = "stats")]
+            inserts: counters.iter().map(|c| c.inserts).sum(),
+            #[cfg(feature = "stats")]
+            shard_locks: counters.iter().map(|c| c.locks).collect(),
+            #[cfg(feature = "stats")]
+            shard_contended: counters.iter().map(|c| c.contended).collect(),
+        }
+    }
+}
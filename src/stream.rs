@@ -0,0 +1,275 @@
+use crate::*;
+
+use std::io::{self, Read, Write};
+
+/// Encodes symbols into a compact stream, for a [`SymbolDecoder`] in
+/// another process.
+///
+/// Symbols are only meaningful to the table that made them, so sending them
+/// to another process normally means sending their strings, every time.
+/// Instead, the encoder numbers the distinct symbols of a stream in the
+/// order they first occur, and sends the string only the first time; after
+/// that, a symbol is sent as the difference from the number of the symbol
+/// before it, as a varint. Repeated and recently introduced symbols then
+/// take a single byte.
+///
+/// An encoder and its decoder keep the numbers for the whole stream, so
+/// keep one of each per connection, and feed every encoded byte to the
+/// decoder in order. Both sides can use any table, with any shard count or
+/// hasher, but each side must always use the same one.
+///
+/// ```
+/// use symbol_table::{SymbolDecoder, SymbolEncoder, SymbolTable};
+///
+/// let sender = SymbolTable::new();
+/// let syms = sender.intern_words("a rose is a rose is a rose");
+/// let mut encoder = SymbolEncoder::new();
+/// let mut bytes = Vec::new();
+/// encoder.encode_all(&sender, syms.iter().copied(), &mut bytes).unwrap();
+/// // 13 bytes for the 3 new words, and 1 for each repeated one
+/// assert_eq!(bytes.len(), 18);
+///
+/// let receiver = SymbolTable::new();
+/// let mut decoder = SymbolDecoder::new();
+/// let decoded = decoder.decode_all(&receiver, &bytes[..]).unwrap();
+/// let words: Vec<_> = decoded.iter().map(|&sym| receiver.resolve(sym)).collect();
+/// assert_eq!(words.join(" "), "a rose is a rose is a rose");
+/// ```
+///
+/// # Format
+///
+/// Every symbol starts with a varint: LEB128, seven bits a byte, least
+/// significant first. `0` introduces a new string, followed by its length
+/// as a varint and its UTF-8 bytes, and numbers it one more than the last
+/// new one. Any other value `n` is the symbol numbered `prev + d`, where
+/// `prev` is the number of the previous symbol, and `d` is `n - 1`
+/// zigzag-decoded. Both numberings start at `0`.
+#[derive(Debug, Clone)]
+pub struct SymbolEncoder<K = Symbol> {
+    // the number of every symbol sent so far
+    ids: SymbolHashMap<K, u32>,
+    prev: u32,
+}
+
+/// Decodes the symbols encoded by a [`SymbolEncoder`], interning their
+/// strings into a table.
+///
+/// See [`SymbolEncoder`] for the format.
+#[derive(Debug, Clone)]
+pub struct SymbolDecoder<K = Symbol> {
+    // the symbol of every number received so far
+    syms: Vec<K>,
+    prev: u32,
+}
+
+impl<K: SymbolKey> SymbolEncoder<K> {
+    /// Creates an encoder for a new stream.
+    pub fn new() -> Self {
+        Self {
+            ids: SymbolHashMap::default(),
+            prev: 0,
+        }
+    }
+
+    /// The number of distinct symbols encoded so far, whose strings were
+    /// sent.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether no symbols were encoded yet.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Encode a symbol of `table` into the stream.
+    ///
+    /// This does a few small writes, so `writer` should be buffered.
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`resolve`](SymbolTable::resolve) if `sym` isn't from
+    /// `table`.
+    pub fn encode<const N: usize, S: BuildHasher, B: Backend>(
+        &mut self,
+        table: &SymbolTable<N, S, B, K>,
+        sym: K,
+        mut writer: impl Write,
+    ) -> io::Result<()> {
+        let next = self.ids.len() as u32;
+        let id = *self.ids.entry(sym).or_insert(next);
+        if id == next {
+            let bytes = table.resolve_bytes(sym);
+            write_varint(&mut writer, 0)?;
+            write_varint(&mut writer, bytes.len() as u64)?;
+            writer.write_all(bytes)?;
+        } else {
+            let delta = id as i64 - self.prev as i64;
+            write_varint(&mut writer, zigzag(delta) + 1)?;
+        }
+        self.prev = id;
+        Ok(())
+    }
+
+    /// Encode every symbol of `syms` into the stream, in order.
+    pub fn encode_all<const N: usize, S: BuildHasher, B: Backend>(
+        &mut self,
+        table: &SymbolTable<N, S, B, K>,
+        syms: impl IntoIterator<Item = K>,
+        mut writer: impl Write,
+    ) -> io::Result<()> {
+        syms.into_iter()
+            .try_for_each(|sym| self.encode(table, sym, &mut writer))
+    }
+}
+
+impl<K: SymbolKey> SymbolDecoder<K> {
+    /// Creates a decoder for a new stream.
+    pub fn new() -> Self {
+        Self {
+            syms: Vec::new(),
+            prev: 0,
+        }
+    }
+
+    /// The number of distinct symbols decoded so far.
+    pub fn len(&self) -> usize {
+        self.syms.len()
+    }
+
+    /// Whether no symbols were decoded yet.
+    pub fn is_empty(&self) -> bool {
+        self.syms.is_empty()
+    }
+
+    /// Decode the next symbol of the stream, interning its string into
+    /// `table` if it's new.
+    ///
+    /// The strings are interned as they were sent, without the table's
+    /// [transform](SymbolTable::with_transform). This fails with
+    /// [`io::ErrorKind::InvalidData`] if the stream wasn't encoded by a
+    /// [`SymbolEncoder`], or if the table can't intern a string, and with
+    /// [`io::ErrorKind::UnexpectedEof`] if the stream ends mid-symbol. After
+    /// an error, the rest of the stream can't be decoded.
+    pub fn decode<const N: usize, S: BuildHasher, B: Backend>(
+        &mut self,
+        table: &SymbolTable<N, S, B, K>,
+        mut reader: impl Read,
+    ) -> io::Result<K> {
+        self.decode_next(table, &mut reader)?
+            .ok_or_else(|| io::ErrorKind::UnexpectedEof.into())
+    }
+
+    /// Decode every symbol up to the end of `reader`.
+    ///
+    /// This fails like [`decode`](Self::decode), including when `reader`
+    /// ends mid-symbol.
+    pub fn decode_all<const N: usize, S: BuildHasher, B: Backend>(
+        &mut self,
+        table: &SymbolTable<N, S, B, K>,
+        mut reader: impl Read,
+    ) -> io::Result<Vec<K>> {
+        let mut syms = Vec::new();
+        while let Some(sym) = self.decode_next(table, &mut reader)? {
+            syms.push(sym);
+        }
+        Ok(syms)
+    }
+
+    /// Decode the next symbol, or `None` if the stream ends before it.
+    fn decode_next<const N: usize, S: BuildHasher, B: Backend>(
+        &mut self,
+        table: &SymbolTable<N, S, B, K>,
+        reader: &mut impl Read,
+    ) -> io::Result<Option<K>> {
+        let mut first = [0];
+        if reader.read(&mut first)? == 0 {
+            return Ok(None);
+        }
+        let tag = read_varint(first[0], reader)?;
+        let id = if tag == 0 {
+            let len = read_varint_start(reader)?;
+            // don't trust the length enough to allocate it up front
+            let mut buf = Vec::new();
+            reader.take(len).read_to_end(&mut buf)?;
+            if buf.len() as u64 != len {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let string = std::str::from_utf8(&buf).map_err(invalid_data)?;
+            let sym = table.try_intern_internable(string).map_err(invalid_data)?;
+            self.syms.push(sym);
+            self.syms.len() - 1
+        } else {
+            let delta = unzigzag(tag - 1);
+            match (self.prev as i64).checked_add(delta) {
+                Some(id) if (0..self.syms.len() as i64).contains(&id) => id as usize,
+                _ => return Err(invalid_data("symbol number out of range")),
+            }
+        };
+        self.prev = id as u32;
+        Ok(Some(self.syms[id]))
+    }
+}
+
+impl<K: SymbolKey> Default for SymbolEncoder<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: SymbolKey> Default for SymbolDecoder<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn zigzag(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+fn unzigzag(n: u64) -> i64 {
+    (n >> 1) as i64 ^ -((n & 1) as i64)
+}
+
+fn write_varint(writer: &mut impl Write, mut n: u64) -> io::Result<()> {
+    let mut buf = [0; 10];
+    let mut len = 0;
+    loop {
+        buf[len] = (n & 0x7f) as u8;
+        n >>= 7;
+        len += 1;
+        if n == 0 {
+            break;
+        }
+        buf[len - 1] |= 0x80;
+    }
+    writer.write_all(&buf[..len])
+}
+
+fn read_varint_start(reader: &mut impl Read) -> io::Result<u64> {
+    let mut first = [0];
+    reader.read_exact(&mut first)?;
+    read_varint(first[0], reader)
+}
+
+/// Read the rest of a varint that starts with the byte `first`.
+fn read_varint(first: u8, reader: &mut impl Read) -> io::Result<u64> {
+    let mut n = (first & 0x7f) as u64;
+    let mut byte = first;
+    let mut shift = 7;
+    while byte & 0x80 != 0 {
+        let mut next = [0];
+        reader.read_exact(&mut next)?;
+        byte = next[0];
+        if shift > 63 || (shift == 63 && byte & 0x7f > 1) {
+            return Err(invalid_data("varint overflows 64 bits"));
+        }
+        n |= ((byte & 0x7f) as u64) << shift;
+        shift += 7;
+    }
+    Ok(n)
+}
+
+fn invalid_data(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
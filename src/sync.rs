@@ -0,0 +1,151 @@
+//! The locks and atomics that the tables synchronize with.
+//!
+//! These are `std`'s, with every shard padded to its own cache line by
+//! [`crossbeam_utils::CachePadded`], except:
+//!
+//! - On `wasm32` without the `atomics` target feature, there are no threads,
+//!   so the locks are the lock-free stand-ins of the `lock` module.
+//! - With the `loom` feature, when built with `--cfg loom`, they're
+//!   [`loom`](https://docs.rs/loom)'s, so code using a table can be model
+//!   checked. `loom` only knows about locks and atomics created inside the
+//!   model, so each of these only creates its `loom` counterpart on first
+//!   use, which keeps the constructors of the tables `const`.
+//!
+//! The tables only use the parts of the `std` API that every flavor has.
+
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+pub(crate) use crate::lock::CachePadded;
+#[cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+pub(crate) use crossbeam_utils::CachePadded;
+
+#[cfg(all(loom, feature = "loom"))]
+pub(crate) use self::model::{AtomicUsize, Mutex, RwLock};
+#[cfg(all(
+    not(all(loom, feature = "loom")),
+    target_arch = "wasm32",
+    not(target_feature = "atomics")
+))]
+pub(crate) use crate::lock::{Mutex, RwLock};
+#[cfg(not(all(loom, feature = "loom")))]
+pub(crate) use std::sync::atomic::AtomicUsize;
+#[cfg(not(any(
+    all(loom, feature = "loom"),
+    all(target_arch = "wasm32", not(target_feature = "atomics"))
+)))]
+pub(crate) use std::sync::{Mutex, RwLock};
+
+#[cfg(all(loom, feature = "loom"))]
+mod model {
+    use std::sync::{atomic::Ordering, LockResult, OnceLock};
+
+    /// A [`loom::sync::Mutex`] that can be created in a `const`.
+    pub(crate) struct Mutex<T> {
+        init: std::sync::Mutex<Option<T>>,
+        mutex: OnceLock<loom::sync::Mutex<T>>,
+    }
+
+    impl<T> Mutex<T> {
+        pub(crate) const fn new(value: T) -> Self {
+            Self {
+                init: std::sync::Mutex::new(Some(value)),
+                mutex: OnceLock::new(),
+            }
+        }
+
+        fn get(&self) -> &loom::sync::Mutex<T> {
+            self.mutex.get_or_init(|| {
+                let value = self.init.lock().unwrap().take().unwrap();
+                loom::sync::Mutex::new(value)
+            })
+        }
+
+        pub(crate) fn lock(&self) -> LockResult<loom::sync::MutexGuard<'_, T>> {
+            self.get().lock()
+        }
+
+        pub(crate) fn try_lock(&self) -> std::sync::TryLockResult<loom::sync::MutexGuard<'_, T>> {
+            self.get().try_lock()
+        }
+
+        pub(crate) fn get_mut(&mut self) -> LockResult<&mut T> {
+            self.get();
+            self.mutex.get_mut().unwrap().get_mut()
+        }
+    }
+
+    /// A [`loom::sync::RwLock`] that can be created in a `const`.
+    pub(crate) struct RwLock<T> {
+        init: std::sync::Mutex<Option<T>>,
+        lock: OnceLock<loom::sync::RwLock<T>>,
+    }
+
+    impl<T> RwLock<T> {
+        pub(crate) const fn new(value: T) -> Self {
+            Self {
+                init: std::sync::Mutex::new(Some(value)),
+                lock: OnceLock::new(),
+            }
+        }
+
+        fn get(&self) -> &loom::sync::RwLock<T> {
+            self.lock.get_or_init(|| {
+                let value = self.init.lock().unwrap().take().unwrap();
+                loom::sync::RwLock::new(value)
+            })
+        }
+
+        pub(crate) fn read(&self) -> LockResult<loom::sync::RwLockReadGuard<'_, T>> {
+            self.get().read()
+        }
+
+        pub(crate) fn write(&self) -> LockResult<loom::sync::RwLockWriteGuard<'_, T>> {
+            self.get().write()
+        }
+    }
+
+    /// A [`loom::sync::atomic::AtomicUsize`] that can be created in a
+    /// `const`.
+    pub(crate) struct AtomicUsize {
+        init: usize,
+        atomic: OnceLock<loom::sync::atomic::AtomicUsize>,
+    }
+
+    impl AtomicUsize {
+        pub(crate) const fn new(value: usize) -> Self {
+            Self {
+                init: value,
+                atomic: OnceLock::new(),
+            }
+        }
+
+        fn get(&self) -> &loom::sync::atomic::AtomicUsize {
+            self.atomic
+                .get_or_init(|| loom::sync::atomic::AtomicUsize::new(self.init))
+        }
+
+        pub(crate) fn load(&self, order: Ordering) -> usize {
+            self.get().load(order)
+        }
+
+        pub(crate) fn store(&self, value: usize, order: Ordering) {
+            self.get().store(value, order)
+        }
+
+        pub(crate) fn fetch_add(&self, value: usize, order: Ordering) -> usize {
+            self.get().fetch_add(value, order)
+        }
+
+        pub(crate) fn fetch_sub(&self, value: usize, order: Ordering) -> usize {
+            self.get().fetch_sub(value, order)
+        }
+
+        pub(crate) fn fetch_update(
+            &self,
+            set_order: Ordering,
+            fetch_order: Ordering,
+            f: impl FnMut(usize) -> Option<usize>,
+        ) -> Result<usize, usize> {
+            self.get().fetch_update(set_order, fetch_order, f)
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::*;
+
+/// How much a shard can hold before it has to grow, to tell when interning
+/// or reserving made it reallocate.
+///
+/// Without the `tracing` feature, this keeps and traces nothing.
+pub(crate) struct Capacities {
+    #[cfg(feature = "tracing")]
+    map: usize,
+    #[cfg(feature = "tracing")]
+    hashes: usize,
+}
+
+impl<B: Backend> Shard<B> {
+    pub(crate) fn capacities(&self) -> Capacities {
+        Capacities {
+            #[cfg(feature = "tracing")]
+            map: self.map.capacity(),
+            #[cfg(feature = "tracing")]
+            hashes: self.hashes.capacity(),
+        }
+    }
+}
+
+impl Capacities {
+    /// Emit a `tracing` event for every part of `shard` that grew since
+    /// these capacities were taken.
+    ///
+    /// The vector of hashes gets an entry for every string, so it grows
+    /// with the strings of the shard.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn trace_growth<B: Backend>(self, shard_i: usize, shard: &Shard<B>) {
+        #[cfg(feature = "tracing")]
+        {
+            let after = shard.capacities();
+            if after.map != self.map {
+                tracing::debug!(
+                    shard = shard_i,
+                    capacity = after.map,
+                    len = shard.map.len(),
+                    "symbol table shard map grew"
+                );
+            }
+            if after.hashes != self.hashes {
+                tracing::debug!(
+                    shard = shard_i,
+                    capacity = after.hashes,
+                    len = shard.hashes.len(),
+                    "symbol table shard strings grew"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::*;
+
+/// Tables that resolve symbols to strings.
+///
+/// This is implemented by every table that resolves its symbols to `&str`s,
+/// including the read-only ones like [`FrozenSymbolTable`], so code that
+/// only reads strings can be generic over the table it's handed. The global
+/// table of a [`Namespace`] is a plain [`SymbolTable`], so it implements
+/// this too. Each table resolves like its own `resolve` method, except that
+/// [`FrozenSymbolTable`] panics where its own method returns `None`.
+///
+/// ```
+/// use symbol_table::{Resolve, Symbol, SymbolTable};
+///
+/// fn describe(table: &impl Resolve, syms: &[Symbol]) -> String {
+///     syms.iter().map(|&sym| table.resolve(sym)).collect::<Vec<_>>().join(".")
+/// }
+///
+/// let table = SymbolTable::new();
+/// let path = table.intern_many(["std", "fmt"]);
+/// assert_eq!(describe(&table, &path), "std.fmt");
+/// ```
+pub trait Resolve<K = Symbol> {
+    /// Resolve a symbol of this table to its string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the symbol isn't from this table.
+    fn resolve(&self, sym: K) -> &str;
+}
+
+/// Tables that intern strings, with a shared reference.
+///
+/// This is implemented by every table whose `intern` takes any `&str` and
+/// only needs `&self`, giving the same symbol as that method. Like the
+/// tables, it's meant to be shared, e.g. in an `Arc`.
+///
+/// ```
+/// use symbol_table::{Intern, LocalSymbolTable, SymbolTable};
+///
+/// fn tokenize<K>(table: &impl Intern<K>, text: &str) -> Vec<K> {
+///     text.split_whitespace().map(|word| table.intern(word)).collect()
+/// }
+///
+/// let table = SymbolTable::new();
+/// assert_eq!(tokenize(&table, "a b a").len(), 3);
+/// let local = LocalSymbolTable::new();
+/// let tokens = tokenize(&local, "a b a");
+/// assert_eq!(tokens[0], tokens[2]);
+/// ```
+pub trait Intern<K = Symbol>: Resolve<K> {
+    /// Intern a string into this table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table can't hold the string, like the table's own
+    /// `intern` method.
+    fn intern(&self, string: &str) -> K;
+}
+
+impl<K, T: Resolve<K> + ?Sized> Resolve<K> for &T {
+    fn resolve(&self, sym: K) -> &str {
+        T::resolve(self, sym)
+    }
+}
+
+impl<K, T: Intern<K> + ?Sized> Intern<K> for &T {
+    fn intern(&self, string: &str) -> K {
+        T::intern(self, string)
+    }
+}
+
+// The tables whose inherent `intern` and `resolve` fit the traits as is.
+macro_rules! impl_intern_resolve {
+    ($($table:ident),*) => {$(
+        impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> Resolve<K>
+            for $table<N, S, B, K>
+        {
+            fn resolve(&self, sym: K) -> &str {
+                $table::resolve(self, sym)
+            }
+        }
+
+        impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> Intern<K>
+            for $table<N, S, B, K>
+        {
+            fn intern(&self, string: &str) -> K {
+                $table::intern(self, string)
+            }
+        }
+    )*};
+}
+
+impl_intern_resolve!(SymbolTable, LocalSymbolTable, DenseSymbolTable);
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> Resolve<K>
+    for ScopedSymbolTable<N, S, B, K>
+{
+    fn resolve(&self, sym: K) -> &str {
+        SymbolTable::resolve(self, sym)
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> Intern<K>
+    for ScopedSymbolTable<N, S, B, K>
+{
+    fn intern(&self, string: &str) -> K {
+        SymbolTable::intern(self, string)
+    }
+}
+
+impl Resolve for SharedSymbolTable<'_> {
+    fn resolve(&self, sym: Symbol) -> &str {
+        SharedSymbolTable::resolve(self, sym)
+    }
+}
+
+impl Intern for SharedSymbolTable<'_> {
+    fn intern(&self, string: &str) -> Symbol {
+        SharedSymbolTable::intern(self, string)
+    }
+}
+
+impl<const N: usize, S: BuildHasher, K: SymbolKey> Resolve<K> for BorrowedSymbolTable<'_, N, S, K> {
+    fn resolve(&self, sym: K) -> &str {
+        BorrowedSymbolTable::resolve(self, sym)
+    }
+}
+
+impl<const N: usize, S: BuildHasher, B: Backend, K: SymbolKey> Resolve<K>
+    for StagingTable<'_, N, S, B, K>
+{
+    fn resolve(&self, sym: K) -> &str {
+        StagingTable::resolve(self, sym)
+    }
+}
+
+impl<K: SymbolKey> Resolve<K> for FrozenSymbolTable<'_, K> {
+    fn resolve(&self, sym: K) -> &str {
+        FrozenSymbolTable::resolve(self, sym).expect("Symbol isn't in the frozen table")
+    }
+}
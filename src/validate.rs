@@ -0,0 +1,57 @@
+/*!
+Validators for [`SymbolTable::with_validator`](crate::SymbolTable::with_validator).
+
+```
+use symbol_table::{validate, InternError, InvalidStr, SymbolTable};
+
+let table = SymbolTable::new().with_validator(validate::identifier);
+assert!(table.try_intern("snake_case").is_ok());
+assert_eq!(
+    table.try_intern("kebab-case"),
+    Err(InternError::Invalid { reason: InvalidStr::NotIdentifier { at: 5 } }),
+);
+```
+*/
+
+use crate::InvalidStr;
+
+/// Only accept ASCII strings.
+///
+/// ```
+/// use symbol_table::{validate, InvalidStr};
+///
+/// assert_eq!(validate::ascii("plain"), Ok(()));
+/// assert_eq!(validate::ascii("naïve"), Err(InvalidStr::NotAscii { at: 2 }));
+/// ```
+pub fn ascii(string: &str) -> Result<(), InvalidStr> {
+    match string.bytes().position(|b| !b.is_ascii()) {
+        Some(at) => Err(InvalidStr::NotAscii { at }),
+        None => Ok(()),
+    }
+}
+
+/// Only accept identifiers: a letter or `_`, followed by letters, digits
+/// and `_`s.
+///
+/// Letters and digits are Unicode ones, as in [`char::is_alphabetic`] and
+/// [`char::is_alphanumeric`], which is close to, but looser than, the
+/// identifiers of Rust. Combine this with [`ascii`] for ASCII-only ones.
+///
+/// ```
+/// use symbol_table::{validate, InvalidStr};
+///
+/// assert_eq!(validate::identifier("_tmp1"), Ok(()));
+/// assert_eq!(validate::identifier("1st"), Err(InvalidStr::NotIdentifier { at: 0 }));
+/// assert_eq!(validate::identifier(""), Err(InvalidStr::NotIdentifier { at: 0 }));
+/// ```
+pub fn identifier(string: &str) -> Result<(), InvalidStr> {
+    let mut chars = string.char_indices();
+    match chars.next() {
+        Some((_, c)) if c.is_alphabetic() || c == '_' => {}
+        _ => return Err(InvalidStr::NotIdentifier { at: 0 }),
+    }
+    match chars.find(|&(_, c)| !(c.is_alphanumeric() || c == '_')) {
+        Some((at, _)) => Err(InvalidStr::NotIdentifier { at }),
+        None => Ok(()),
+    }
+}
@@ -1,56 +1,2503 @@
-use std::collections::{hash_map::Entry, HashMap};
+use std::borrow::Cow;
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::sync::{Arc, Mutex};
 use symbol_table::*;
 
 static TEXT: &str = include_str!("../tests/gulliver.txt");
 
+fn check_resolve<const N: usize, S: std::hash::BuildHasher, B: backend::Backend, K: SymbolKey>(
+    interner: SymbolTable<N, S, B, K>,
+) {
+    let mut resolved = Vec::new();
+    let mut map = HashMap::new();
+
+    for word in TEXT.split_whitespace().chain(TEXT.split_whitespace().rev()) {
+        let sym = interner.intern(word);
+        match map.entry(word) {
+            Entry::Occupied(e) => assert_eq!(*e.get(), sym),
+            Entry::Vacant(e) => {
+                e.insert(sym);
+            }
+        }
+        resolved.push(interner.resolve(sym));
+        assert_eq!(interner.resolve(sym), word);
+        assert_eq!(&interner[sym], word);
+        assert_eq!(interner.resolve_owned(sym), word);
+        assert_eq!(interner.hash_of(sym), interner.hash_str(word));
+        assert!(interner.contains_symbol(sym));
+        let interned = interner.intern_get(word);
+        assert_eq!((interned.symbol(), interned.as_str()), (sym, word));
+        // SAFETY: the symbol was just interned
+        assert_eq!(unsafe { interner.resolve_unchecked(sym) }, word);
+    }
+}
+
+#[test]
+fn test_resolve() {
+    check_resolve(SymbolTable::new());
+}
+
+#[test]
+fn test_intern_get() {
+    let table = SymbolTable::new()
+        .with_inline_strings()
+        .with_transform(|s| s.to_lowercase().into());
+    let foo = table.intern_get("FOO");
+    assert_eq!(foo.symbol(), table.intern("foo"));
+    assert_eq!(&*foo, "foo");
+    assert_eq!(format!("{foo} {foo:?}"), "foo \"foo\"");
+    let x = table.intern_get("X");
+    assert_eq!((x.symbol(), x.as_str()), (table.intern("x"), "x"));
+    assert_ne!(foo, x);
+
+    let set: HashSet<_> = ["a", "b", "a"].map(|s| table.intern_get(s)).into();
+    assert_eq!(set.len(), 2);
+    let limited = SymbolTable::new().with_max_str_len(2);
+    assert!(limited.try_intern_get("foo").is_err());
+}
+
+#[test]
+fn test_snapshot() {
+    let table: SymbolTable<4> = TEXT.split_whitespace().take(1000).collect();
+    let snapshot = table.snapshot();
+    let before: Vec<Symbol> = table.symbols().collect();
+    assert_eq!(snapshot.len(), before.len());
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            for word in TEXT.split_whitespace() {
+                table.intern(word);
+            }
+        });
+        // the snapshot keeps its strings while the table grows
+        let entries: Vec<(Symbol, &str)> = snapshot.iter().collect();
+        assert_eq!(
+            entries.iter().map(|&(sym, _)| sym).collect::<Vec<_>>(),
+            before
+        );
+        for (sym, string) in entries {
+            assert!(snapshot.contains(sym));
+            assert_eq!(table.resolve(sym), string);
+        }
+    });
+
+    let new = table.intern("<new>");
+    assert!(!snapshot.contains(new));
+    assert_eq!(table.snapshot().len(), table.symbols().count());
+}
+
+#[test]
+fn test_alias() {
+    let mut table = SymbolTable::new().with_transform(|s| s.to_lowercase().into());
+    let color = table.intern("color");
+    table.alias("Colour", color);
+    assert_eq!(table.intern("colour"), color);
+    assert_eq!(table.intern_owned("COLOUR".to_string()), color);
+    assert_eq!(table.intern_many(["colour", "color"]), [color, color]);
+    assert_eq!(table.intern_get("colour").as_str(), "color");
+    assert_eq!(table.lookup("colour"), Some(color));
+    assert_eq!(table.symbols().count(), 1);
+
+    // aliases go with their canonical string
+    let cp = table.checkpoint();
+    let grey = table.intern("grey");
+    table.alias("gray", grey);
+    assert_eq!(table.clone().intern("gray"), grey);
+    table.rollback_to(&cp);
+    assert_eq!(table.lookup("gray"), None);
+    assert_eq!(table.intern("colour"), color);
+
+    table.intern("tmp");
+    let remap = table.compact(|_, s| s != "tmp");
+    assert_eq!(table.intern("colour"), remap.get(color).unwrap());
+}
+
+#[test]
+#[should_panic = "Can't alias \"bar\", which is already interned"]
+fn test_alias_interned() {
+    let table = SymbolTable::new();
+    let foo = table.intern("foo");
+    table.intern("bar");
+    table.alias("bar", foo);
+}
+
+#[test]
+fn test_intern_in_shard() {
+    let mut table = SymbolTable::new().with_shards(4);
+    let foo = table.intern("foo");
+    let other = (table.shard_of(foo) + 1) % 4;
+    // strings that are already interned keep their symbols
+    assert_eq!(table.intern_in_shard(other, "foo"), foo);
+    assert_eq!(table.intern_in_shard(other, ""), Symbol::EMPTY);
+
+    let home = table.hash_str("bar") as usize % 4;
+    let shard = (home + 1) % 4;
+    let bar = table.intern_in_shard(shard, "bar");
+    assert_eq!(table.shard_of(bar), shard);
+    assert_eq!(table.resolve(bar), "bar");
+    assert_eq!(table.intern_in_shard(shard, "bar"), bar);
+    assert_eq!(table.intern_in_shard(home, "bar"), bar);
+    assert_eq!(table.intern("bar"), bar);
+    assert_eq!(table.lookup("bar"), Some(bar));
+    assert!(table.eq_str(bar, table.intern("bar")));
+    assert_eq!(table.symbols().count(), 2);
+    #[cfg(feature = "check")]
+    assert_eq!(table.check_invariants(), Ok(()));
+
+    // strings stay in the shard they were pinned to
+    let remap = table.compact(|_, s| s != "foo");
+    let bar = remap.get(bar).unwrap();
+    assert_eq!(table.shard_of(bar), shard);
+    assert_eq!(table.intern("bar"), bar);
+
+    let mut bytes = Vec::new();
+    table.save_to(&mut bytes).unwrap();
+    let loaded = SymbolTable::<16>::load_from(&bytes[..]).unwrap();
+    assert!(table.same_symbols(&loaded));
+    assert_eq!(loaded.intern("bar"), bar);
+    #[cfg(feature = "check")]
+    assert_eq!(loaded.check_invariants(), Ok(()));
+}
+
+#[test]
+#[should_panic = "Can't alias \"bar\", which is already interned"]
+fn test_alias_pinned() {
+    let table = SymbolTable::new();
+    let foo = table.intern("foo");
+    let home = table.hash_str("bar") as usize % 16;
+    table.intern_in_shard((home + 1) % 16, "bar");
+    table.alias("bar", foo);
+}
+
+#[test]
+#[should_panic = "Can't intern into shard 2 of 2 in use"]
+fn test_intern_in_shard_unused() {
+    SymbolTable::new().with_shards(2).intern_in_shard(2, "foo");
+}
+
+#[test]
+fn test_thread_affinity() {
+    let table: SymbolTable<4> = SymbolTable::default().with_thread_affinity();
+    let words: Vec<_> = TEXT.split_whitespace().take(2000).collect();
+    let per_thread: Vec<Vec<Symbol>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..8)
+            .map(|t| {
+                let (table, words) = (&table, &words);
+                scope.spawn(move || {
+                    // every thread's own strings land in the same shard
+                    let own: Vec<_> = (0..500)
+                        .map(|i| table.intern(&format!("t{t}-{i}")))
+                        .collect();
+                    let shard = table.shard_of(own[0]);
+                    assert!(own.iter().all(|&sym| table.shard_of(sym) == shard));
+                    let more: Vec<_> = (0..500).map(|i| format!("u{t}-{i}")).collect();
+                    let own_many = table.intern_many(more.iter().map(String::as_str));
+                    assert!(own_many.iter().all(|&sym| table.shard_of(sym) == shard));
+                    table.intern_many(words.iter().copied())
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    // the shared words are only interned once
+    assert!(per_thread.iter().all(|syms| *syms == per_thread[0]));
+    for (&word, &sym) in words.iter().zip(&per_thread[0]) {
+        assert_eq!(table.resolve(sym), word);
+        assert_eq!(table.lookup(word), Some(sym));
+    }
+    let distinct: HashSet<_> = words.iter().collect();
+    assert_eq!(table.symbols().count(), distinct.len() + 8 * 1000);
+    #[cfg(feature = "check")]
+    assert_eq!(table.check_invariants(), Ok(()));
+
+    let mut bytes = Vec::new();
+    table.save_to(&mut bytes).unwrap();
+    let loaded = SymbolTable::<4>::load_from(&bytes[..]).unwrap();
+    assert!(table.same_symbols(&loaded));
+}
+
+#[test]
+#[should_panic = "Can't log a table with thread affinity"]
+fn test_thread_affinity_log() {
+    SymbolTable::new()
+        .with_thread_affinity()
+        .with_log(Vec::new());
+}
+
+#[cfg(feature = "check")]
+#[test]
+fn test_check_invariants() {
+    let mut table = SymbolTable::<16>::default()
+        .with_shards(3)
+        .with_inline_strings();
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| table.intern_words(TEXT));
+        }
+        scope.spawn(|| {
+            for _ in 0..10 {
+                assert_eq!(table.check_invariants(), Ok(()));
+            }
+        });
+    });
+    let the = table.intern("the");
+    table.alias("teh", the);
+    assert_eq!(table.check_invariants(), Ok(()));
+
+    let cp = table.checkpoint();
+    table.intern_many(["foo", "bar", "baz"]);
+    table.rollback_to(&cp);
+    assert_eq!(table.check_invariants(), Ok(()));
+    table.compact(|_, s| s.len() > 4);
+    assert_eq!(table.check_invariants(), Ok(()));
+    assert_eq!(table.clone().check_invariants(), Ok(()));
+    assert_eq!(SymbolTable64::<1>::default().check_invariants(), Ok(()));
+}
+
+#[test]
+fn test_contains_symbol() {
+    let table = SymbolTable::new();
+    let foo = table.intern("foo");
+    assert!(table.contains_symbol(foo));
+    assert!(table.contains_symbol(Symbol::EMPTY));
+    // an index of zero, a shard that's empty, an index past the end, and
+    // an inline payload that doesn't encode a string
+    for raw in [1 << 27, u32::MAX >> 1, foo.to_u32() + 1, 1 << 31 | 1] {
+        let sym = Symbol::try_from_u32(raw).unwrap();
+        assert!(!table.contains_symbol(sym), "{raw:#x}");
+    }
+    assert!(!SymbolTable::new().contains_symbol(foo));
+
+    let inline = SymbolTable::new().with_inline_strings();
+    let ab = inline.intern("ab");
+    assert!(inline.contains_symbol(ab));
+    assert!(!table.contains_symbol(ab));
+}
+
+#[cfg(feature = "fxhash")]
+#[test]
+fn test_resolve_fxhash() {
+    check_resolve(FxSymbolTable::<4>::default());
+}
+
+#[test]
+fn test_portable_symbols() {
+    // pinned, as these have to be the same on every platform and version
+    let strings = ["foo", "bar", "hello, world!", "symbol_table"];
+    let table: PortableSymbolTable = PortableSymbolTable::default();
+    let hashes = strings.map(|s| table.hash_of(table.intern(s)));
+    assert_eq!(
+        hashes,
+        [
+            0x5176_edfc_00a5_6e90,
+            0x1023_3758_64c9_52c9,
+            0x8449_c3c1_a4ee_8ed5,
+            0x1a36_ee86_8338_8da0
+        ]
+    );
+    let raw = strings.map(|s| std::num::NonZeroU32::from(table.intern(s)).get());
+    assert_eq!(raw, [0x1, 0x4800_0001, 0x2800_0001, 0x2]);
+
+    let table = PortableSymbolTable::<16>::default().with_shards(3);
+    let raw = strings.map(|s| std::num::NonZeroU32::from(table.intern(s)).get());
+    assert_eq!(raw, [0x1, 0x0800_0001, 0x2, 0x0800_0002]);
+    check_resolve(PortableSymbolTable::<4>::default());
+}
+
+#[test]
+fn test_resolve_64() {
+    check_resolve(SymbolTable64::<1>::default());
+    check_resolve(SymbolTable64::<1024>::default().with_inline_strings());
+}
+
+#[test]
+fn test_resolve_16() {
+    let interner =
+        SymbolTable::<2, DeterministicHashBuilder, backend::StringBackend, Symbol16>::default();
+    for word in ["foo", "bar", "baz", "🧵"] {
+        let sym = interner.intern(word);
+        assert_eq!(interner.resolve(sym), word);
+    }
+    assert_eq!(Symbol16::try_from_usize(0), None);
+    assert_eq!(Symbol16::try_from_usize(1 << 16), None);
+    assert_eq!(Symbol16::try_from_usize(7).unwrap().into_usize(), 7);
+}
+
+#[test]
+fn test_resolve_buffer_backend() {
+    check_resolve(SymbolTable::<
+        4,
+        DeterministicHashBuilder,
+        backend::BufferBackend,
+    >::default());
+}
+
+#[test]
+fn test_resolve_arc_backend() {
+    check_resolve(SymbolTable::<
+        4,
+        DeterministicHashBuilder,
+        backend::ArcBackend,
+    >::default());
+}
+
+#[test]
+fn test_arc() {
+    let interner = SymbolTable::<4, DeterministicHashBuilder, backend::ArcBackend>::default()
+        .with_inline_strings()
+        .with_transform(|s| match s.trim() {
+            s if s.chars().any(char::is_uppercase) => Cow::Owned(s.to_lowercase()),
+            s => Cow::Borrowed(s),
+        });
+    let foo: Arc<str> = Arc::from("foo");
+    let sym = interner.intern_arc(foo.clone());
+    assert!(Arc::ptr_eq(&interner.resolve_arc(sym), &foo));
+    assert_eq!(interner.intern_arc(Arc::from("foo")), sym);
+    assert_eq!(interner.intern_arc(Arc::from(" FOO ")), sym);
+
+    let bar = interner.intern_arc(Arc::from("  bar"));
+    let le = interner.intern_arc(Arc::from("<="));
+    let (bar, le) = (interner.resolve_arc(bar), interner.resolve_arc(le));
+    drop(interner);
+    assert_eq!((&*bar, &*le), ("bar", "<="));
+    assert_eq!(Arc::strong_count(&foo), 1);
+}
+
+// small enough to run under Miri, see the CI workflow
+fn check_resolve_while_growing<B: backend::Backend>() {
+    let interner = SymbolTable::<2, DeterministicHashBuilder, B>::default();
+    let first = interner.resolve(interner.intern("first"));
+    let owned = interner.resolve(interner.intern_owned("owned".to_string()));
+    let syms: Vec<Symbol> = (0..200).map(|i| interner.intern(&i.to_string())).collect();
+    assert_eq!((first, owned), ("first", "owned"));
+    for (i, &sym) in syms.iter().enumerate() {
+        assert_eq!(interner.resolve(sym), i.to_string());
+        // SAFETY: the symbol was interned into this table
+        assert_eq!(unsafe { interner.resolve_unchecked(sym) }, i.to_string());
+    }
+    let clone = interner.clone();
+    drop(interner);
+    assert_eq!(clone.resolve(syms[7]), "7");
+}
+
+#[test]
+fn test_resolve_while_growing() {
+    check_resolve_while_growing::<backend::StringBackend>();
+    check_resolve_while_growing::<backend::BufferBackend>();
+    check_resolve_while_growing::<backend::ArcBackend>();
+}
+
+#[cfg(feature = "global")]
+#[test]
+fn test_global() {
+    let mut map = HashMap::new();
+
+    for word in TEXT.split_whitespace().chain(TEXT.split_whitespace().rev()) {
+        let sym = GlobalSymbol::from(word);
+        match map.entry(word) {
+            Entry::Occupied(e) => {
+                assert_eq!(*e.get(), sym);
+            }
+            Entry::Vacant(e) => {
+                e.insert(sym);
+            }
+        }
+
+        assert_eq!(sym.to_string(), word);
+        assert_eq!(sym.to_owned_string(), word);
+        assert_eq!(sym.as_str(), word);
+    }
+}
+
+#[cfg(feature = "global")]
+#[test]
+fn test_symbol_macro() {
+    for word in TEXT.split_whitespace().take(100) {
+        assert_eq!(
+            GlobalSymbol::new_prehashed(SymbolTable::new().hash_str(word), word),
+            GlobalSymbol::from(word)
+        );
+    }
+    let hello = symbol_table_macros::symbol!("hello");
+    assert_eq!(hello, GlobalSymbol::from("hello"));
+    assert_eq!(symbol_table_macros::symbol!("hello"), hello);
+
+    let probe_hash = SymbolTable::new().hash_str(symbol_table::__HASH_PROBE);
+    assert!(GlobalSymbol::__hashes_match(probe_hash));
+    assert!(!GlobalSymbol::__hashes_match(probe_hash ^ 1));
+}
+
+#[cfg(feature = "global")]
+#[test]
+fn test_keywords() {
+    keywords! {
+        mod kw {
+            IF = "if",
+            ELSE = "else",
+            WHILE = "while",
+            ALSO_IF = "if",
+        }
+    }
+
+    assert_eq!((kw::IF, kw::ELSE, kw::WHILE, kw::ALSO_IF), (0, 1, 2, 3));
+    let table = kw::table();
+    assert_eq!(table.len(), 4);
+    for (i, s) in kw::STRINGS.iter().enumerate() {
+        assert_eq!(table.symbol(i).as_str(), *s);
+    }
+    assert_eq!(table.index_of(GlobalSymbol::new("while")), Some(kw::WHILE));
+    assert_eq!(table.index_of(GlobalSymbol::new("if")), Some(kw::IF));
+    assert_eq!(table.index_of(GlobalSymbol::new("loop")), None);
+    assert_eq!(kw::index_of(GlobalSymbol::new("else")), Some(kw::ELSE));
+    assert_eq!(kw::symbol(kw::ALSO_IF), GlobalSymbol::new("if"));
+}
+
+#[cfg(feature = "global")]
+#[test]
+fn test_derive_symbols() {
+    #[derive(Debug, Clone, Copy, PartialEq, symbol_table_macros::Symbols)]
+    enum Token {
+        Let,
+        If,
+        #[symbol = "=>"]
+        FatArrow,
+        WhileLet,
+        HTTPServer,
+        Utf8Str,
+    }
+
+    let all = [
+        Token::Let,
+        Token::If,
+        Token::FatArrow,
+        Token::WhileLet,
+        Token::HTTPServer,
+        Token::Utf8Str,
+    ];
+    let strings = ["let", "if", "=>", "while_let", "http_server", "utf8_str"];
+    for (token, s) in all.iter().zip(strings) {
+        assert_eq!(token.to_symbol(), GlobalSymbol::from(s));
+        assert_eq!(Token::from_symbol(s.into()), Some(*token));
+    }
+    assert_eq!(Token::from_symbol("Let".into()), None);
+
+    // the same names as `to_snake_case` gives
+    for (name, token) in ["Let", "WhileLet", "HTTPServer", "Utf8Str"]
+        .into_iter()
+        .zip([
+            Token::Let,
+            Token::WhileLet,
+            Token::HTTPServer,
+            Token::Utf8Str,
+        ])
+    {
+        assert_eq!(GlobalSymbol::new(name).to_snake_case(), token.to_symbol());
+    }
+}
+
+#[cfg(feature = "global")]
+#[test]
+fn test_global_deref() {
+    fn takes_str(s: impl AsRef<str>) -> usize {
+        s.as_ref().len()
+    }
+
+    let sym = GlobalSymbol::from("hello world");
+    assert_eq!(sym.len(), 11);
+    assert_eq!(sym.split(' ').collect::<Vec<_>>(), ["hello", "world"]);
+    assert_eq!(takes_str(sym), 11);
+    assert_eq!(&*sym, "hello world");
+}
+
+#[cfg(feature = "global")]
+#[test]
+fn test_global_as_path() {
+    use std::{ffi::OsStr, path::Path};
+
+    let dir = GlobalSymbol::from("src");
+    let path = Path::new("/crate")
+        .join(dir)
+        .join(GlobalSymbol::from("lib.rs"));
+    assert_eq!(path, Path::new("/crate/src/lib.rs"));
+    assert_eq!(AsRef::<OsStr>::as_ref(&dir), OsStr::new("src"));
+    assert!(std::fs::metadata(GlobalSymbol::from("no/such/file")).is_err());
+}
+
+#[cfg(feature = "global")]
+#[test]
+fn test_global_from_display() {
+    for i in (0..1000u64).chain([u64::MAX]) {
+        assert_eq!(GlobalSymbol::from_display(i), i.to_string());
+    }
+    let long = "y".repeat(500);
+    assert_eq!(GlobalSymbol::from_display(&long), long);
+}
+
+#[cfg(feature = "global")]
+#[test]
+fn test_global_case() {
+    for word in TEXT.split_whitespace().take(1000) {
+        let sym = GlobalSymbol::new(word);
+        assert_eq!(sym.to_lowercase(), word.to_lowercase());
+        assert_eq!(sym.to_uppercase(), word.to_uppercase());
+    }
+    let long = "Y".repeat(500);
+    assert_eq!(GlobalSymbol::new(&long).to_lowercase(), long.to_lowercase());
+
+    for (from, to) in [
+        ("camelCase", "camel_case"),
+        ("PascalCase", "pascal_case"),
+        ("SCREAMING_SNAKE", "screaming_snake"),
+        ("kebab-case", "kebab_case"),
+        ("XMLHttpRequest", "xml_http_request"),
+        ("utf8Bytes", "utf8_bytes"),
+        ("  padded  words ", "padded_words"),
+        ("__private", "private"),
+        ("ÉtéÀParis", "été_à_paris"),
+        ("", ""),
+        ("already_snake_2", "already_snake_2"),
+        ("_private", "private"),
+        ("trailing_", "trailing"),
+        ("double__under", "double_under"),
+        ("snake_Upper", "snake_upper"),
+        ("with space", "with_space"),
+    ] {
+        assert_eq!(GlobalSymbol::new(from).to_snake_case(), to);
+    }
+}
+
+#[cfg(feature = "global")]
+#[test]
+fn test_global_join() {
+    let words: Vec<&str> = TEXT.split_whitespace().take(100).collect();
+    assert_eq!(GlobalSymbol::concat(&words), words.concat());
+    assert_eq!(GlobalSymbol::join("::", &words), words.join("::"));
+    assert_eq!(GlobalSymbol::join(", ", &words[..1]), words[0]);
+    assert_eq!(GlobalSymbol::join(", ", Vec::<&str>::new()), "");
+}
+
+#[cfg(feature = "global")]
+#[test]
+fn test_global_eq_str() {
+    let sym = GlobalSymbol::from("return");
+    assert!(sym == "return");
+    assert!("return" == sym);
+    assert!(sym == *"return");
+    assert!(*"return" == sym);
+    let owned = String::from("return");
+    assert!(sym == owned);
+    assert!(owned == sym);
+    assert!(sym != "continue");
+}
+
+#[cfg(feature = "global")]
+#[test]
+fn test_str_hash_global_symbol() {
+    use std::hash::BuildHasher;
+
+    #[derive(Hash)]
+    struct Field<T> {
+        name: T,
+        ty: T,
+    }
+
+    let build_hasher = PortableHashBuilder;
+    let field = Field {
+        name: StrHashGlobalSymbol::from(GlobalSymbol::new("len")),
+        ty: StrHashGlobalSymbol::from(GlobalSymbol::new("usize")),
+    };
+    let strs = Field {
+        name: "len",
+        ty: "usize",
+    };
+    assert_eq!(build_hasher.hash_one(&field), build_hasher.hash_one(&strs));
+
+    let set: HashSet<_> = TEXT
+        .split_whitespace()
+        .map(|word| StrHashGlobalSymbol(GlobalSymbol::new(word)))
+        .collect();
+    let words: HashSet<&str> = TEXT.split_whitespace().collect();
+    assert_eq!(set.len(), words.len());
+    let first = TEXT.split_whitespace().next().unwrap();
+    assert!(set.contains(&StrHashGlobalSymbol(GlobalSymbol::new(first))));
+}
+
+#[cfg(feature = "global")]
+#[test]
+fn test_ordered_global_symbol() {
+    use std::collections::BTreeMap;
+
+    let mut counts = BTreeMap::new();
+    for word in TEXT.split_whitespace() {
+        *counts
+            .entry(OrderedGlobalSymbol::from(GlobalSymbol::new(word)))
+            .or_insert(0) += 1;
+    }
+    let mut words: Vec<&str> = TEXT.split_whitespace().collect();
+    words.sort();
+    words.dedup();
+    let keys: Vec<&str> = counts.keys().map(|sym| sym.as_str()).collect();
+    assert_eq!(keys, words);
+}
+
+#[cfg(feature = "global")]
+#[test]
+fn test_global_namespace() {
+    symbol_namespace!(Words);
+
+    let words: Vec<&str> = TEXT.split_whitespace().collect();
+    let syms: Vec<NsSymbol<Words>> = words.iter().map(NsSymbol::intern).collect();
+    for (word, sym) in words.iter().zip(&syms) {
+        assert_eq!(sym.as_str(), *word);
+        assert_eq!(NsSymbol::<Words>::intern(word), *sym);
+    }
+    assert_eq!(GlobalSymbol::intern("foo"), GlobalSymbol::from("foo"));
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn test_reset_for_tests() {
+    symbol_namespace!(Scratch);
+    let table = Scratch::table();
+
+    NsSymbol::<Scratch>::intern("foo");
+    let tmp = table.resolve(table.fresh("tmp")).to_owned();
+    assert_eq!(table.stats().len, 2);
+
+    unsafe { NsSymbol::<Scratch>::reset_for_tests() };
+    assert_eq!(table.stats().len, 0);
+    assert_eq!(table.lookup("foo"), None);
+    assert_eq!(table.resolve(table.fresh("tmp")), tmp);
+    assert_eq!(NsSymbol::<Scratch>::intern("foo").as_str(), "foo");
+}
+
+#[test]
+fn test_symbol_u32() {
+    let interner = SymbolTable::new();
+    for word in TEXT.split_whitespace() {
+        let sym = interner.intern(word);
+        let raw = sym.to_u32();
+        assert_eq!(raw, std::num::NonZeroU32::from(sym).get());
+        assert_eq!(Symbol::try_from_u32(raw), Some(sym));
+        assert_eq!(unsafe { Symbol::from_u32_unchecked(raw) }, sym);
+    }
+    assert_eq!(Symbol::try_from_u32(0), None);
+}
+
+#[test]
+#[cfg(feature = "bytemuck")]
+fn test_symbol_bytemuck() {
+    let interner = SymbolTable::new();
+    let syms = interner.intern_words(TEXT);
+    let bytes: &[u8] = bytemuck::cast_slice(&syms);
+    assert_eq!(bytes.len(), 4 * syms.len());
+    let raws: &[u32] = bytemuck::cast_slice(&syms);
+    assert!(raws
+        .iter()
+        .zip(&syms)
+        .all(|(&raw, sym)| raw == sym.to_u32()));
+    assert_eq!(
+        bytemuck::checked::cast_slice::<u32, Symbol>(raws),
+        &syms[..]
+    );
+    assert!(bytemuck::checked::try_cast::<u32, Symbol>(0).is_err());
+
+    let raws = [0, syms[0].to_u32()];
+    let maybe: &[Option<Symbol>] = bytemuck::cast_slice(&raws);
+    assert_eq!(maybe, [None, Some(syms[0])]);
+}
+
+#[test]
+#[cfg(feature = "zerocopy")]
+fn test_symbol_zerocopy() {
+    use zerocopy::{IntoBytes, TryFromBytes};
+
+    let interner = SymbolTable::new();
+    let syms = interner.intern_words(TEXT);
+    let bytes = syms.as_bytes();
+    assert_eq!(bytes.len(), 4 * syms.len());
+    assert_eq!(<[Symbol]>::try_ref_from_bytes(bytes).unwrap(), &syms[..]);
+    assert!(Symbol::try_read_from_bytes(&[0; 4]).is_err());
+    assert_eq!(
+        Symbol16::try_read_from_bytes(&1u16.to_ne_bytes()).unwrap(),
+        Symbol16::from(std::num::NonZeroU16::MIN),
+    );
+}
+
+#[test]
+fn test_shard_of() {
+    let interner = SymbolTable::new();
+    let mut shards: Vec<Vec<&str>> = vec![Vec::new(); DEFAULT_N_SHARDS];
+    for word in TEXT.split_whitespace() {
+        let sym = interner.intern(word);
+        let shard = &mut shards[interner.shard_of(sym)];
+        let idx = interner.index_in_shard(sym) as usize;
+        assert!(idx <= shard.len());
+        if idx == shard.len() {
+            shard.push(word);
+        }
+        assert_eq!(shard[idx], word);
+    }
+    assert!(shards.iter().all(|shard| !shard.is_empty()));
+}
+
+#[test]
+#[should_panic = "Inline symbols aren't in a shard"]
+fn test_shard_of_empty() {
+    let interner = SymbolTable::new();
+    interner.shard_of(interner.intern(""));
+}
+
+#[test]
+#[should_panic = "Inline symbols aren't in a shard"]
+fn test_index_in_shard_empty() {
+    SymbolTable::new().index_in_shard(Symbol::EMPTY);
+}
+
+#[test]
+fn test_symbols_with_prefix() {
+    let interner = SymbolTable::new().with_inline_strings();
+    let mut words: Vec<&str> = TEXT.split_whitespace().collect();
+    for word in &words {
+        interner.intern(word);
+    }
+    words.sort_unstable();
+    words.dedup();
+
+    for prefix in ["", "th", "Gulliver", "zzz"] {
+        let mut matches: Vec<&str> = interner
+            .symbols_with_prefix(prefix)
+            .map(|(sym, s)| {
+                assert_eq!(interner.resolve(sym), s);
+                s
+            })
+            .collect();
+        matches.sort_unstable();
+        let expected = words.iter().filter(|word| word.starts_with(prefix));
+        // inline strings aren't stored, so they can't be found
+        let expected = expected.filter(|word| word.len() > 2 || !word.is_ascii());
+        assert_eq!(matches, expected.copied().collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn test_find() {
+    let interner = SymbolTable::new();
+    for word in TEXT.split_whitespace() {
+        interner.intern(word);
+    }
+    let found: Vec<(Symbol, &str)> = interner.find(|s| s.contains("ll")).collect();
+    assert!(!found.is_empty());
+    for &(sym, s) in &found {
+        assert!(s.contains("ll"));
+        assert_eq!(interner.resolve(sym), s);
+    }
+    let all = interner
+        .symbols()
+        .filter(|&sym| interner.resolve(sym).contains("ll"));
+    assert_eq!(all.count(), found.len());
+    assert_eq!(interner.find(|_| false).count(), 0);
+}
+
+#[test]
+fn test_sorted_index() {
+    let interner = SymbolTable::new();
+    let mut index = interner.sorted_index();
+    assert!(index.is_empty());
+
+    let mut words: Vec<&str> = Vec::new();
+    for (i, word) in TEXT.split_whitespace().enumerate() {
+        interner.intern(word);
+        words.push(word);
+        if i % 1000 == 0 {
+            words.sort_unstable();
+            words.dedup();
+            let sorted: Vec<&str> = index.iter().map(|(_, s)| s).collect();
+            assert_eq!(sorted, words);
+        }
+    }
+    words.sort_unstable();
+    words.dedup();
+    for (sym, s) in index.iter() {
+        assert_eq!(interner.resolve(sym), s);
+    }
+
+    let range: Vec<&str> = index.range("b".."d").map(|(_, s)| s).collect();
+    let expected = words.iter().filter(|&&w| ("b".."d").contains(&w));
+    assert_eq!(range, expected.copied().collect::<Vec<_>>());
+    let prefixed: Vec<&str> = index.with_prefix("th").map(|(_, s)| s).collect();
+    let expected = words.iter().filter(|w| w.starts_with("th"));
+    assert_eq!(prefixed, expected.copied().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_intern_slice() {
+    let interner = SymbolTable::new();
+    let text = interner.intern(TEXT);
+    let mut start = 0;
+    for word in TEXT.split_whitespace() {
+        start += TEXT[start..].find(word).unwrap();
+        let sym = interner.intern_slice(text, start..start + word.len());
+        assert_eq!(sym, interner.intern(word));
+        start += word.len();
+    }
+    assert_eq!(interner.resolve(text), TEXT);
+    let tail = interner.intern_slice(text, TEXT.len() - 10..);
+    assert_ne!(
+        interner.resolve(tail).as_ptr(),
+        interner.resolve(text)[TEXT.len() - 10..].as_ptr()
+    );
+
+    let borrowed = BorrowedSymbolTable::new();
+    let text = borrowed.intern(TEXT);
+    let tail = borrowed.intern_slice(text, TEXT.len() - 10..);
+    assert_eq!(
+        borrowed.resolve(tail).as_ptr(),
+        TEXT[TEXT.len() - 10..].as_ptr()
+    );
+}
+
+#[test]
+fn test_symbol_builder() {
+    use std::fmt::Write;
+
+    let interner = SymbolTable::new();
+    let mut builder = interner.builder();
+    for (i, word) in TEXT.split_whitespace().enumerate().take(1000) {
+        write!(builder, "{word}#{i}").unwrap();
+        assert_eq!(builder.as_str(), format!("{word}#{i}"));
+        let sym = builder.finish();
+        assert_eq!(builder.as_str(), "");
+        assert_eq!(interner.resolve(sym), format!("{word}#{i}"));
+    }
+    builder.push_str("discarded");
+    builder.clear();
+    builder.push_str("kept");
+    assert_eq!(builder.finish(), interner.intern("kept"));
+}
+
+#[test]
+fn test_specific_strings() {
+    let interner = SymbolTable::new();
+    let strings = ["", "asdf", "🧵"];
+    for word in strings {
+        let sym = interner.intern(word);
+        assert_eq!(interner.resolve(sym), word);
+    }
+}
+
+#[test]
+fn test_large_strings() {
+    let interner = SymbolTable::new();
+    let strings: Vec<String> = (0..20).map(|i| "x".repeat(1 << i)).collect();
+    let syms: Vec<Symbol> = strings.iter().map(|s| interner.intern(s)).collect();
+    for (s, sym) in strings.iter().zip(syms) {
+        assert_eq!(interner.intern(s), sym);
+        assert_eq!(interner.resolve(sym), s);
+    }
+}
+
+#[test]
+fn test_large_strings_buffer_backend() {
+    let interner = SymbolTable::<4, DeterministicHashBuilder, backend::BufferBackend>::default();
+    let strings: Vec<String> = (0..20).map(|i| "x".repeat(1 << i)).collect();
+    let syms: Vec<Symbol> = strings.iter().map(|s| interner.intern(s)).collect();
+    for (s, sym) in strings.iter().zip(syms) {
+        assert_eq!(interner.intern(s), sym);
+        assert_eq!(interner.resolve(sym), s);
+    }
+}
+
+#[test]
+fn test_empty_symbol() {
+    let interner = SymbolTable::<4, DeterministicHashBuilder, backend::BufferBackend>::default();
+    assert_eq!(interner.lookup(""), Some(Symbol::EMPTY));
+    assert_eq!(interner.intern(""), Symbol::EMPTY);
+    assert_eq!(interner.intern_owned(String::new()), Symbol::EMPTY);
+    assert_eq!(interner.intern_many(["", "a"])[0], Symbol::EMPTY);
+    assert_eq!(interner.resolve(Symbol::EMPTY), "");
+    assert_eq!(interner.symbols().count(), 1);
+
+    let interner = SymbolTable::new().with_inline_strings();
+    assert_eq!(interner.intern(""), Symbol::EMPTY);
+
+    let bytes = ByteSymbolTable::new();
+    assert_eq!(bytes.resolve_bytes(bytes.intern_bytes(b"")), b"");
+    let cstrs = CStrSymbolTable::new();
+    let sym = cstrs.intern("").unwrap();
+    assert_ne!(sym, Symbol::EMPTY);
+    assert_eq!(cstrs.resolve_cstr(sym).to_bytes(), b"");
+}
+
+#[test]
+fn test_inline_strings() {
+    let interner = SymbolTable::new().with_inline_strings();
+    let strings = ["", "a", "<=", "\0\x7f", "abc", "é", "🧵"];
+    for word in strings {
+        let sym = interner.intern(word);
+        assert_eq!(interner.intern(word), sym);
+        assert_eq!(interner.resolve(sym), word);
+    }
+    check_resolve(SymbolTable::new().with_inline_strings());
+}
+
+#[test]
+fn test_intern_owned() {
+    let interner = SymbolTable::new();
+    for word in TEXT.split_whitespace() {
+        let sym = interner.intern_owned(word.to_string());
+        assert_eq!(interner.intern(word), sym);
+        assert_eq!(interner.resolve(sym), word);
+    }
+}
+
+#[test]
+fn test_intern_static() {
+    let mut interner: SymbolTable<1> =
+        SymbolTable::default().with_transform(|s| Cow::Borrowed(s.trim()));
+    let foo = interner.intern_static(" foo ");
+    assert_eq!(interner.resolve(foo), "foo");
+    let cp = interner.checkpoint();
+    let mut words = Vec::new();
+    for (i, word) in TEXT.split_whitespace().take(100).enumerate() {
+        let sym = match i % 2 {
+            0 => interner.intern_cow(Cow::Borrowed(word)),
+            _ => interner.intern_cow(Cow::Owned(word.to_string())),
+        };
+        assert_eq!(interner.intern(word), sym);
+        words.push((sym, word));
+    }
+    for &(sym, word) in &words {
+        assert_eq!(interner.resolve(sym), word);
+    }
+
+    // only the owned strings are freed
+    interner.rollback_to(&cp);
+    assert_eq!(interner.lookup(words[0].1), None);
+    assert_eq!(interner.intern("foo"), foo);
+    let clone = interner.clone();
+    drop(interner);
+    assert_eq!(clone.resolve(foo), "foo");
+}
+
+#[test]
+fn test_transform() {
+    let interner = SymbolTable::new().with_transform(|s| Cow::Owned(s.to_ascii_lowercase()));
+    for word in TEXT.split_whitespace() {
+        let sym = interner.intern(&word.to_ascii_uppercase());
+        assert_eq!(interner.intern_owned(word.to_string()), sym);
+        assert_eq!(interner.resolve(sym), word.to_ascii_lowercase());
+    }
+
+    let interner = SymbolTable::new().with_transform(|s| Cow::Borrowed(s.trim()));
+    let sym = interner.intern_owned(" padded ".to_string());
+    assert_eq!(interner.intern("padded"), sym);
+    assert_eq!(interner.resolve(sym), "padded");
+}
+
+#[test]
+fn test_unwind_safe() {
+    fn assert_unwind_safe<T: UnwindSafe + RefUnwindSafe>(_: &T) {}
+
+    let interner = SymbolTable::new().with_transform(|s| Cow::Borrowed(s.trim()));
+    assert_unwind_safe(&interner);
+    let result = std::panic::catch_unwind(|| interner.intern("foo"));
+    assert_eq!(interner.resolve(result.unwrap()), "foo");
+}
+
+#[test]
+fn test_limits() {
+    let interner = SymbolTable::new()
+        .with_max_str_len(5)
+        .with_max_entries(100)
+        .with_inline_strings();
+    let mut syms = HashMap::new();
+    for word in TEXT.split_whitespace() {
+        match interner.try_intern(word) {
+            Ok(sym) => assert_eq!(*syms.entry(word).or_insert(sym), sym),
+            Err(InternError::TooLong { len, max: 5 }) => assert_eq!(len, word.len()),
+            Err(err) => {
+                assert_eq!(err, InternError::TooManyEntries { max: 100 });
+                assert!(!syms.contains_key(word));
+            }
+        }
+    }
+    let stored = syms.keys().filter(|w| w.len() > 2 || !w.is_ascii()).count();
+    assert_eq!(stored, 100);
+    for (word, sym) in syms {
+        assert_eq!(interner.resolve(sym), word);
+    }
+}
+
+#[test]
+#[should_panic(expected = "longer than the limit")]
+fn test_limits_panic() {
+    SymbolTable::new().with_max_str_len(2).intern("foo");
+}
+
+#[test]
+fn test_validator() {
+    let interner = SymbolTable::new()
+        .with_inline_strings()
+        .with_transform(|s| Cow::Borrowed(s.trim_end_matches(['.', ',', ';'])))
+        .with_validator(validate::identifier);
+    for word in TEXT.split_whitespace() {
+        let trimmed = word.trim_end_matches(['.', ',', ';']);
+        match interner.try_intern(word) {
+            Ok(sym) => assert_eq!(interner.resolve(sym), trimmed),
+            Err(InternError::Invalid {
+                reason: InvalidStr::NotIdentifier { at },
+            }) => assert!(validate::identifier(&trimmed[..at]).is_ok() || at == 0),
+            Err(err) => panic!("{err}"),
+        }
+    }
+    assert!(interner
+        .symbols()
+        .all(|sym| validate::identifier(interner.resolve(sym)).is_ok()));
+    assert!(interner.try_intern("-").is_err());
+    assert!(interner.intern_many(["a", "b"]).len() == 2);
+
+    let mut staging = interner.staging();
+    staging.intern("fine");
+    let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| staging.intern("not fine")));
+    assert!(err.is_err());
+}
+
+#[test]
+#[should_panic(expected = "invalid string: not ASCII")]
+fn test_validator_panic() {
+    SymbolTable::new()
+        .with_validator(|s| match s.is_ascii() {
+            true => Ok(()),
+            false => Err(InvalidStr::Other("not ASCII".into())),
+        })
+        .intern("naïve");
+}
+
+#[test]
+fn test_compact() {
+    let mut interner = SymbolTable::new();
+    let syms: Vec<Symbol> = TEXT
+        .split_whitespace()
+        .map(|w| interner.intern(w))
+        .collect();
+    let remap = interner.compact(|_, s| s.len() % 2 == 0);
+    for (word, sym) in TEXT.split_whitespace().zip(syms) {
+        match remap.get(sym) {
+            Some(new) => {
+                assert_eq!(word.len() % 2, 0);
+                assert_eq!(interner.resolve(new), word);
+                assert_eq!(interner.intern(word), new);
+            }
+            None => assert_ne!(word.len() % 2, 0),
+        }
+    }
+    for (old, new) in remap.iter() {
+        assert_eq!(remap.get(old), Some(new));
+    }
+}
+
+#[test]
+fn test_reserve() {
+    let interner: SymbolTable<4> = SymbolTable::default();
+    let words: Vec<&str> = TEXT.split_whitespace().collect();
+    let (first, rest) = words.split_at(words.len() / 2);
+    let syms: Vec<Symbol> = first.iter().map(|w| interner.intern(w)).collect();
+    interner.reserve(100_000);
+    for (word, sym) in first.iter().zip(&syms) {
+        assert_eq!(interner.intern(word), *sym);
+    }
+    for word in rest {
+        let sym = interner.intern(word);
+        assert_eq!(interner.resolve(sym), *word);
+    }
+}
+
+#[test]
+fn test_symbols() {
+    let interner: SymbolTable<4> = SymbolTable::default();
+    let mut syms: Vec<Symbol> = TEXT
+        .split_whitespace()
+        .map(|w| interner.intern(w))
+        .collect();
+    syms.sort();
+    syms.dedup();
+
+    let snapshot = interner.symbols();
+    let new = interner.intern("not in the text");
+    let mut all: Vec<Symbol> = snapshot.collect();
+    all.sort();
+    assert_eq!(all, syms);
+    assert!(interner.symbols().any(|sym| sym == new));
+}
+
+#[test]
+fn test_cmp_str() {
+    let interner: SymbolTable<4> = SymbolTable::default().with_inline_strings();
+    let mut words: Vec<&str> = TEXT.split_whitespace().collect();
+    let mut syms: Vec<Symbol> = words.iter().map(|w| interner.intern(w)).collect();
+    words.sort();
+    syms.sort_by(|&a, &b| interner.cmp_str(a, b));
+    let resolved: Vec<&str> = syms.iter().map(|&sym| interner.resolve(sym)).collect();
+    assert_eq!(resolved, words);
+
+    for (&a, &b) in syms.iter().zip(&syms[1..]) {
+        assert_eq!(
+            interner.eq_str(a, b),
+            interner.resolve(a) == interner.resolve(b)
+        );
+    }
+}
+
+#[test]
+fn test_display() {
+    let interner = SymbolTable::new().with_inline_strings();
+    for word in TEXT.split_whitespace() {
+        let sym = interner.intern(word);
+        assert_eq!(interner.display(sym).to_string(), word);
+        assert_eq!(format!("{:?}", interner.display(sym)), format!("{word:?}"));
+    }
+}
+
+#[test]
+fn test_extend() {
+    let table: SymbolTable<4> = TEXT.split_whitespace().collect();
+    let mut words: Vec<&str> = TEXT.split_whitespace().collect();
+    words.sort();
+    words.dedup();
+    assert_eq!(table.symbols().count(), words.len());
+    for word in &words {
+        let sym = table.lookup(word).unwrap();
+        assert_eq!(table.resolve(sym), *word);
+    }
+
+    let mut table = SymbolTable::new()
+        .with_inline_strings()
+        .with_transform(|s| Cow::Owned(s.to_ascii_lowercase()));
+    let foo = table.intern("foo");
+    table.extend(["FOO", "Bar", "a", "bar"]);
+    assert_eq!(table.lookup("Foo"), Some(foo));
+    assert_eq!(table.resolve(table.lookup("BAR").unwrap()), "bar");
+    assert_eq!(table.symbols().count(), 2);
+}
+
+#[test]
+fn test_intern_lines() {
+    let interner = SymbolTable::new();
+    let lines = interner.intern_lines(std::io::BufReader::with_capacity(16, TEXT.as_bytes()));
+    let syms: Vec<Symbol> = lines.collect::<std::io::Result<_>>().unwrap();
+    let expected: Vec<&str> = TEXT.lines().collect();
+    assert_eq!(syms.len(), expected.len());
+    for (sym, line) in syms.iter().zip(expected) {
+        assert_eq!(interner.resolve(*sym), line);
+    }
+
+    let mut lines = interner.intern_lines(&b"ok\n\xff\nok again"[..]);
+    assert_eq!(lines.next().unwrap().unwrap(), interner.intern("ok"));
+    let err = lines.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(lines.next().unwrap().unwrap(), interner.intern("ok again"));
+    assert!(lines.next().is_none());
+}
+
+#[test]
+fn test_intern_lines_limits() {
+    let interner = SymbolTable::new().with_max_str_len(3);
+    let mut lines = interner.intern_lines(
+        &b"foo
+too long
+bar"[..],
+    );
+    assert_eq!(lines.next().unwrap().unwrap(), interner.intern("foo"));
+    let err = lines.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    let err = err.into_inner().unwrap().downcast::<InternError>().unwrap();
+    assert!(matches!(*err, InternError::TooLong { .. }));
+    assert_eq!(lines.next().unwrap().unwrap(), interner.intern("bar"));
+}
+
+#[test]
+fn test_intern_words() {
+    let one_by_one = SymbolTable::new().with_inline_strings();
+    let expected: Vec<Symbol> = TEXT
+        .split_whitespace()
+        .map(|w| one_by_one.intern(w))
+        .collect();
+    let batched = SymbolTable::new().with_inline_strings();
+    batched.intern("Gulliver");
+    let syms = batched.intern_words(TEXT);
+    assert_eq!(syms.len(), expected.len());
+    for (sym, word) in syms.iter().zip(TEXT.split_whitespace()) {
+        assert_eq!(batched.resolve(*sym), word);
+        assert_eq!(batched.intern(word), *sym);
+    }
+
+    let lowercase = SymbolTable::new().with_transform(|s| Cow::Owned(s.to_ascii_lowercase()));
+    let syms = lowercase.intern_many(["Foo", "FOO", "bar"]);
+    assert_eq!(syms[0], syms[1]);
+    assert_eq!(lowercase.resolve(syms[1]), "foo");
+    assert!(lowercase.intern_many([]).is_empty());
+}
+
+#[test]
+fn test_intern_fmt() {
+    let interner = SymbolTable::new();
+    for (i, word) in TEXT.split_whitespace().enumerate() {
+        let sym = intern_fmt!(interner, "{word}_{i}");
+        assert_eq!(interner.resolve(sym), format!("{word}_{i}"));
+    }
+    let long = "x".repeat(1000);
+    let sym = intern_fmt!(interner, "a{}b{}", &long[..40], long);
+    assert_eq!(interner.resolve(sym), format!("a{}b{long}", &long[..40]));
+    assert_eq!(intern_fmt!(interner, "literal"), interner.intern("literal"));
+}
+
+#[test]
+fn test_fresh() {
+    let interner: SymbolTable<4> = SymbolTable::default().with_inline_strings();
+    let taken: Vec<Symbol> = (0..100)
+        .filter(|i| i % 3 == 0)
+        .map(|i| interner.intern(&format!("x{i}")))
+        .collect();
+    let fresh: Vec<Symbol> = std::thread::scope(|s| {
+        let threads: Vec<_> = (0..4)
+            .map(|_| s.spawn(|| (0..50).map(|_| interner.fresh("x")).collect::<Vec<_>>()))
+            .collect();
+        threads
+            .into_iter()
+            .flat_map(|t| t.join().unwrap())
+            .collect()
+    });
+
+    let mut all: Vec<Symbol> = taken.iter().chain(&fresh).copied().collect();
+    all.sort();
+    all.dedup();
+    assert_eq!(all.len(), taken.len() + fresh.len());
+    for sym in fresh {
+        let name = interner.resolve(sym);
+        assert!(name.len() > 2 && name.starts_with('x'));
+    }
+}
+
+// run with `RUSTFLAGS="--cfg loom" cargo test --features loom --test test loom`
+#[test]
+#[cfg(all(loom, feature = "loom"))]
+fn test_loom_intern() {
+    loom::model(|| {
+        let interner = Arc::new(SymbolTable::<1>::default().with_max_entries(2));
+        let threads: Vec<_> = ["foo", "bar"]
+            .into_iter()
+            .map(|other| {
+                let interner = interner.clone();
+                loom::thread::spawn(move || (interner.intern("foo"), interner.try_intern(other)))
+            })
+            .collect();
+        let results: Vec<_> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+        assert_eq!(results[0].0, results[1].0);
+        assert_eq!(results[0].1, Ok(results[0].0));
+        assert_eq!(interner.resolve(results[1].1.clone().unwrap()), "bar");
+        assert_eq!(
+            interner.try_intern("baz"),
+            Err(InternError::TooManyEntries { max: 2 })
+        );
+    });
+}
+
+#[test]
+fn test_symbol_map() {
+    let interner: SymbolTable<4> = SymbolTable::default();
+    let mut counts: SymbolMap<usize, 4> = SymbolMap::new();
+    let mut expected = HashMap::new();
+    for word in TEXT.split_whitespace() {
+        let sym = interner.intern(word);
+        match counts.get_mut(sym) {
+            Some(count) => *count += 1,
+            None => assert_eq!(counts.insert(sym, 1), None),
+        }
+        *expected.entry(sym).or_insert(0) += 1;
+    }
+    assert_eq!(counts.len(), expected.len());
+    assert_eq!(counts.iter().count(), expected.len());
+    for (sym, &count) in counts.iter() {
+        assert_eq!(expected[&sym], count);
+    }
+
+    let the = interner.intern("the");
+    assert_eq!(counts.remove(the), Some(expected[&the]));
+    assert_eq!(counts.remove(the), None);
+    assert!(!counts.contains_key(the));
+    assert_eq!(counts.len(), expected.len() - 1);
+    counts.clear();
+    assert!(counts.is_empty());
+}
+
+#[test]
+fn test_symbol_set() {
+    let interner: SymbolTable<4> = SymbolTable::default();
+    let syms: Vec<Symbol> = TEXT
+        .split_whitespace()
+        .map(|w| interner.intern(w))
+        .collect();
+    let (evens, odds): (Vec<_>, Vec<_>) = syms.chunks(2).map(|c| (c[0], c[c.len() - 1])).unzip();
+
+    let mut a: SymbolSet<4> = evens.iter().copied().collect();
+    let b: SymbolSet<4> = odds.iter().copied().collect();
+    let hash_a: HashSet<Symbol> = evens.iter().copied().collect();
+    let hash_b: HashSet<Symbol> = odds.iter().copied().collect();
+    assert_eq!(a.len(), hash_a.len());
+    assert!(hash_a.iter().all(|&sym| a.contains(sym)));
+    assert_eq!(a.iter().collect::<HashSet<_>>(), hash_a);
+
+    let mut union = a.clone();
+    union.union_with(&b);
+    assert_eq!(union.iter().collect::<HashSet<_>>(), &hash_a | &hash_b);
+    a.intersect_with(&b);
+    assert_eq!(a.iter().collect::<HashSet<_>>(), &hash_a & &hash_b);
+
+    let the = interner.intern("the");
+    assert!(!union.insert(the));
+    assert!(union.remove(the));
+    assert!(!union.remove(the));
+    assert!(!union.contains(the));
+    union.clear();
+    assert!(union.is_empty());
+}
+
+#[test]
+fn test_identity_hasher() {
+    let interner = SymbolTable::new().with_inline_strings();
+    let words: Vec<&str> = TEXT.split_whitespace().collect();
+    let mut map = SymbolHashMap::default();
+    let mut set = SymbolHashSet::default();
+    for (i, word) in words.iter().enumerate() {
+        let sym = interner.intern(word);
+        map.entry(sym).or_insert(i);
+        set.insert(sym);
+    }
+    assert_eq!(map.len(), set.len());
+    for (&sym, &i) in &map {
+        assert_eq!(words[i], interner.resolve(sym));
+    }
+}
+
+#[test]
+fn test_stats() {
+    let interner: SymbolTable<4> = SymbolTable::default();
+    let mut words: Vec<&str> = TEXT.split_whitespace().collect();
+    for word in &words {
+        interner.intern(word);
+    }
+    words.sort();
+    words.dedup();
+
+    let stats = interner.stats();
+    assert_eq!(stats.len, words.len());
+    assert_eq!(stats.bytes, words.iter().map(|w| w.len()).sum::<usize>());
+    assert_eq!(stats.shard_lens.len(), 4);
+    assert_eq!(stats.shard_lens.iter().sum::<usize>(), stats.len);
+}
+
+#[test]
+fn test_staging() {
+    let interner: SymbolTable<4> = SymbolTable::default()
+        .with_inline_strings()
+        .with_transform(|s| Cow::Owned(s.to_lowercase()));
+    interner.intern("the");
+    let words: Vec<&str> = TEXT.split_whitespace().collect();
+
+    let chunks: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = words
+            .chunks(words.len() / 4 + 1)
+            .map(|chunk| {
+                let interner = &interner;
+                scope.spawn(move || {
+                    let mut staging = interner.staging();
+                    let syms: Vec<_> = chunk.iter().map(|word| staging.intern(word)).collect();
+                    for (&sym, word) in syms.iter().zip(chunk) {
+                        assert_eq!(staging.resolve(sym), word.to_lowercase());
+                    }
+                    (syms, staging.publish())
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let published = chunks
+        .iter()
+        .flat_map(|(syms, remap)| syms.iter().map(|&sym| remap.get(sym).unwrap()));
+    for (sym, word) in published.zip(&words) {
+        assert_eq!(sym, interner.intern(word));
+    }
+}
+
+#[test]
+fn test_on_intern() {
+    let new = Arc::new(Mutex::new(Vec::new()));
+    let interner: SymbolTable<4> = SymbolTable::default()
+        .with_inline_strings()
+        .with_on_intern({
+            let new = new.clone();
+            move |sym, s| new.lock().unwrap().push((sym, s.to_string()))
+        });
+    let words: Vec<&str> = TEXT.split_whitespace().collect();
+    interner.intern_many(words.iter().copied());
+    for word in &words {
+        interner.intern(word);
+    }
+    interner.intern_owned("a brand new string".to_string());
+
+    let new = new.lock().unwrap();
+    let stored: Vec<_> = interner.symbols().collect();
+    assert_eq!(new.len(), stored.len());
+    for (sym, s) in new.iter() {
+        assert!(stored.contains(sym));
+        assert_eq!(interner.resolve(*sym), s);
+    }
+}
+
+#[test]
+#[cfg(feature = "stats")]
+fn test_stats_counters() {
+    let interner: SymbolTable<4> = SymbolTable::default();
+    let words: Vec<&str> = TEXT.split_whitespace().collect();
+    let syms: Vec<_> = words.iter().map(|word| interner.intern(word)).collect();
+
+    let stats = interner.stats();
+    assert_eq!(stats.inserts as usize, stats.len);
+    assert_eq!((stats.hits + stats.inserts) as usize, words.len());
+    assert_eq!(stats.shard_locks.iter().sum::<u64>(), words.len() as u64);
+    assert_eq!(stats.shard_contended, [0; 4]);
+
+    for &sym in &syms {
+        interner.resolve(sym);
+    }
+    let after = interner.stats();
+    assert_eq!(after.hits, stats.hits);
+    assert_eq!(
+        after.shard_locks.iter().sum::<u64>(),
+        2 * words.len() as u64
+    );
+}
+
+#[test]
+#[cfg(feature = "counts")]
+fn test_intern_counts() {
+    let mut interner: SymbolTable<4> = SymbolTable::default().with_inline_strings();
+    let mut expected: HashMap<Symbol, u64> = HashMap::new();
+    for sym in interner.intern_words(TEXT) {
+        *expected.entry(sym).or_default() += 1;
+    }
+    let cp = interner.checkpoint();
+    let fresh = interner.fresh("tmp");
+    assert_eq!(interner.intern_count(fresh), 1);
+    interner.lookup(interner.resolve(fresh));
+    assert_eq!(interner.intern_count(fresh), 1);
+    interner.rollback_to(&cp);
+
+    let le = interner.intern("<=");
+    assert_eq!(interner.intern_count(le), 0);
+    for (&sym, &n) in &expected {
+        if interner.resolve(sym).len() > 2 {
+            assert_eq!(interner.intern_count(sym), n);
+        }
+    }
+
+    let top = interner.top_n(10);
+    assert_eq!(top.len(), 10);
+    assert!(top.windows(2).all(|w| w[0].1 >= w[1].1));
+    let max = expected
+        .iter()
+        .filter(|(sym, _)| interner.resolve(**sym).len() > 2);
+    assert_eq!(top[0].1, max.map(|(_, &n)| n).max().unwrap());
+
+    let the = interner.intern("the");
+    let remap = interner.compact(|_, s| s == "the");
+    assert_eq!(
+        interner.top_n(2),
+        [(remap.get(the).unwrap(), expected[&the] + 1)]
+    );
+}
+
+// records the `shard` and `capacity` fields of every event
+#[cfg(feature = "tracing")]
+#[derive(Default)]
+struct GrowthEvents(Mutex<Vec<(u64, u64)>>);
+
+#[cfg(feature = "tracing")]
+impl tracing::Subscriber for GrowthEvents {
+    fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        tracing::span::Id::from_u64(1)
+    }
+    fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+    fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+    fn enter(&self, _: &tracing::span::Id) {}
+    fn exit(&self, _: &tracing::span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        struct Fields(u64, u64);
+        impl tracing::field::Visit for Fields {
+            fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+                match field.name() {
+                    "shard" => self.0 = value,
+                    "capacity" => self.1 = value,
+                    _ => {}
+                }
+            }
+            fn record_debug(&mut self, _: &tracing::field::Field, _: &dyn std::fmt::Debug) {}
+        }
+        let mut fields = Fields(u64::MAX, 0);
+        event.record(&mut fields);
+        self.0.lock().unwrap().push((fields.0, fields.1));
+    }
+}
+
+#[test]
+#[cfg(feature = "tracing")]
+fn test_tracing_growth() {
+    let events = Arc::new(GrowthEvents::default());
+    let interner: SymbolTable<4> = SymbolTable::default();
+    tracing::subscriber::with_default(events.clone(), || {
+        for word in TEXT.split_whitespace() {
+            interner.intern(word);
+        }
+    });
+    let grown = std::mem::take(&mut *events.0.lock().unwrap());
+    assert!(!grown.is_empty());
+    assert!(grown
+        .iter()
+        .all(|&(shard, capacity)| shard < 4 && capacity > 0));
+    for shard in 0..4 {
+        assert!(grown.iter().any(|&(s, _)| s == shard));
+    }
+
+    // nothing grows when the strings are already there
+    tracing::subscriber::with_default(events.clone(), || {
+        for word in TEXT.split_whitespace() {
+            interner.intern(word);
+        }
+    });
+    assert!(events.0.lock().unwrap().is_empty());
+
+    let interner: SymbolTable<4> = SymbolTable::default();
+    tracing::subscriber::with_default(events.clone(), || interner.reserve(1000));
+    assert_eq!(events.0.lock().unwrap().len(), 8);
+}
+
+#[test]
+fn test_lookup() {
+    let interner: SymbolTable<4> =
+        SymbolTable::default().with_transform(|s| Cow::Owned(s.to_lowercase()));
+    let words: Vec<&str> = TEXT.split_whitespace().take(1000).collect();
+    for word in &words {
+        interner.intern(word);
+    }
+    for word in &words {
+        assert_eq!(interner.lookup(word), Some(interner.intern(word)));
+        assert_eq!(
+            interner.lookup(&word.to_uppercase()),
+            Some(interner.intern(word))
+        );
+    }
+    let len = interner.stats().len;
+    assert_eq!(interner.lookup("not in the text"), None);
+    assert_eq!(interner.stats().len, len);
+}
+
+#[test]
+fn test_clone() {
+    let interner: SymbolTable<16, DeterministicHashBuilder, backend::BufferBackend> =
+        SymbolTable::default();
+    let syms: Vec<Symbol> = TEXT
+        .split_whitespace()
+        .map(|w| interner.intern(w))
+        .collect();
+    let clone = interner.clone();
+    drop(interner);
+    for (word, sym) in TEXT.split_whitespace().zip(syms) {
+        assert_eq!(clone.resolve(sym), word);
+        assert_eq!(clone.intern(word), sym);
+    }
+
+    // the clone can grow independently
+    let fresh = clone.clone().intern("not in gulliver");
+    assert_ne!(clone.intern("also not in gulliver"), fresh);
+}
+
+#[test]
+fn test_eq() {
+    let words: Vec<&str> = TEXT.split_whitespace().collect();
+    let forward = SymbolTable::new();
+    let backward: SymbolTable<4, DeterministicHashBuilder, backend::BufferBackend, Symbol64> =
+        SymbolTable::default().with_inline_strings();
+    for word in &words {
+        forward.intern(word);
+    }
+    for word in words.iter().rev() {
+        backward.intern(word);
+    }
+    assert!(forward == backward);
+    assert!(backward == forward);
+    assert!(forward.same_symbols(&forward.clone()));
+    assert!(!forward.same_symbols(&SymbolTable::new()));
+
+    let reversed = SymbolTable::new();
+    for word in words.iter().rev() {
+        reversed.intern(word);
+    }
+    assert!(forward == reversed);
+    assert!(!forward.same_symbols(&reversed));
+
+    backward.intern("not in gulliver");
+    assert!(forward != backward);
+}
+
+#[test]
+fn test_intern_prehashed() {
+    use std::hash::{BuildHasher, RandomState};
+
+    let hasher = RandomState::new();
+    let prehashed: SymbolTable<4, _> = SymbolTable::with_hasher(hasher.clone());
+    let interner: SymbolTable<4, _> = SymbolTable::with_hasher(hasher.clone());
+    for word in TEXT.split_whitespace() {
+        let sym = prehashed.intern_prehashed(hasher.hash_one(word), word);
+        assert_eq!(sym, interner.intern(word));
+        assert_eq!(prehashed.hash_of(sym), hasher.hash_one(word));
+    }
+    assert!(prehashed.same_symbols(&interner));
+}
+
+#[test]
+fn test_debug() {
+    let interner: SymbolTable<2> = SymbolTable::default().with_inline_strings();
+    interner.intern("foo");
+    interner.intern("+");
+    let debug = format!("{interner:?}");
+    assert!(
+        debug.starts_with("SymbolTable { len: 1, shards: ["),
+        "{debug}"
+    );
+    assert!(!debug.contains("foo"));
+
+    let debug = format!("{interner:#?}");
+    assert!(debug.contains("}: \"foo\""), "{debug}");
+    assert!(!debug.contains("\"+\""));
+}
+
+#[test]
+fn test_symbol_debug() {
+    let table: &'static SymbolTable<2> = SymbolTable::default().with_inline_strings().leak();
+    let foo = table.intern("foo");
+    let plus = table.intern("+");
+    assert_eq!(format!("{plus:?}"), r#"Symbol { inline: "+" }"#);
+    assert_eq!(format!("{:?}", Symbol::EMPTY), r#"Symbol { inline: "" }"#);
+    assert_eq!(
+        format!("{:?}", Symbol::try_from_u32(u32::MAX).unwrap()),
+        format!("Symbol {{ invalid: {} }}", u32::MAX)
+    );
+
+    Symbol::set_debug_table(table);
+    let (shard, index) = (table.shard_of(foo), table.index_in_shard(foo));
+    assert_eq!(
+        format!("{foo:?}"),
+        format!(r#"Symbol {{ shard: {shard}, index: {index}, string: "foo" }}"#)
+    );
+    // symbols that aren't in the table only show where they would be
+    let missing = Symbol::try_from_u32(foo.to_u32() + 1).unwrap();
+    assert!(!format!("{missing:?}").contains("string"));
+    Symbol::clear_debug_table();
+    assert!(!format!("{foo:?}").contains("string"));
+}
+
+#[test]
+fn test_absorb() {
+    let (first, second) = TEXT.split_at(TEXT.len() / 2);
+    let interner = SymbolTable::new().with_inline_strings();
+    let other: SymbolTable<4> = SymbolTable::default().with_inline_strings();
+    for word in first.split_whitespace() {
+        interner.intern(word);
+    }
+    let syms: Vec<Symbol> = second.split_whitespace().map(|w| other.intern(w)).collect();
+
+    let remap = interner.absorb(&other);
+    for (word, sym) in second.split_whitespace().zip(syms) {
+        let new = remap.get(sym).unwrap();
+        assert_eq!(interner.resolve(new), word);
+        assert_eq!(interner.intern(word), new);
+    }
+
+    // absorbing a table into itself maps every symbol to itself
+    let remap = interner.absorb(&interner);
+    assert!(remap.iter().all(|(old, new)| old == new));
+}
+
+#[test]
+fn test_diff() {
+    let words: Vec<&str> = TEXT.split_whitespace().collect();
+    let (first, second) = words.split_at(words.len() / 2);
+    let old: SymbolTable<4> = first.iter().copied().collect();
+    assert!(old.diff(&old).is_empty());
+
+    // interning in another order changes symbols, but not strings
+    let new: SymbolTable<4> = words.iter().rev().copied().collect();
+    let diff = old.diff(&new);
+    assert!(diff.only_in_self.is_empty());
+    assert!(!diff.changed.is_empty());
+    for &(s, ours, theirs) in &diff.changed {
+        assert_eq!((old.resolve(ours), new.resolve(theirs)), (s, s));
+    }
+    let added: HashSet<&str> = second
+        .iter()
+        .copied()
+        .filter(|w| old.lookup(w).is_none())
+        .collect();
+    assert_eq!(diff.only_in_other.len(), added.len());
+    assert!(diff.only_in_other.windows(2).all(|w| w[0].0 < w[1].0));
+    for &(s, sym) in &diff.only_in_other {
+        assert!(added.contains(s));
+        assert_eq!(new.resolve(sym), s);
+    }
+
+    let reverse = new.diff(&old);
+    assert_eq!(reverse.only_in_self, diff.only_in_other);
+    assert_eq!(reverse.changed.len(), diff.changed.len());
+
+    // a table with more shards packs the symbols differently
+    let wide: SymbolTable<16> = first.iter().copied().collect();
+    assert!(old.diff(&wide).changed.len() > old.symbols().count() / 2);
+}
+
+#[test]
+fn test_rollback() {
+    let (first, second) = TEXT.split_at(TEXT.len() / 2);
+    let mut interner: SymbolTable<16, DeterministicHashBuilder, backend::BufferBackend> =
+        SymbolTable::default().with_max_entries(1 << 20);
+    let syms: Vec<Symbol> = first
+        .split_whitespace()
+        .map(|w| interner.intern(w))
+        .collect();
+    let cp = interner.checkpoint();
+    for word in second.split_whitespace() {
+        interner.intern(word);
+    }
+
+    interner.rollback_to(&cp);
+    assert_eq!(interner.checkpoint(), cp);
+    for (word, sym) in first.split_whitespace().zip(&syms) {
+        assert_eq!(interner.resolve(*sym), word);
+    }
+    for (word, sym) in first.split_whitespace().zip(syms) {
+        assert_eq!(interner.intern(word), sym);
+    }
+    assert_eq!(interner.checkpoint(), cp);
+
+    // the second half can be interned again
+    for word in second.split_whitespace() {
+        let sym = interner.intern(word);
+        assert_eq!(interner.resolve(sym), word);
+    }
+}
+
 #[test]
-fn test_resolve() {
+fn test_save_load() {
+    let interner: SymbolTable<8> = SymbolTable::default().with_inline_strings();
+    let syms: Vec<Symbol> = TEXT
+        .split_whitespace()
+        .map(|w| interner.intern(w))
+        .collect();
+    let mut bytes = Vec::new();
+    interner.save_to(&mut bytes).unwrap();
+
+    let loaded: SymbolTable<8> = SymbolTable::load_from(&bytes[..]).unwrap();
+    assert!(interner.same_symbols(&loaded));
+    for (word, sym) in TEXT.split_whitespace().zip(syms) {
+        assert_eq!(loaded.resolve(sym), word);
+        assert_eq!(loaded.intern(word), sym);
+    }
+
+    let err = SymbolTable::<4>::load_from(&bytes[..]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    let err = SymbolTable64::<8>::load_from(&bytes[..]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    let err = SymbolTable::<8>::load_from(&bytes[..bytes.len() - 1]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    let err = SymbolTable::<8>::load_from(&b"not a table"[..]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_with_shards() {
+    let interner: SymbolTable<64> = SymbolTable::default().with_shards(4);
+    assert_eq!(interner.n_shards(), 4);
+    let syms = interner.intern_many(TEXT.split_whitespace());
+    assert!(syms.iter().all(|&sym| interner.shard_of(sym) < 4));
+    assert_eq!(interner.stats().shard_lens[4..], [0; 60]);
+    for (word, sym) in TEXT.split_whitespace().zip(&syms) {
+        assert_eq!(interner.resolve(*sym), word);
+    }
+
+    let mut bytes = Vec::new();
+    interner.save_to(&mut bytes).unwrap();
+    let loaded: SymbolTable<64> = SymbolTable::load_from(&bytes[..]).unwrap();
+    assert_eq!(loaded.n_shards(), 4);
+    assert!(interner.same_symbols(&loaded));
+    assert_eq!(
+        loaded.intern("not in gulliver"),
+        interner.intern("not in gulliver")
+    );
+
+    let all: SymbolTable<64> = SymbolTable::default().with_available_parallelism();
+    assert!(all.n_shards().is_power_of_two() && all.n_shards() <= 64);
+    assert_eq!(
+        SymbolTable::new().with_available_parallelism().n_shards(),
+        {
+            let n = std::thread::available_parallelism().unwrap().get();
+            n.next_power_of_two().min(16)
+        }
+    );
+}
+
+#[test]
+#[should_panic = "Can't change the number of shards of a table with strings"]
+fn test_with_shards_after_interning() {
     let interner = SymbolTable::new();
-    let mut resolved = Vec::new();
-    let mut map = HashMap::new();
+    interner.intern("foo");
+    let _ = interner.with_shards(2);
+}
 
-    for word in TEXT.split_whitespace().chain(TEXT.split_whitespace().rev()) {
-        let sym = interner.intern(word);
-        match map.entry(word) {
-            Entry::Occupied(e) => assert_eq!(*e.get(), sym),
-            Entry::Vacant(e) => {
-                e.insert(sym);
-            }
+#[test]
+fn test_into_from_vec() {
+    let interner: SymbolTable<8> = TEXT.split_whitespace().collect();
+    let syms: Vec<Symbol> = interner.symbols().collect();
+    let strings = interner.clone().into_vec();
+    assert_eq!(strings, interner.clone().into_vec());
+    assert_eq!(strings.len(), syms.len());
+
+    let (rebuilt, rebuilt_syms) = SymbolTable::<8>::from_vec(strings);
+    assert!(interner.same_symbols(&rebuilt));
+    assert_eq!(rebuilt_syms, syms);
+    for word in TEXT.split_whitespace() {
+        assert_eq!(rebuilt.intern(word), interner.intern(word));
+    }
+}
+
+#[test]
+fn test_into_from_vec_settings() {
+    // the rebuilt table uses all its shards, so its symbols differ
+    let interner: SymbolTable<8> = SymbolTable::default().with_shards(3);
+    let syms = interner.intern_many(TEXT.split_whitespace());
+    let (rebuilt, _) = SymbolTable::<8>::from_vec(interner.clone().into_vec());
+    assert_eq!(rebuilt.n_shards(), 8);
+    assert!(!interner.same_symbols(&rebuilt));
+    assert!(syms
+        .iter()
+        .any(|&sym| rebuilt.intern(interner.resolve(sym)) != sym));
+
+    // and doesn't have inline strings, which aren't in the vector
+    let interner: SymbolTable<8> = SymbolTable::default().with_inline_strings();
+    let a = interner.intern("a");
+    let strings = interner.clone().into_vec();
+    assert!(strings.is_empty());
+    let (rebuilt, _) = SymbolTable::<8>::from_vec(strings);
+    assert!(!rebuilt.contains_symbol(a));
+    assert_ne!(rebuilt.intern("a"), a);
+}
+
+#[test]
+fn test_symbol_stream() {
+    let sender: SymbolTable<8> = SymbolTable::default().with_inline_strings();
+    let receiver: SymbolTable64 = SymbolTable64::default();
+    let mut encoder = SymbolEncoder::new();
+    let mut decoder = SymbolDecoder::new();
+
+    // several messages over one stream
+    let words: Vec<&str> = TEXT.split_whitespace().collect();
+    for chunk in words.chunks(1000) {
+        let syms = chunk.iter().map(|w| sender.intern(w));
+        let mut message = Vec::new();
+        encoder.encode_all(&sender, syms, &mut message).unwrap();
+        let decoded = decoder.decode_all(&receiver, &message[..]).unwrap();
+        let strings: Vec<&str> = decoded.iter().map(|&sym| receiver.resolve(sym)).collect();
+        assert_eq!(strings, chunk);
+    }
+    assert_eq!(encoder.len(), decoder.len());
+    assert_eq!(decoder.len(), receiver.symbols().count());
+
+    let mut message = Vec::new();
+    encoder
+        .encode(&sender, sender.intern("<new>"), &mut message)
+        .unwrap();
+    message.pop();
+    let err = decoder.clone().decode(&receiver, &message[..]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    let err = SymbolDecoder::new()
+        .decode(&receiver, &[0x05][..])
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[cfg(feature = "lasso")]
+#[test]
+fn test_compat_lasso() {
+    use lasso::{Interner, Reader, Resolver, Rodeo};
+    use symbol_table::compat::FromLassoKey;
+
+    fn intern_words<K>(mut interner: impl Interner<K>) -> Vec<K> {
+        TEXT.split_whitespace()
+            .map(|w| interner.get_or_intern(w))
+            .collect()
+    }
+
+    let mut rodeo = Rodeo::default();
+    let keys = intern_words(&mut rodeo);
+    let table = SymbolTable::new();
+    let syms = intern_words(&table);
+    assert_eq!(Resolver::len(&table), rodeo.len());
+    for (&key, &sym) in keys.iter().zip(&syms) {
+        assert_eq!(table.intern_lasso_key(&rodeo, key), Some(sym));
+        assert_eq!(Resolver::resolve(&table, &sym), rodeo.resolve(&key));
+        assert_eq!(table.get(rodeo.resolve(&key)), Some(sym));
+    }
+    assert!(!table.contains("<missing>"));
+    let bogus = Symbol::try_from_u32(u32::MAX >> 1).unwrap();
+    assert_eq!(table.try_resolve(&bogus), None);
+    assert_eq!(Rodeo::default().try_resolve(&keys[0]), None);
+    assert_eq!(table.intern_lasso_key(&Rodeo::default(), keys[0]), None);
+}
+
+#[cfg(feature = "string-interner")]
+#[test]
+fn test_compat_string_interner() {
+    use string_interner::DefaultStringInterner;
+    use symbol_table::compat::FromInternerSymbol;
+
+    let mut interner = DefaultStringInterner::default();
+    let table = SymbolTable::new();
+    for word in TEXT.split_whitespace() {
+        let sym = interner.get_or_intern(word);
+        assert_eq!(
+            table.intern_interner_symbol(&interner, sym),
+            Some(table.intern(word))
+        );
+    }
+    let sym = interner.get_or_intern("<new>");
+    let other = DefaultStringInterner::default();
+    assert_eq!(table.intern_interner_symbol(&other, sym), None);
+}
+
+#[test]
+fn test_frozen() {
+    let interner: SymbolTable<8> = SymbolTable::default().with_inline_strings();
+    let syms: Vec<Symbol> = TEXT
+        .split_whitespace()
+        .map(|w| interner.intern(w))
+        .collect();
+    let mut bytes = Vec::new();
+    interner.save_frozen(&mut bytes).unwrap();
+
+    let frozen = FrozenSymbolTable::from_bytes(&bytes).unwrap();
+    let stored: std::collections::HashSet<&str> = TEXT
+        .split_whitespace()
+        .filter(|w| w.len() > 2 || !w.is_ascii())
+        .collect();
+    assert_eq!(frozen.len(), stored.len());
+    for (word, sym) in TEXT.split_whitespace().zip(syms) {
+        assert_eq!(frozen.resolve(sym), Some(word));
+    }
+    let missing = interner.intern("not in gulliver");
+    assert_eq!(frozen.resolve(missing), None);
+    assert_eq!(frozen.resolve(Symbol::EMPTY), Some(""));
+    // no string encodes to this inline payload
+    let bad = Symbol::try_from_u32(1 << 31 | 3 << 14).unwrap();
+    assert_eq!(frozen.resolve(bad), None);
+    assert!(FrozenSymbolTable::<Symbol64>::from_bytes(&bytes).is_err());
+
+    // only tables with inline strings have inline symbols
+    let plain = SymbolTable::<8>::default();
+    let mut plain_bytes = Vec::new();
+    plain.save_frozen(&mut plain_bytes).unwrap();
+    let plain_frozen = FrozenSymbolTable::<Symbol>::from_bytes(&plain_bytes).unwrap();
+    assert_eq!(plain_frozen.resolve(Symbol::EMPTY), Some(""));
+    assert_eq!(plain_frozen.resolve(interner.intern("a")), None);
+    assert!(FrozenSymbolTable::<Symbol>::from_bytes(&bytes[..30]).is_err());
+
+    // a truncated table still can't resolve out of bounds
+    let frozen = FrozenSymbolTable::<Symbol>::from_bytes(&bytes[..bytes.len() - 1]).unwrap();
+    let mut resolved = TEXT
+        .split_whitespace()
+        .map(|w| frozen.resolve(interner.intern(w)));
+    assert!(resolved.any(|s| s.is_none()));
+}
+
+#[test]
+fn test_log() {
+    #[derive(Clone, Default)]
+    struct SharedLog(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for SharedLog {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
         }
-        resolved.push(interner.resolve(sym));
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let log = SharedLog::default();
+    let interner: SymbolTable<8> = SymbolTable::default().with_log(log.clone());
+    let words: Vec<&str> = TEXT.split_whitespace().collect();
+    let syms: Vec<Symbol> = std::thread::scope(|s| {
+        let threads: Vec<_> = words
+            .chunks(words.len() / 4 + 1)
+            .map(|chunk| s.spawn(|| chunk.iter().map(|w| interner.intern(w)).collect::<Vec<_>>()))
+            .collect();
+        threads
+            .into_iter()
+            .flat_map(|t| t.join().unwrap())
+            .collect()
+    });
+
+    let bytes = log.0.lock().unwrap().clone();
+    let replayed: SymbolTable<8> = SymbolTable::default();
+    assert_eq!(replayed.replay_log(&bytes[..]).unwrap(), bytes.len() as u64);
+    assert!(interner.same_symbols(&replayed));
+    for (word, sym) in words.iter().zip(&syms) {
+        assert_eq!(replayed.resolve(*sym), *word);
+    }
+
+    // a partially written string is skipped
+    let partial: SymbolTable<8> = SymbolTable::default();
+    let valid = partial.replay_log(&bytes[..bytes.len() - 1]).unwrap();
+    assert!(valid < bytes.len() as u64);
+    assert!(!interner.same_symbols(&partial));
+}
+
+#[test]
+#[should_panic = "Can't roll back a table with a log"]
+fn test_rollback_with_log() {
+    let mut interner = SymbolTable::new().with_log(Vec::new());
+    let cp = interner.checkpoint();
+    interner.intern("foo");
+    interner.rollback_to(&cp);
+}
+
+#[test]
+#[should_panic = "Can't compact a table with a log"]
+fn test_compact_with_log() {
+    let mut interner = SymbolTable::new().with_log(Vec::new());
+    interner.intern("foo");
+    interner.compact(|_, _| false);
+}
+
+#[test]
+#[cfg(feature = "allocator-api2")]
+fn test_alloc_backend() {
+    use allocator_api2::alloc::{AllocError, Allocator, Global, Layout};
+    use std::{
+        ptr::NonNull,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Default)]
+    struct Counting;
+
+    unsafe impl Allocator for Counting {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+            Global.allocate(layout)
+        }
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    let interner: SymbolTable<4, DeterministicHashBuilder, backend::AllocBackend<Counting>> =
+        SymbolTable::default();
+    let syms: Vec<Symbol> = TEXT
+        .split_whitespace()
+        .map(|w| interner.intern(w))
+        .collect();
+    for (word, sym) in TEXT.split_whitespace().zip(&syms) {
+        assert_eq!(interner.resolve(*sym), word);
+    }
+    assert!(ALLOCATED.load(Ordering::Relaxed) > 0);
+    drop(interner);
+    assert_eq!(ALLOCATED.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn test_shared() {
+    let mut memory = vec![0u64; 1 << 16];
+    let (ptr, len) = (memory.as_mut_ptr().cast(), memory.len() * 8);
+    let table = unsafe { SharedSymbolTable::init(ptr, len, 5000) }.unwrap();
+    let other = unsafe { SharedSymbolTable::attach(ptr, len) }.unwrap();
+
+    let words: Vec<&str> = TEXT.split_whitespace().take(5000).collect();
+    let syms: Vec<Symbol> = std::thread::scope(|s| {
+        let threads: Vec<_> = words
+            .chunks(words.len() / 4 + 1)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let table = if i % 2 == 0 { &table } else { &other };
+                s.spawn(move || chunk.iter().map(|w| table.intern(w)).collect::<Vec<_>>())
+            })
+            .collect();
+        threads
+            .into_iter()
+            .flat_map(|t| t.join().unwrap())
+            .collect()
+    });
+    for (word, sym) in words.iter().zip(&syms) {
+        assert_eq!(table.resolve(*sym), *word);
+        assert_eq!(other.intern(word), *sym);
+    }
+    let len = table.len();
+    assert_eq!(table.intern(""), Symbol::EMPTY);
+    assert_eq!(other.resolve(Symbol::EMPTY), "");
+    assert_eq!(table.len(), len);
+
+    let small = unsafe { SharedSymbolTable::init(ptr, 1024, 4) }.unwrap();
+    for s in ["a", "b", "c", "d"] {
+        small.intern(s);
+    }
+    assert_eq!(small.try_intern("a").unwrap(), small.intern("a"));
+    assert_eq!(
+        small.try_intern("e"),
+        Err(InternError::TooManyEntries { max: 4 })
+    );
+    let long = "x".repeat(2000);
+    let fresh = unsafe { SharedSymbolTable::init(ptr, 1024, 4) }.unwrap();
+    for _ in 0..4 {
+        assert!(matches!(
+            fresh.try_intern(&long),
+            Err(InternError::OutOfSpace { .. })
+        ));
+    }
+    // the failed strings used up neither entries nor space
+    assert_eq!(fresh.len(), 0);
+    let a = fresh.intern("a");
+    assert_eq!(fresh.resolve(a), "a");
+    assert!(unsafe { SharedSymbolTable::attach(ptr, 16) }.is_err());
+}
+
+#[test]
+fn test_leak() {
+    let interner = SymbolTable::new().leak();
+    let resolved: Vec<&'static str> = TEXT
+        .split_whitespace()
+        .map(|word| interner.resolve(interner.intern(word)))
+        .collect();
+    assert!(resolved.into_iter().eq(TEXT.split_whitespace()));
+}
+
+#[test]
+fn test_borrowed() {
+    let interner = BorrowedSymbolTable::new();
+    let mut map = HashMap::new();
+    for word in TEXT.split_whitespace() {
+        let sym = interner.intern(word);
+        let first = *map.entry(sym).or_insert(word);
         assert_eq!(interner.resolve(sym), word);
+        assert_eq!(interner.resolve(sym).as_ptr(), first.as_ptr());
+    }
+}
+
+#[test]
+fn test_bytes() {
+    let interner = ByteSymbolTable::new();
+    let strings: [&[u8]; 5] = [
+        b"",
+        b"asdf",
+        b"\xff",
+        "🧵".as_bytes(),
+        &"🧵".as_bytes()[1..],
+    ];
+    let syms: Vec<Symbol> = strings.iter().map(|s| interner.intern_bytes(s)).collect();
+    for (bytes, sym) in strings.iter().zip(syms) {
+        assert_eq!(interner.intern_bytes_owned(bytes.to_vec()), sym);
+        assert_eq!(interner.resolve_bytes(sym), *bytes);
     }
 }
 
+#[cfg(unix)]
+#[test]
+fn test_os() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::Path};
+
+    let interner = OsSymbolTable::new();
+    let not_utf8 = OsStr::from_bytes(b"caf\xe9.txt");
+    let sym = interner.intern_os(not_utf8);
+    assert_eq!(interner.intern_path(Path::new(not_utf8)), sym);
+    assert_eq!(interner.resolve_os(sym), not_utf8);
+}
+
 #[cfg(feature = "global")]
 #[test]
-fn test_global() {
+fn test_path_symbol() {
+    use std::path::{Path, PathBuf};
+
+    let sym = PathSymbol::new("a/b/c");
+    assert_eq!(sym, PathSymbol::from(PathBuf::from("a/b/c")));
+    assert_eq!(sym.as_path(), Path::new("a/b/c"));
+    assert_eq!(Path::new("x").join(sym), Path::new("x/a/b/c"));
+}
+
+#[test]
+fn test_cstr() {
+    let interner = CStrSymbolTable::new();
+    for word in TEXT.split_whitespace() {
+        let sym = interner.intern(word).unwrap();
+        let cstr = interner.resolve_cstr(sym);
+        assert_eq!(cstr.to_bytes(), word.as_bytes());
+        assert_eq!(interner.intern_cstr(cstr), sym);
+        assert_eq!(interner.resolve(sym), Some(word));
+    }
+
+    let err = interner.intern("a\0b").unwrap_err();
+    assert_eq!(err.nul_position(), 1);
+    let not_utf8 = interner.intern_cstr(c"\xff");
+    assert_eq!(interner.resolve(not_utf8), None);
+
+    // the empty string is inline in every table, without a NUL to point to
+    assert_eq!(interner.resolve_cstr(Symbol::EMPTY), c"");
+    assert_eq!(interner.resolve(Symbol::EMPTY), Some(""));
+}
+
+#[test]
+#[should_panic = "inline symbol"]
+fn test_cstr_inline_symbol() {
+    let inline = SymbolTable::new().with_inline_strings().intern("ab");
+    CStrSymbolTable::new().resolve_cstr(inline);
+}
+
+#[test]
+fn test_interner() {
+    let interner = Interner::new();
     let mut map = HashMap::new();
+    let words: Vec<&str> = TEXT.split_whitespace().collect();
 
-    for word in TEXT.split_whitespace().chain(TEXT.split_whitespace().rev()) {
-        let sym = GlobalSymbol::from(word);
-        match map.entry(word) {
-            Entry::Occupied(e) => {
-                assert_eq!(*e.get(), sym);
-            }
-            Entry::Vacant(e) => {
-                e.insert(sym);
+    for pair in words.windows(2) {
+        let value = (pair[0].to_string(), pair[1].len());
+        let id = interner.intern(&value);
+        assert_eq!(*map.entry(value.clone()).or_insert(id), id);
+        assert_eq!(interner.intern_owned(value.clone()), id);
+        assert_eq!(interner.resolve(id), &value);
+    }
+}
+
+#[test]
+fn test_rc() {
+    let interner = RcSymbolTable::new();
+    let syms: Vec<RcSymbol> = TEXT
+        .split_whitespace()
+        .map(|w| interner.intern(w))
+        .collect();
+    let n_words = interner.len();
+    for (word, sym) in TEXT.split_whitespace().zip(&syms) {
+        assert_eq!(sym.as_str(), word);
+        assert_eq!(&interner.intern(word), sym);
+    }
+    assert_eq!(interner.collect(), 0);
+
+    // keep only the words that start with 'a'
+    let kept: Vec<RcSymbol> = syms.into_iter().filter(|s| s.starts_with('a')).collect();
+    let n_kept = kept.iter().collect::<std::collections::HashSet<_>>().len();
+    assert_eq!(interner.collect(), n_words - n_kept);
+    assert_eq!(interner.len(), n_kept);
+    for sym in &kept {
+        assert_eq!(&interner.intern(sym), sym);
+    }
+
+    // resolved strings outlive the table
+    drop(interner);
+    assert!(kept.iter().all(|s| s.starts_with('a')));
+}
+
+#[test]
+fn test_lru() {
+    let table: LruSymbolTable<4> = LruSymbolTable::with_hasher(10, DeterministicHashBuilder);
+    assert_eq!(table.capacity(), 12);
+    let mut syms = HashMap::new();
+    for word in TEXT.split_whitespace() {
+        let sym = table.intern(word);
+        assert_eq!(table.resolve(sym).as_deref(), Some(word));
+        assert!(table.len() <= table.capacity());
+        syms.entry(word).or_insert_with(Vec::new).push(sym);
+    }
+    assert_eq!(table.len(), table.capacity());
+
+    // every symbol resolves to its own string, or to nothing once evicted
+    let mut n_evicted = 0;
+    for (word, syms) in &syms {
+        for &sym in syms {
+            match table.resolve(sym) {
+                Some(s) => assert_eq!(&*s, *word),
+                None => n_evicted += 1,
             }
         }
+    }
+    assert!(n_evicted > 0);
 
-        assert_eq!(sym.to_string(), word);
-        assert_eq!(sym.as_str(), word);
+    // a word that's used over and over is never evicted
+    let hot = table.intern("hot");
+    for word in TEXT.split_whitespace() {
+        table.intern(word);
+        assert_eq!(table.resolve(hot).as_deref(), Some("hot"));
     }
 }
 
 #[test]
-fn test_specific_strings() {
-    let interner = SymbolTable::new();
-    let strings = ["", "asdf", "🧵"];
-    for word in strings {
+fn test_scoped() {
+    let mut table: ScopedSymbolTable<4> = SymbolTable::default().into();
+    let words: Vec<&str> = TEXT.split_whitespace().collect();
+    let (outer, inner) = words.split_at(words.len() / 2);
+    let outer_syms: Vec<Symbol> = outer.iter().map(|w| table.intern(w)).collect();
+    let n_outer = table.stats().len;
+
+    table.push_scope();
+    let inner_syms: Vec<Symbol> = inner.iter().map(|w| table.intern(w)).collect();
+    table.push_scope();
+    let deepest = table.intern("<deepest>");
+    assert_eq!(table.depth(), 2);
+    assert_eq!(table.scope_of(deepest), 2);
+    for (word, &sym) in inner.iter().zip(&inner_syms) {
+        let expected = if outer.contains(word) { 0 } else { 1 };
+        assert_eq!(table.scope_of(sym), expected);
+        assert_eq!(table.resolve(sym), *word);
+    }
+    table.pop_scope();
+    assert_eq!(table.lookup("<deepest>"), None);
+    table.pop_scope();
+
+    assert_eq!(table.depth(), 0);
+    assert_eq!(table.stats().len, n_outer);
+    for (word, &sym) in outer.iter().zip(&outer_syms) {
+        assert_eq!(table.scope_of(sym), 0);
+        assert_eq!(table.resolve(sym), *word);
+        assert_eq!(table.intern(word), sym);
+    }
+}
+
+#[test]
+#[should_panic = "No scope to pop"]
+fn test_scoped_pop_outermost() {
+    ScopedSymbolTable::new().pop_scope();
+}
+
+#[test]
+fn test_local() {
+    let shared: SymbolTable<4> = SymbolTable::default().with_inline_strings();
+    let local: LocalSymbolTable<4> = LocalSymbolTable::default().with_inline_strings();
+    for word in TEXT.split_whitespace() {
+        assert_eq!(local.lookup(word), shared.lookup(word));
+        let sym = local.intern(word);
+        assert_eq!(sym, shared.intern(word));
+        assert_eq!(local.resolve(sym), word);
+    }
+    assert_eq!(local.intern_owned("new".to_string()), shared.intern("new"));
+    assert_eq!(local.len(), shared.stats().len);
+    assert_eq!(
+        local.symbols().collect::<Vec<_>>(),
+        shared.symbols().collect::<Vec<_>>()
+    );
+
+    let local = std::thread::spawn(move || {
+        local.intern("sent");
+        local
+    })
+    .join()
+    .unwrap();
+    assert_eq!(local.lookup("sent"), Some(shared.intern("sent")));
+}
+
+#[test]
+fn test_dense() {
+    let interner = DenseSymbolTable::new();
+    let mut words = Vec::new();
+
+    for word in TEXT.split_whitespace() {
         let sym = interner.intern(word);
+        let index = interner.index_of(sym);
+        if index == words.len() {
+            words.push(word);
+        }
+        assert_eq!(words[index], word);
         assert_eq!(interner.resolve(sym), word);
     }
+
+    assert_eq!(interner.len(), words.len());
+    for (i, word) in words.iter().enumerate() {
+        let sym = interner.symbol_at(i).unwrap();
+        assert_eq!(interner.resolve(sym), *word);
+    }
+    assert_eq!(interner.symbol_at(words.len()), None);
+
+    assert_eq!(interner.intern(""), Symbol::EMPTY);
+    assert_eq!(interner.resolve(Symbol::EMPTY), "");
+    assert_eq!(interner.len(), words.len());
+}
+
+#[test]
+fn test_dense_full() {
+    let interner: DenseSymbolTable<4, DeterministicHashBuilder, backend::StringBackend, Symbol16> =
+        DenseSymbolTable::default();
+    let max = (1 << 15) - 1;
+    for i in 0..max {
+        interner.intern(&i.to_string());
+    }
+    let last = interner.symbol_at(max - 1).unwrap();
+    assert_eq!(last.into_usize(), 0x7fff);
+    assert_eq!(interner.resolve(last), (max - 1).to_string());
+    assert_eq!(interner.index_of(last), max - 1);
+
+    let full = std::panic::catch_unwind(|| interner.intern("one too many"));
+    let err = full.unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<String>().unwrap(),
+        "Can't represent index 32767 in a Symbol"
+    );
+    assert_eq!(interner.len(), max);
+    assert_eq!(interner.intern("0"), interner.symbol_at(0).unwrap());
+}
+
+#[test]
+fn test_intern_traits() {
+    fn intern_words<K>(table: &impl Intern<K>) -> Vec<K> {
+        TEXT.split_whitespace().map(|w| table.intern(w)).collect()
+    }
+
+    fn check_words<K: Copy>(table: &impl Resolve<K>, syms: &[K]) {
+        for (&sym, word) in syms.iter().zip(TEXT.split_whitespace()) {
+            assert_eq!(table.resolve(sym), word);
+        }
+    }
+
+    let table: SymbolTable<4> = SymbolTable::default();
+    let syms = intern_words(&table);
+    check_words(&table, &syms);
+    check_words(&&table, &syms);
+    let mut bytes = Vec::new();
+    table.save_frozen(&mut bytes).unwrap();
+    check_words(&FrozenSymbolTable::from_bytes(&bytes).unwrap(), &syms);
+
+    let local: LocalSymbolTable<4> = LocalSymbolTable::default();
+    assert_eq!(intern_words(&local), syms);
+    let scoped = ScopedSymbolTable::from_table(table);
+    assert_eq!(intern_words(&scoped), syms);
+    check_words(&scoped, &syms);
+
+    let dense = DenseSymbolTable::new();
+    let syms = intern_words(&dense);
+    check_words(&dense, &syms);
+    let dyn_table: &dyn Resolve = &dense;
+    check_words(&dyn_table, &syms);
 }
 
 #[cfg(feature = "global")]
@@ -59,8 +2506,8 @@ fn test_specific_strings() {
 fn test_serde_serialization_deserialization() {
     let sym = GlobalSymbol::from("foo");
 
-    fn ser(_: impl serde::Serialize) {}
-    fn de<'a>(_: impl serde::Deserialize<'a>) {}
+    fn ser(_: impl ::serde::Serialize) {}
+    fn de<'a>(_: impl ::serde::Deserialize<'a>) {}
 
     ser(sym);
     de(sym);
@@ -81,7 +2528,7 @@ fn test_serde_file() {
     std::io::Seek::seek(&mut file, std::io::SeekFrom::Start(0)).expect("Failed to seek");
 
     // deserialize the symbol back out from the file
-    let deserialized = serde_json::from_reader(file).expect("Failed to deserialize");
+    let deserialized: GlobalSymbol = serde_json::from_reader(file).expect("Failed to deserialize");
 
     assert_eq!(test, deserialized);
 }
@@ -93,7 +2540,296 @@ fn test_serde_string() {
     let test: GlobalSymbol = GlobalSymbol::from("foo");
 
     let ser = serde_json::to_string(&test).expect("Failed to serialize");
-    let de = serde_json::from_str(&ser).expect("Failed to deserialize");
+    let de: GlobalSymbol = serde_json::from_str(&ser).expect("Failed to deserialize");
 
     assert_eq!(test, de);
 }
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_table() {
+    let interner: SymbolTable<8> = SymbolTable::default().with_inline_strings();
+    let syms: Vec<Symbol> = TEXT
+        .split_whitespace()
+        .map(|w| interner.intern(w))
+        .collect();
+
+    let json = serde_json::to_string(&(&interner, &syms)).expect("Failed to serialize");
+    let (de, de_syms): (SymbolTable<8>, Vec<Symbol>) =
+        serde_json::from_str(&json).expect("Failed to deserialize");
+    assert_eq!(syms, de_syms);
+    assert!(interner.same_symbols(&de));
+    for (word, sym) in TEXT.split_whitespace().zip(syms) {
+        assert_eq!(de.resolve(sym), word);
+        assert_eq!(de.intern(word), sym);
+    }
+
+    // the strings have to be in the shards they hash to
+    assert!(serde_json::from_str::<SymbolTable<4>>(&json).is_err());
+    let json = r#"{"inline":false,"n_shards":2,"shards":[["foo"],[]]}"#;
+    let ok = serde_json::from_str::<SymbolTable<2>>(json).is_ok();
+    assert!(ok);
+    // strings in other shards were pinned there
+    let swapped = r#"{"inline":false,"n_shards":2,"shards":[[],["foo"]]}"#;
+    let swapped: SymbolTable<2> = serde_json::from_str(swapped).unwrap();
+    assert_eq!(swapped.shard_of(swapped.intern("foo")), 1);
+    let unused = r#"{"inline":false,"n_shards":1,"shards":[[],["foo"]]}"#;
+    assert!(serde_json::from_str::<SymbolTable<2>>(unused).is_err());
+    let twice = r#"{"inline":false,"n_shards":2,"shards":[["foo","foo"],["foo","foo"]]}"#;
+    assert!(serde_json::from_str::<SymbolTable<2>>(twice).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_table_shards() {
+    let table: SymbolTable<8> = SymbolTable::default().with_shards(3);
+    let syms = table.intern_many(TEXT.split_whitespace().take(100));
+    let json = serde_json::to_string(&table).unwrap();
+    let de: SymbolTable<8> = serde_json::from_str(&json).unwrap();
+    assert_eq!(de.n_shards(), 3);
+    assert!(table.same_symbols(&de));
+    assert_eq!(de.resolve(syms[10]), table.resolve(syms[10]));
+    assert!(serde_json::from_str::<SymbolTable<2>>(&json).is_err());
+
+    // the number of shards in use is required
+    let json = json.replace(r#""n_shards":3,"#, "");
+    assert!(serde_json::from_str::<SymbolTable<8>>(&json).is_err());
+}
+
+#[cfg(feature = "borsh")]
+#[test]
+fn test_borsh_table() {
+    let table: SymbolTable<8> = SymbolTable::default().with_shards(3).with_inline_strings();
+    let syms = table.intern_many(TEXT.split_whitespace());
+    let home = table.hash_str("not in gulliver") as usize % 3;
+    let pinned = table.intern_in_shard((home + 1) % 3, "not in gulliver");
+
+    let bytes = borsh::to_vec(&(&table, &syms)).unwrap();
+    let (de, de_syms): (SymbolTable<8>, Vec<Symbol>) = borsh::from_slice(&bytes).unwrap();
+    assert_eq!(syms, de_syms);
+    assert_eq!(de.n_shards(), 3);
+    assert!(table.same_symbols(&de));
+    assert_eq!(de.intern("not in gulliver"), pinned);
+    for (word, sym) in TEXT.split_whitespace().zip(syms) {
+        assert_eq!(de.resolve(sym), word);
+        assert_eq!(de.intern(word), sym);
+    }
+
+    assert!(borsh::from_slice::<SymbolTable<4>>(&bytes).is_err());
+    assert!(borsh::from_slice::<SymbolTable<8>>(&bytes[..bytes.len() / 2]).is_err());
+    let twice = borsh::to_vec(&(false, 1u32, 1u32, vec!["foo", "foo"])).unwrap();
+    assert!(borsh::from_slice::<SymbolTable<1>>(&twice).is_err());
+
+    let sym = Symbol16::try_from_usize(7).unwrap();
+    assert_eq!(borsh::to_vec(&sym).unwrap(), [7, 0]);
+    assert_eq!(borsh::from_slice::<Symbol16>(&[7, 0]).unwrap(), sym);
+    assert!(borsh::from_slice::<Symbol16>(&[0, 0]).is_err());
+}
+
+#[cfg(feature = "global")]
+#[cfg(feature = "borsh")]
+#[test]
+fn test_borsh_global() {
+    let foo = GlobalSymbol::from("foo");
+    let bytes = borsh::to_vec(&foo).unwrap();
+    assert_eq!(bytes, borsh::to_vec("foo").unwrap());
+    assert_eq!(borsh::from_slice::<GlobalSymbol>(&bytes).unwrap(), foo);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_seed() {
+    use ::serde::de::DeserializeSeed;
+    use symbol_table::serde::{SymbolSeed, SymbolSerializer};
+
+    let interner = SymbolTable::new();
+    let syms: Vec<Symbol> = TEXT
+        .split_whitespace()
+        .map(|w| interner.intern(w))
+        .collect();
+    let strs: Vec<_> = syms
+        .iter()
+        .map(|&sym| SymbolSerializer::new(&interner, sym))
+        .collect();
+    let json = serde_json::to_string(&strs).expect("Failed to serialize");
+    let words: Vec<&str> = TEXT.split_whitespace().collect();
+    assert_eq!(json, serde_json::to_string(&words).unwrap());
+
+    let other = SymbolTable::new();
+    let seed = SymbolSeed::new(&other);
+    let values: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap();
+    for (value, word) in values.into_iter().zip(words) {
+        let sym = seed.deserialize(value).expect("Failed to deserialize");
+        assert_eq!(other.resolve(sym), word);
+    }
+
+    let limited = SymbolTable::new().with_max_str_len(2);
+    let value = serde_json::Value::from("too long");
+    assert!(SymbolSeed::new(&limited).deserialize(value).is_err());
+}
+
+#[cfg(feature = "global")]
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_dict() {
+    #[derive(::serde::Serialize, ::serde::Deserialize)]
+    struct Words {
+        #[serde(with = "symbol_table::serde::dict")]
+        words: Vec<GlobalSymbol>,
+        #[serde(with = "symbol_table::serde::dict")]
+        set: std::collections::BTreeSet<GlobalSymbol>,
+    }
+
+    let words: Vec<GlobalSymbol> = TEXT.split_whitespace().map(GlobalSymbol::from).collect();
+    let set = words.iter().copied().collect();
+    let json = serde_json::to_string(&Words { words, set }).expect("Failed to serialize");
+
+    let de: Words = serde_json::from_str(&json).expect("Failed to deserialize");
+    assert!(de
+        .words
+        .iter()
+        .map(|w| w.as_str())
+        .eq(TEXT.split_whitespace()));
+    assert!(de.words.iter().all(|w| de.set.contains(w)));
+
+    let bad = r#"{"strings":["a"],"symbols":[0,1]}"#;
+    let mut de = serde_json::Deserializer::from_str(bad);
+    assert!(symbol_table::serde::dict::deserialize::<Vec<GlobalSymbol>, _>(&mut de).is_err());
+}
+
+#[cfg(feature = "global")]
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_registry() {
+    use symbol_table::serde::registry::SymbolRegistry;
+
+    let words: Vec<GlobalSymbol> = TEXT.split_whitespace().map(GlobalSymbol::from).collect();
+    let plain = bincode::serialize(&words).unwrap();
+    let mut registry = SymbolRegistry::new();
+    let compact = registry.scope(|| bincode::serialize(&words)).unwrap();
+    assert!(compact.len() < plain.len() / 2);
+    let distinct: HashSet<GlobalSymbol> = words.iter().copied().collect();
+    assert_eq!(registry.len(), distinct.len());
+
+    // human-readable formats ignore the registry
+    let json = registry
+        .scope(|| serde_json::to_string(&words[..3]))
+        .unwrap();
+    assert_eq!(json, serde_json::to_string(&words[..3]).unwrap());
+    let de: Vec<GlobalSymbol> = registry.scope(|| serde_json::from_str(&json)).unwrap();
+    assert_eq!(de, words[..3]);
+
+    let registry_bytes = bincode::serialize(&registry).unwrap();
+    let mut registry: SymbolRegistry = bincode::deserialize(&registry_bytes).unwrap();
+    let de: Vec<GlobalSymbol> = registry.scope(|| bincode::deserialize(&compact)).unwrap();
+    assert_eq!(de, words);
+    // out of scope, the data is read as strings again
+    assert!(bincode::deserialize::<Vec<GlobalSymbol>>(&compact).is_err());
+    let de: Vec<GlobalSymbol> = bincode::deserialize(&plain).unwrap();
+    assert_eq!(de, words);
+
+    let mut empty = SymbolRegistry::new();
+    assert!(empty
+        .scope(|| bincode::deserialize::<Vec<GlobalSymbol>>(&compact))
+        .is_err());
+}
+
+#[cfg(feature = "global")]
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_visitor() {
+    use ::serde::de::{value::*, Deserialize};
+
+    let foo = GlobalSymbol::from("foo");
+    let de = StringDeserializer::<Error>::new("foo".to_string());
+    assert_eq!(GlobalSymbol::deserialize(de), Ok(foo));
+    let de = BorrowedStrDeserializer::<Error>::new("foo");
+    assert_eq!(GlobalSymbol::deserialize(de), Ok(foo));
+    let de = BytesDeserializer::<Error>::new(b"foo");
+    assert_eq!(GlobalSymbol::deserialize(de), Ok(foo));
+    let de = BytesDeserializer::<Error>::new(b"\xff");
+    assert!(GlobalSymbol::deserialize(de).is_err());
+}
+
+#[test]
+#[cfg(feature = "ffi")]
+fn test_ffi() {
+    use symbol_table::ffi::*;
+
+    let sym = unsafe { symbol_intern(c"hello".as_ptr()) };
+    assert_eq!(
+        GlobalSymbol::from(std::num::NonZeroU32::new(sym).unwrap()),
+        GlobalSymbol::from("hello")
+    );
+    assert_eq!(
+        unsafe { symbol_intern_len(b"hello!".as_ptr().cast(), 5) },
+        sym
+    );
+    let resolved = unsafe { std::ffi::CStr::from_ptr(symbol_resolve(sym)) };
+    assert_eq!(resolved, c"hello");
+
+    let mut len = 0;
+    let ptr = unsafe { symbol_resolve_len(sym, &mut len) };
+    assert_eq!(
+        unsafe { std::slice::from_raw_parts(ptr.cast::<u8>(), len) },
+        b"hello"
+    );
+
+    let nul = unsafe { symbol_intern_len(b"a\0b".as_ptr().cast(), 3) };
+    assert!(symbol_resolve(nul).is_null());
+    assert_eq!(unsafe { symbol_intern_len(b"\xff".as_ptr().cast(), 1) }, 0);
+    assert_eq!(unsafe { symbol_intern(std::ptr::null()) }, 0);
+    assert!(symbol_resolve(0).is_null());
+
+    // symbols that aren't in the table don't panic into C
+    // the last index of the first shard
+    assert!(symbol_resolve((1 << 27) - 1).is_null());
+    assert!(symbol_resolve(u32::MAX).is_null());
+    let mut len = 1;
+    assert!(unsafe { symbol_resolve_len(u32::MAX, &mut len) }.is_null());
+    assert_eq!(len, 0);
+    let empty = unsafe { symbol_intern(c"".as_ptr()) };
+    let resolved = unsafe { std::ffi::CStr::from_ptr(symbol_resolve(empty)) };
+    assert_eq!(resolved, c"");
+}
+
+/// The C declarations in `include/symbol_table.h` match the `extern "C"`
+/// functions in `src/ffi.rs`.
+#[test]
+#[cfg(feature = "ffi")]
+fn test_ffi_header() {
+    // a C type, followed by a space unless it's a pointer
+    fn c_type(rust: &str) -> &'static str {
+        match rust.trim() {
+            "u32" => "uint32_t ",
+            "usize" => "size_t ",
+            "*const c_char" => "const char *",
+            "*mut usize" => "size_t *",
+            other => panic!("no C type for {other}"),
+        }
+    }
+
+    let rust = std::fs::read_to_string("src/ffi.rs").unwrap();
+    let mut expected: Vec<String> = rust
+        .lines()
+        .filter_map(|line| line.split_once("extern \"C\" fn "))
+        .map(|(_, decl)| {
+            let (name, rest) = decl.split_once('(').unwrap();
+            let (params, ret) = rest.split_once(") -> ").unwrap();
+            let params: Vec<String> = params
+                .split(", ")
+                .map(|param| {
+                    let (param, ty) = param.split_once(": ").unwrap();
+                    format!("{}{param}", c_type(ty))
+                })
+                .collect();
+            let ret = c_type(ret.trim_end_matches(" {"));
+            format!("{ret}{name}({});", params.join(", "))
+        })
+        .collect();
+    let header = std::fs::read_to_string("include/symbol_table.h").unwrap();
+    let mut declared: Vec<&str> = header.lines().filter(|line| line.ends_with(");")).collect();
+    expected.sort();
+    declared.sort();
+    assert_eq!(declared, expected);
+}